@@ -0,0 +1,42 @@
+#![no_main]
+
+use clmm_demo::error::ClmmError;
+use clmm_demo::libraries::{mul_div, mul_div_round_up, U512};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: (u128, u128, u128)| {
+    let (a, b, denominator) = input;
+
+    if denominator == 0 {
+        assert_eq!(mul_div(a, b, denominator), Err(ClmmError::DivisionByZero));
+        assert_eq!(
+            mul_div_round_up(a, b, denominator),
+            Err(ClmmError::DivisionByZero)
+        );
+        return;
+    }
+
+    let product = U512::from(a) * U512::from(b);
+    let floor = product / U512::from(denominator);
+    let ceil = if (product % U512::from(denominator)).is_zero() {
+        floor
+    } else {
+        floor + 1
+    };
+
+    match mul_div(a, b, denominator) {
+        Ok(result) => assert_eq!(U512::from(result), floor),
+        Err(err) => {
+            assert_eq!(err, ClmmError::MathOverflow);
+            assert!(floor > U512::from(u128::MAX));
+        }
+    }
+
+    match mul_div_round_up(a, b, denominator) {
+        Ok(result) => assert_eq!(U512::from(result), ceil),
+        Err(err) => {
+            assert_eq!(err, ClmmError::MathOverflow);
+            assert!(ceil > U512::from(u128::MAX));
+        }
+    }
+});
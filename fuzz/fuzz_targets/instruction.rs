@@ -0,0 +1,62 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use borsh::BorshDeserialize;
+use clmm_demo::instruction::ClmmInstruction;
+use clmm_demo::processor::process_instruction;
+use libfuzzer_sys::fuzz_target;
+use solana_account_info::AccountInfo;
+use solana_pubkey::Pubkey;
+
+#[derive(Arbitrary, Debug)]
+struct FuzzAccount {
+    key: [u8; 32],
+    owned_by_program: bool,
+    is_signer: bool,
+    is_writable: bool,
+    lamports: u64,
+    data: Vec<u8>,
+}
+
+#[derive(Arbitrary, Debug)]
+struct FuzzInput {
+    instruction_data: Vec<u8>,
+    accounts: Vec<FuzzAccount>,
+}
+
+fuzz_target!(|input: FuzzInput| {
+    // Borsh decoding is canonical: anything it accepts must re-encode to the same bytes
+    if let Ok(instruction) = ClmmInstruction::try_from_slice(&input.instruction_data) {
+        assert_eq!(borsh::to_vec(&instruction).unwrap(), input.instruction_data);
+    }
+
+    let program_id = Pubkey::new_from_array([7; 32]);
+    let other_owner = Pubkey::new_from_array([9; 32]);
+    let mut accounts = input.accounts;
+    let keys: Vec<Pubkey> = accounts
+        .iter()
+        .map(|account| Pubkey::new_from_array(account.key))
+        .collect();
+    let account_infos: Vec<AccountInfo> = accounts
+        .iter_mut()
+        .zip(keys.iter())
+        .map(|(account, key)| {
+            AccountInfo::new(
+                key,
+                account.is_signer,
+                account.is_writable,
+                &mut account.lamports,
+                &mut account.data,
+                if account.owned_by_program {
+                    &program_id
+                } else {
+                    &other_owner
+                },
+                false,
+            )
+        })
+        .collect();
+
+    // Garbage input must be rejected with an error, never a panic
+    let _ = process_instruction(&program_id, &account_infos, &input.instruction_data);
+});
@@ -0,0 +1,47 @@
+#![no_main]
+
+use clmm_demo::libraries::{
+    add_delta, get_delta_amount_0_unsigned, get_delta_amount_1_unsigned,
+    get_liquidity_from_amount_0, get_liquidity_from_amount_1,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: (u128, u128, u64, u128, i128)| {
+    let (sqrt_price_a_x64, sqrt_price_b_x64, amount, liquidity, delta) = input;
+
+    if let Ok(result) = add_delta(liquidity, delta) {
+        assert_eq!(result as i128, (liquidity as i128).wrapping_add(delta));
+    }
+
+    if sqrt_price_a_x64 == 0 || sqrt_price_b_x64 == 0 {
+        return;
+    }
+
+    // Depositing `amount` never mints liquidity that costs more than `amount`
+    if let Ok(liquidity) = get_liquidity_from_amount_0(sqrt_price_a_x64, sqrt_price_b_x64, amount) {
+        if let Ok(charged) =
+            get_delta_amount_0_unsigned(sqrt_price_a_x64, sqrt_price_b_x64, liquidity, true)
+        {
+            assert!(charged <= amount);
+        }
+    }
+    if let Ok(liquidity) = get_liquidity_from_amount_1(sqrt_price_a_x64, sqrt_price_b_x64, amount) {
+        if let Ok(charged) =
+            get_delta_amount_1_unsigned(sqrt_price_a_x64, sqrt_price_b_x64, liquidity, true)
+        {
+            assert!(charged <= amount);
+        }
+    }
+
+    // Rounding up never gives less than rounding down, and by at most one unit
+    for get_delta_amount in [get_delta_amount_0_unsigned, get_delta_amount_1_unsigned] {
+        let down = get_delta_amount(sqrt_price_a_x64, sqrt_price_b_x64, liquidity, false);
+        let up = get_delta_amount(sqrt_price_a_x64, sqrt_price_b_x64, liquidity, true);
+        match (down, up) {
+            (Ok(down), Ok(up)) => assert!(down <= up && up - down <= 1),
+            // Only the rounded-up amount may spill over u64::MAX
+            (Ok(down), Err(_)) => assert_eq!(down, u64::MAX),
+            (Err(_), up) => assert!(up.is_err()),
+        }
+    }
+});
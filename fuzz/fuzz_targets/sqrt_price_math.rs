@@ -0,0 +1,58 @@
+#![no_main]
+
+use clmm_demo::libraries::{
+    get_delta_amount_0_unsigned, get_delta_amount_1_unsigned, get_next_sqrt_price_from_input,
+    get_next_sqrt_price_from_output,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: (u128, u128, u64, bool)| {
+    let (sqrt_price_x64, liquidity, amount, zero_for_one) = input;
+
+    if let Ok(next) =
+        get_next_sqrt_price_from_input(sqrt_price_x64, liquidity, amount, zero_for_one)
+    {
+        // Input moves the price against the swapper, and never by more than
+        // the input can pay for once amounts are rounded in the pool's favor.
+        if zero_for_one {
+            assert!(next <= sqrt_price_x64);
+            if next > 0 {
+                if let Ok(amount_in) =
+                    get_delta_amount_0_unsigned(next, sqrt_price_x64, liquidity, true)
+                {
+                    assert!(amount_in <= amount);
+                }
+            }
+        } else {
+            assert!(next >= sqrt_price_x64);
+            if let Ok(amount_in) =
+                get_delta_amount_1_unsigned(sqrt_price_x64, next, liquidity, true)
+            {
+                assert!(amount_in <= amount);
+            }
+        }
+    }
+
+    if let Ok(next) =
+        get_next_sqrt_price_from_output(sqrt_price_x64, liquidity, amount, zero_for_one)
+    {
+        // The price moves far enough that the pool can pay out at least `amount`.
+        if zero_for_one {
+            assert!(next < sqrt_price_x64 || amount == 0);
+            if next > 0 {
+                if let Ok(amount_out) =
+                    get_delta_amount_1_unsigned(next, sqrt_price_x64, liquidity, false)
+                {
+                    assert!(amount_out >= amount);
+                }
+            }
+        } else {
+            assert!(next > sqrt_price_x64 || amount == 0);
+            if let Ok(amount_out) =
+                get_delta_amount_0_unsigned(sqrt_price_x64, next, liquidity, false)
+            {
+                assert!(amount_out >= amount);
+            }
+        }
+    }
+});
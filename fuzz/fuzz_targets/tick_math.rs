@@ -0,0 +1,36 @@
+#![no_main]
+
+use clmm_demo::libraries::{
+    get_sqrt_price_at_tick, get_tick_at_sqrt_price, MAX_SQRT_PRICE_X64, MAX_TICK,
+    MIN_SQRT_PRICE_X64, MIN_TICK,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: (i32, u128)| {
+    let (tick, sqrt_price_x64) = input;
+
+    match get_sqrt_price_at_tick(tick) {
+        Ok(sqrt_price) => {
+            assert!((MIN_TICK..=MAX_TICK).contains(&tick));
+            assert!((MIN_SQRT_PRICE_X64..=MAX_SQRT_PRICE_X64).contains(&sqrt_price));
+            assert_eq!(get_tick_at_sqrt_price(sqrt_price).unwrap(), tick);
+            if tick < MAX_TICK {
+                assert!(get_sqrt_price_at_tick(tick + 1).unwrap() > sqrt_price);
+            }
+        }
+        Err(_) => assert!(!(MIN_TICK..=MAX_TICK).contains(&tick)),
+    }
+
+    match get_tick_at_sqrt_price(sqrt_price_x64) {
+        Ok(tick) => {
+            // Largest tick whose price does not exceed the input
+            assert!(get_sqrt_price_at_tick(tick).unwrap() <= sqrt_price_x64);
+            if tick < MAX_TICK {
+                assert!(get_sqrt_price_at_tick(tick + 1).unwrap() > sqrt_price_x64);
+            }
+        }
+        Err(_) => {
+            assert!(!(MIN_SQRT_PRICE_X64..=MAX_SQRT_PRICE_X64).contains(&sqrt_price_x64))
+        }
+    }
+});
@@ -0,0 +1,1075 @@
+//! Cross-program invocation helpers for programs composing with this CLMM.
+//!
+//! Depend on the crate with the `no-entrypoint` feature to use them. Every
+//! instruction has a typed accounts struct, listing the accounts in the order its
+//! processor reads them, and a wrapper that builds the instruction and invokes it.
+//! Wrappers take the caller's `signers_seeds`; pass `&[]` for a plain invoke.
+
+use borsh::BorshDeserialize;
+use solana_account_info::AccountInfo;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_program_error::{ProgramError, ProgramResult};
+use solana_pubkey::Pubkey;
+
+use crate::error::ClmmError;
+use crate::instruction::ClmmInstruction;
+use crate::instructions::{Deadline, SqrtPriceBand, SwapQuote};
+use crate::state::{AccountRef, PoolState, PositionState};
+use crate::utils::{get_return_data, invoke_signed};
+
+/// Loads a pool account owned by this program, without keeping the account borrowed.
+pub fn load_pool(info: &AccountInfo) -> Result<Box<PoolState>, ProgramError> {
+    AccountRef::<PoolState>::load(info, &crate::ID).map(AccountRef::into_inner)
+}
+
+/// Loads a position account owned by this program, without keeping the account borrowed.
+pub fn load_position(info: &AccountInfo) -> Result<Box<PositionState>, ProgramError> {
+    AccountRef::<PositionState>::load(info, &crate::ID).map(AccountRef::into_inner)
+}
+
+/// Accounts of one instruction, with the signer and writable flags it expects.
+pub trait CpiAccounts<'info> {
+    fn to_account_metas(&self) -> Vec<(AccountMeta, AccountInfo<'info>)>;
+}
+
+fn readonly<'info>(info: &AccountInfo<'info>) -> (AccountMeta, AccountInfo<'info>) {
+    (AccountMeta::new_readonly(*info.key, false), info.clone())
+}
+
+fn writable<'info>(info: &AccountInfo<'info>) -> (AccountMeta, AccountInfo<'info>) {
+    (AccountMeta::new(*info.key, false), info.clone())
+}
+
+fn signer<'info>(info: &AccountInfo<'info>) -> (AccountMeta, AccountInfo<'info>) {
+    (AccountMeta::new_readonly(*info.key, true), info.clone())
+}
+
+fn signer_writable<'info>(info: &AccountInfo<'info>) -> (AccountMeta, AccountInfo<'info>) {
+    (AccountMeta::new(*info.key, true), info.clone())
+}
+
+fn invoke_clmm<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &impl CpiAccounts<'info>,
+    instruction: &ClmmInstruction,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    if *program.key != crate::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let (metas, mut infos): (Vec<_>, Vec<_>) = accounts.to_account_metas().into_iter().unzip();
+    infos.push(program.clone());
+    let instruction = Instruction {
+        program_id: crate::ID,
+        accounts: metas,
+        // Writing into a Vec cannot fail.
+        data: borsh::to_vec(instruction).unwrap(),
+    };
+    invoke_signed(&instruction, &infos, signers_seeds)
+}
+
+pub struct InitializeConfigAccounts<'a, 'info> {
+    pub admin: &'a AccountInfo<'info>,
+    pub amm_config: &'a AccountInfo<'info>,
+    pub fee_tier_registry: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+impl<'info> CpiAccounts<'info> for InitializeConfigAccounts<'_, 'info> {
+    fn to_account_metas(&self) -> Vec<(AccountMeta, AccountInfo<'info>)> {
+        vec![
+            signer_writable(self.admin),
+            writable(self.amm_config),
+            writable(self.fee_tier_registry),
+            readonly(self.system_program),
+        ]
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_config<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &InitializeConfigAccounts<'_, 'info>,
+    index: u16,
+    trade_fee_rate: u32,
+    protocol_fee_rate: u32,
+    tick_spacing: u16,
+    fund_fee_rate: u32,
+    fund_owner: Pubkey,
+    max_referral_fee_rate: u32,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_clmm(
+        program,
+        accounts,
+        &ClmmInstruction::InitializeConfig {
+            index,
+            trade_fee_rate,
+            protocol_fee_rate,
+            tick_spacing,
+            fund_fee_rate,
+            fund_owner,
+            max_referral_fee_rate,
+        },
+        signers_seeds,
+    )
+}
+
+pub struct CreatePoolAccounts<'a, 'info> {
+    pub creator: &'a AccountInfo<'info>,
+    pub amm_config: &'a AccountInfo<'info>,
+    pub pool: &'a AccountInfo<'info>,
+    pub token_mint_0: &'a AccountInfo<'info>,
+    pub token_mint_1: &'a AccountInfo<'info>,
+    pub token_vault_0: &'a AccountInfo<'info>,
+    pub token_vault_1: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+impl<'info> CpiAccounts<'info> for CreatePoolAccounts<'_, 'info> {
+    fn to_account_metas(&self) -> Vec<(AccountMeta, AccountInfo<'info>)> {
+        vec![
+            signer_writable(self.creator),
+            readonly(self.amm_config),
+            writable(self.pool),
+            readonly(self.token_mint_0),
+            readonly(self.token_mint_1),
+            writable(self.token_vault_0),
+            writable(self.token_vault_1),
+            readonly(self.token_program),
+            readonly(self.system_program),
+        ]
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_pool<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &CreatePoolAccounts<'_, 'info>,
+    sqrt_price_x64: u128,
+    permission_authority: Option<Pubkey>,
+    open_time: u64,
+    launch_duration: u64,
+    launch_max_amount_0: u64,
+    launch_max_amount_1: u64,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_clmm(
+        program,
+        accounts,
+        &ClmmInstruction::CreatePool {
+            sqrt_price_x64,
+            permission_authority,
+            open_time,
+            launch_duration,
+            launch_max_amount_0,
+            launch_max_amount_1,
+        },
+        signers_seeds,
+    )
+}
+
+/// The NFT mint and token account are new accounts and must sign too.
+pub struct OpenPositionAccounts<'a, 'info> {
+    pub payer: &'a AccountInfo<'info>,
+    pub pool: &'a AccountInfo<'info>,
+    pub nft_mint: &'a AccountInfo<'info>,
+    pub nft_account: &'a AccountInfo<'info>,
+    pub position: &'a AccountInfo<'info>,
+    pub tick_array_lower: &'a AccountInfo<'info>,
+    pub tick_array_upper: &'a AccountInfo<'info>,
+    pub token_account_0: &'a AccountInfo<'info>,
+    pub token_account_1: &'a AccountInfo<'info>,
+    pub token_vault_0: &'a AccountInfo<'info>,
+    pub token_vault_1: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    /// Required by permissioned pools
+    pub allowlist_entry: Option<&'a AccountInfo<'info>>,
+}
+
+impl<'info> CpiAccounts<'info> for OpenPositionAccounts<'_, 'info> {
+    fn to_account_metas(&self) -> Vec<(AccountMeta, AccountInfo<'info>)> {
+        let mut accounts = vec![
+            signer_writable(self.payer),
+            writable(self.pool),
+            signer_writable(self.nft_mint),
+            signer_writable(self.nft_account),
+            writable(self.position),
+            writable(self.tick_array_lower),
+            writable(self.tick_array_upper),
+            writable(self.token_account_0),
+            writable(self.token_account_1),
+            writable(self.token_vault_0),
+            writable(self.token_vault_1),
+            readonly(self.token_program),
+            readonly(self.system_program),
+        ];
+        accounts.extend(self.allowlist_entry.map(readonly));
+        accounts
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn open_position<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &OpenPositionAccounts<'_, 'info>,
+    tick_lower: i32,
+    tick_upper: i32,
+    liquidity: u128,
+    amount_0_max: u64,
+    amount_1_max: u64,
+    deadline: Option<Deadline>,
+    sqrt_price_band: Option<SqrtPriceBand>,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_clmm(
+        program,
+        accounts,
+        &ClmmInstruction::OpenPosition {
+            tick_lower,
+            tick_upper,
+            liquidity,
+            amount_0_max,
+            amount_1_max,
+            deadline,
+            sqrt_price_band,
+        },
+        signers_seeds,
+    )
+}
+
+/// Accounts shared by the instructions that change a position's liquidity.
+///
+/// The token accounts pay deposits or receive withdrawals, depending on the instruction.
+pub struct ModifyLiquidityAccounts<'a, 'info> {
+    pub owner: &'a AccountInfo<'info>,
+    pub nft_account: &'a AccountInfo<'info>,
+    pub pool: &'a AccountInfo<'info>,
+    pub position: &'a AccountInfo<'info>,
+    pub tick_array_lower: &'a AccountInfo<'info>,
+    pub tick_array_upper: &'a AccountInfo<'info>,
+    pub token_account_0: &'a AccountInfo<'info>,
+    pub token_account_1: &'a AccountInfo<'info>,
+    pub token_vault_0: &'a AccountInfo<'info>,
+    pub token_vault_1: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    /// Required by permissioned pools when adding liquidity
+    pub allowlist_entry: Option<&'a AccountInfo<'info>>,
+}
+
+impl<'info> CpiAccounts<'info> for ModifyLiquidityAccounts<'_, 'info> {
+    fn to_account_metas(&self) -> Vec<(AccountMeta, AccountInfo<'info>)> {
+        let mut accounts = vec![
+            signer(self.owner),
+            readonly(self.nft_account),
+            writable(self.pool),
+            writable(self.position),
+            writable(self.tick_array_lower),
+            writable(self.tick_array_upper),
+            writable(self.token_account_0),
+            writable(self.token_account_1),
+            writable(self.token_vault_0),
+            writable(self.token_vault_1),
+            readonly(self.token_program),
+        ];
+        accounts.extend(self.allowlist_entry.map(readonly));
+        accounts
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn increase_liquidity<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &ModifyLiquidityAccounts<'_, 'info>,
+    liquidity: u128,
+    amount_0_max: u64,
+    amount_1_max: u64,
+    deadline: Option<Deadline>,
+    sqrt_price_band: Option<SqrtPriceBand>,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_clmm(
+        program,
+        accounts,
+        &ClmmInstruction::IncreaseLiquidity {
+            liquidity,
+            amount_0_max,
+            amount_1_max,
+            deadline,
+            sqrt_price_band,
+        },
+        signers_seeds,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn decrease_liquidity<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &ModifyLiquidityAccounts<'_, 'info>,
+    liquidity: u128,
+    amount_0_min: u64,
+    amount_1_min: u64,
+    deadline: Option<Deadline>,
+    sqrt_price_band: Option<SqrtPriceBand>,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_clmm(
+        program,
+        accounts,
+        &ClmmInstruction::DecreaseLiquidity {
+            liquidity,
+            amount_0_min,
+            amount_1_min,
+            deadline,
+            sqrt_price_band,
+        },
+        signers_seeds,
+    )
+}
+
+/// Fees are paid to the token accounts.
+pub fn collect_fees<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &ModifyLiquidityAccounts<'_, 'info>,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_clmm(
+        program,
+        accounts,
+        &ClmmInstruction::CollectFees,
+        signers_seeds,
+    )
+}
+
+pub struct SwapAccounts<'a, 'info> {
+    pub payer: &'a AccountInfo<'info>,
+    pub amm_config: &'a AccountInfo<'info>,
+    pub pool: &'a AccountInfo<'info>,
+    pub input_token_account: &'a AccountInfo<'info>,
+    pub output_token_account: &'a AccountInfo<'info>,
+    pub input_vault: &'a AccountInfo<'info>,
+    pub output_vault: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    /// Required by permissioned pools
+    pub allowlist_entry: Option<&'a AccountInfo<'info>>,
+    pub referral_token_account: Option<&'a AccountInfo<'info>>,
+    /// Starting with the array holding the current tick, in the swap direction
+    pub tick_arrays: &'a [AccountInfo<'info>],
+}
+
+impl<'info> CpiAccounts<'info> for SwapAccounts<'_, 'info> {
+    fn to_account_metas(&self) -> Vec<(AccountMeta, AccountInfo<'info>)> {
+        let mut accounts = vec![
+            signer(self.payer),
+            readonly(self.amm_config),
+            writable(self.pool),
+            writable(self.input_token_account),
+            writable(self.output_token_account),
+            writable(self.input_vault),
+            writable(self.output_vault),
+            readonly(self.token_program),
+        ];
+        accounts.extend(self.allowlist_entry.map(readonly));
+        accounts.extend(self.referral_token_account.map(writable));
+        accounts.extend(self.tick_arrays.iter().map(writable));
+        accounts
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn swap<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &SwapAccounts<'_, 'info>,
+    amount_in: u64,
+    minimum_amount_out: u64,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+    deadline: Option<Deadline>,
+    referral_fee_rate: u32,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_clmm(
+        program,
+        accounts,
+        &ClmmInstruction::Swap {
+            amount_in,
+            minimum_amount_out,
+            sqrt_price_limit_x64,
+            is_base_input,
+            deadline,
+            referral_fee_rate,
+        },
+        signers_seeds,
+    )
+}
+
+pub struct MigrateAccountAccounts<'a, 'info> {
+    pub admin: &'a AccountInfo<'info>,
+    pub payer: &'a AccountInfo<'info>,
+    pub amm_config: &'a AccountInfo<'info>,
+    pub target: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    /// Required when migrating a position or tick array
+    pub pool: Option<&'a AccountInfo<'info>>,
+}
+
+impl<'info> CpiAccounts<'info> for MigrateAccountAccounts<'_, 'info> {
+    fn to_account_metas(&self) -> Vec<(AccountMeta, AccountInfo<'info>)> {
+        let mut accounts = vec![
+            signer(self.admin),
+            signer_writable(self.payer),
+            readonly(self.amm_config),
+            writable(self.target),
+            readonly(self.system_program),
+        ];
+        accounts.extend(self.pool.map(readonly));
+        accounts
+    }
+}
+
+pub fn migrate_account<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &MigrateAccountAccounts<'_, 'info>,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_clmm(
+        program,
+        accounts,
+        &ClmmInstruction::MigrateAccount,
+        signers_seeds,
+    )
+}
+
+pub struct InitializeFeeTierRegistryAccounts<'a, 'info> {
+    pub admin: &'a AccountInfo<'info>,
+    pub fee_tier_registry: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub program_data: &'a AccountInfo<'info>,
+}
+
+impl<'info> CpiAccounts<'info> for InitializeFeeTierRegistryAccounts<'_, 'info> {
+    fn to_account_metas(&self) -> Vec<(AccountMeta, AccountInfo<'info>)> {
+        vec![
+            signer_writable(self.admin),
+            writable(self.fee_tier_registry),
+            readonly(self.system_program),
+            readonly(self.program_data),
+        ]
+    }
+}
+
+pub fn initialize_fee_tier_registry<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &InitializeFeeTierRegistryAccounts<'_, 'info>,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_clmm(
+        program,
+        accounts,
+        &ClmmInstruction::InitializeFeeTierRegistry,
+        signers_seeds,
+    )
+}
+
+pub struct SetFeeTierEnabledAccounts<'a, 'info> {
+    pub admin: &'a AccountInfo<'info>,
+    pub fee_tier_registry: &'a AccountInfo<'info>,
+    pub amm_config: &'a AccountInfo<'info>,
+}
+
+impl<'info> CpiAccounts<'info> for SetFeeTierEnabledAccounts<'_, 'info> {
+    fn to_account_metas(&self) -> Vec<(AccountMeta, AccountInfo<'info>)> {
+        vec![
+            signer(self.admin),
+            writable(self.fee_tier_registry),
+            writable(self.amm_config),
+        ]
+    }
+}
+
+pub fn set_fee_tier_enabled<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &SetFeeTierEnabledAccounts<'_, 'info>,
+    enabled: bool,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_clmm(
+        program,
+        accounts,
+        &ClmmInstruction::SetFeeTierEnabled { enabled },
+        signers_seeds,
+    )
+}
+
+/// Accounts for closing an NFT-owned account, a position or a position bundle.
+pub struct CloseNftAccountAccounts<'a, 'info> {
+    pub owner: &'a AccountInfo<'info>,
+    pub nft_mint: &'a AccountInfo<'info>,
+    pub nft_account: &'a AccountInfo<'info>,
+    pub account: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+}
+
+impl<'info> CpiAccounts<'info> for CloseNftAccountAccounts<'_, 'info> {
+    fn to_account_metas(&self) -> Vec<(AccountMeta, AccountInfo<'info>)> {
+        vec![
+            signer_writable(self.owner),
+            writable(self.nft_mint),
+            writable(self.nft_account),
+            writable(self.account),
+            readonly(self.token_program),
+        ]
+    }
+}
+
+pub fn close_position<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &CloseNftAccountAccounts<'_, 'info>,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_clmm(
+        program,
+        accounts,
+        &ClmmInstruction::ClosePosition,
+        signers_seeds,
+    )
+}
+
+pub struct CloseTickArrayAccounts<'a, 'info> {
+    pub pool: &'a AccountInfo<'info>,
+    pub tick_array: &'a AccountInfo<'info>,
+    pub recipient: &'a AccountInfo<'info>,
+}
+
+impl<'info> CpiAccounts<'info> for CloseTickArrayAccounts<'_, 'info> {
+    fn to_account_metas(&self) -> Vec<(AccountMeta, AccountInfo<'info>)> {
+        vec![
+            writable(self.pool),
+            writable(self.tick_array),
+            writable(self.recipient),
+        ]
+    }
+}
+
+pub fn close_tick_array<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &CloseTickArrayAccounts<'_, 'info>,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_clmm(
+        program,
+        accounts,
+        &ClmmInstruction::CloseTickArray,
+        signers_seeds,
+    )
+}
+
+/// The bundle NFT mint and token account are new accounts and must sign too.
+pub struct InitializePositionBundleAccounts<'a, 'info> {
+    pub payer: &'a AccountInfo<'info>,
+    pub bundle_mint: &'a AccountInfo<'info>,
+    pub bundle_token_account: &'a AccountInfo<'info>,
+    pub position_bundle: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+impl<'info> CpiAccounts<'info> for InitializePositionBundleAccounts<'_, 'info> {
+    fn to_account_metas(&self) -> Vec<(AccountMeta, AccountInfo<'info>)> {
+        vec![
+            signer_writable(self.payer),
+            signer_writable(self.bundle_mint),
+            signer_writable(self.bundle_token_account),
+            writable(self.position_bundle),
+            readonly(self.token_program),
+            readonly(self.system_program),
+        ]
+    }
+}
+
+pub fn initialize_position_bundle<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &InitializePositionBundleAccounts<'_, 'info>,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_clmm(
+        program,
+        accounts,
+        &ClmmInstruction::InitializePositionBundle,
+        signers_seeds,
+    )
+}
+
+pub struct OpenBundledPositionAccounts<'a, 'info> {
+    pub owner: &'a AccountInfo<'info>,
+    pub bundle_token_account: &'a AccountInfo<'info>,
+    pub position_bundle: &'a AccountInfo<'info>,
+    pub pool: &'a AccountInfo<'info>,
+    pub position: &'a AccountInfo<'info>,
+    pub tick_array_lower: &'a AccountInfo<'info>,
+    pub tick_array_upper: &'a AccountInfo<'info>,
+    pub token_account_0: &'a AccountInfo<'info>,
+    pub token_account_1: &'a AccountInfo<'info>,
+    pub token_vault_0: &'a AccountInfo<'info>,
+    pub token_vault_1: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    /// Required by permissioned pools
+    pub allowlist_entry: Option<&'a AccountInfo<'info>>,
+}
+
+impl<'info> CpiAccounts<'info> for OpenBundledPositionAccounts<'_, 'info> {
+    fn to_account_metas(&self) -> Vec<(AccountMeta, AccountInfo<'info>)> {
+        let mut accounts = vec![
+            signer_writable(self.owner),
+            readonly(self.bundle_token_account),
+            writable(self.position_bundle),
+            writable(self.pool),
+            writable(self.position),
+            writable(self.tick_array_lower),
+            writable(self.tick_array_upper),
+            writable(self.token_account_0),
+            writable(self.token_account_1),
+            writable(self.token_vault_0),
+            writable(self.token_vault_1),
+            readonly(self.token_program),
+            readonly(self.system_program),
+        ];
+        accounts.extend(self.allowlist_entry.map(readonly));
+        accounts
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn open_bundled_position<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &OpenBundledPositionAccounts<'_, 'info>,
+    bundle_index: u16,
+    tick_lower: i32,
+    tick_upper: i32,
+    liquidity: u128,
+    amount_0_max: u64,
+    amount_1_max: u64,
+    deadline: Option<Deadline>,
+    sqrt_price_band: Option<SqrtPriceBand>,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_clmm(
+        program,
+        accounts,
+        &ClmmInstruction::OpenBundledPosition {
+            bundle_index,
+            tick_lower,
+            tick_upper,
+            liquidity,
+            amount_0_max,
+            amount_1_max,
+            deadline,
+            sqrt_price_band,
+        },
+        signers_seeds,
+    )
+}
+
+pub struct CloseBundledPositionAccounts<'a, 'info> {
+    pub owner: &'a AccountInfo<'info>,
+    pub bundle_token_account: &'a AccountInfo<'info>,
+    pub position_bundle: &'a AccountInfo<'info>,
+    pub position: &'a AccountInfo<'info>,
+}
+
+impl<'info> CpiAccounts<'info> for CloseBundledPositionAccounts<'_, 'info> {
+    fn to_account_metas(&self) -> Vec<(AccountMeta, AccountInfo<'info>)> {
+        vec![
+            signer_writable(self.owner),
+            readonly(self.bundle_token_account),
+            writable(self.position_bundle),
+            writable(self.position),
+        ]
+    }
+}
+
+pub fn close_bundled_position<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &CloseBundledPositionAccounts<'_, 'info>,
+    bundle_index: u16,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_clmm(
+        program,
+        accounts,
+        &ClmmInstruction::CloseBundledPosition { bundle_index },
+        signers_seeds,
+    )
+}
+
+pub fn close_position_bundle<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &CloseNftAccountAccounts<'_, 'info>,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_clmm(
+        program,
+        accounts,
+        &ClmmInstruction::ClosePositionBundle,
+        signers_seeds,
+    )
+}
+
+pub struct CompoundFeesAccounts<'a, 'info> {
+    pub owner: &'a AccountInfo<'info>,
+    pub nft_account: &'a AccountInfo<'info>,
+    pub pool: &'a AccountInfo<'info>,
+    pub position: &'a AccountInfo<'info>,
+    pub tick_array_lower: &'a AccountInfo<'info>,
+    pub tick_array_upper: &'a AccountInfo<'info>,
+    /// Required by permissioned pools
+    pub allowlist_entry: Option<&'a AccountInfo<'info>>,
+}
+
+impl<'info> CpiAccounts<'info> for CompoundFeesAccounts<'_, 'info> {
+    fn to_account_metas(&self) -> Vec<(AccountMeta, AccountInfo<'info>)> {
+        let mut accounts = vec![
+            signer(self.owner),
+            readonly(self.nft_account),
+            writable(self.pool),
+            writable(self.position),
+            writable(self.tick_array_lower),
+            writable(self.tick_array_upper),
+        ];
+        accounts.extend(self.allowlist_entry.map(readonly));
+        accounts
+    }
+}
+
+pub fn compound_fees<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &CompoundFeesAccounts<'_, 'info>,
+    deadline: Option<Deadline>,
+    sqrt_price_band: Option<SqrtPriceBand>,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_clmm(
+        program,
+        accounts,
+        &ClmmInstruction::CompoundFees {
+            deadline,
+            sqrt_price_band,
+        },
+        signers_seeds,
+    )
+}
+
+pub struct RepositionAccounts<'a, 'info> {
+    pub owner: &'a AccountInfo<'info>,
+    pub nft_account: &'a AccountInfo<'info>,
+    pub pool: &'a AccountInfo<'info>,
+    pub position: &'a AccountInfo<'info>,
+    pub old_tick_array_lower: &'a AccountInfo<'info>,
+    pub old_tick_array_upper: &'a AccountInfo<'info>,
+    pub tick_array_lower: &'a AccountInfo<'info>,
+    pub tick_array_upper: &'a AccountInfo<'info>,
+    pub recipient_token_account_0: &'a AccountInfo<'info>,
+    pub recipient_token_account_1: &'a AccountInfo<'info>,
+    pub token_vault_0: &'a AccountInfo<'info>,
+    pub token_vault_1: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    /// Required by permissioned pools
+    pub allowlist_entry: Option<&'a AccountInfo<'info>>,
+}
+
+impl<'info> CpiAccounts<'info> for RepositionAccounts<'_, 'info> {
+    fn to_account_metas(&self) -> Vec<(AccountMeta, AccountInfo<'info>)> {
+        let mut accounts = vec![
+            signer_writable(self.owner),
+            readonly(self.nft_account),
+            writable(self.pool),
+            writable(self.position),
+            writable(self.old_tick_array_lower),
+            writable(self.old_tick_array_upper),
+            writable(self.tick_array_lower),
+            writable(self.tick_array_upper),
+            writable(self.recipient_token_account_0),
+            writable(self.recipient_token_account_1),
+            writable(self.token_vault_0),
+            writable(self.token_vault_1),
+            readonly(self.token_program),
+            readonly(self.system_program),
+        ];
+        accounts.extend(self.allowlist_entry.map(readonly));
+        accounts
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn reposition<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &RepositionAccounts<'_, 'info>,
+    tick_lower: i32,
+    tick_upper: i32,
+    liquidity_min: u128,
+    amount_0_leftover_max: u64,
+    amount_1_leftover_max: u64,
+    deadline: Option<Deadline>,
+    sqrt_price_band: Option<SqrtPriceBand>,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_clmm(
+        program,
+        accounts,
+        &ClmmInstruction::Reposition {
+            tick_lower,
+            tick_upper,
+            liquidity_min,
+            amount_0_leftover_max,
+            amount_1_leftover_max,
+            deadline,
+            sqrt_price_band,
+        },
+        signers_seeds,
+    )
+}
+
+pub struct CollectFundFeesAccounts<'a, 'info> {
+    pub fund_owner: &'a AccountInfo<'info>,
+    pub amm_config: &'a AccountInfo<'info>,
+    pub pool: &'a AccountInfo<'info>,
+    pub recipient_token_account_0: &'a AccountInfo<'info>,
+    pub recipient_token_account_1: &'a AccountInfo<'info>,
+    pub token_vault_0: &'a AccountInfo<'info>,
+    pub token_vault_1: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+}
+
+impl<'info> CpiAccounts<'info> for CollectFundFeesAccounts<'_, 'info> {
+    fn to_account_metas(&self) -> Vec<(AccountMeta, AccountInfo<'info>)> {
+        vec![
+            signer(self.fund_owner),
+            readonly(self.amm_config),
+            writable(self.pool),
+            writable(self.recipient_token_account_0),
+            writable(self.recipient_token_account_1),
+            writable(self.token_vault_0),
+            writable(self.token_vault_1),
+            readonly(self.token_program),
+        ]
+    }
+}
+
+pub fn collect_fund_fees<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &CollectFundFeesAccounts<'_, 'info>,
+    amount_0_requested: u64,
+    amount_1_requested: u64,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_clmm(
+        program,
+        accounts,
+        &ClmmInstruction::CollectFundFees {
+            amount_0_requested,
+            amount_1_requested,
+        },
+        signers_seeds,
+    )
+}
+
+pub struct AddToAllowlistAccounts<'a, 'info> {
+    pub permission_authority: &'a AccountInfo<'info>,
+    pub pool: &'a AccountInfo<'info>,
+    pub allowlist_entry: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+impl<'info> CpiAccounts<'info> for AddToAllowlistAccounts<'_, 'info> {
+    fn to_account_metas(&self) -> Vec<(AccountMeta, AccountInfo<'info>)> {
+        vec![
+            signer_writable(self.permission_authority),
+            readonly(self.pool),
+            writable(self.allowlist_entry),
+            readonly(self.system_program),
+        ]
+    }
+}
+
+pub fn add_to_allowlist<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &AddToAllowlistAccounts<'_, 'info>,
+    wallet: Pubkey,
+    permissions: u8,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_clmm(
+        program,
+        accounts,
+        &ClmmInstruction::AddToAllowlist {
+            wallet,
+            permissions,
+        },
+        signers_seeds,
+    )
+}
+
+pub struct RemoveFromAllowlistAccounts<'a, 'info> {
+    pub permission_authority: &'a AccountInfo<'info>,
+    pub pool: &'a AccountInfo<'info>,
+    pub allowlist_entry: &'a AccountInfo<'info>,
+}
+
+impl<'info> CpiAccounts<'info> for RemoveFromAllowlistAccounts<'_, 'info> {
+    fn to_account_metas(&self) -> Vec<(AccountMeta, AccountInfo<'info>)> {
+        vec![
+            signer_writable(self.permission_authority),
+            readonly(self.pool),
+            writable(self.allowlist_entry),
+        ]
+    }
+}
+
+pub fn remove_from_allowlist<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &RemoveFromAllowlistAccounts<'_, 'info>,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_clmm(
+        program,
+        accounts,
+        &ClmmInstruction::RemoveFromAllowlist,
+        signers_seeds,
+    )
+}
+
+pub struct SetPoolFeeRateAccounts<'a, 'info> {
+    pub admin: &'a AccountInfo<'info>,
+    pub amm_config: &'a AccountInfo<'info>,
+    pub pool: &'a AccountInfo<'info>,
+}
+
+impl<'info> CpiAccounts<'info> for SetPoolFeeRateAccounts<'_, 'info> {
+    fn to_account_metas(&self) -> Vec<(AccountMeta, AccountInfo<'info>)> {
+        vec![
+            signer(self.admin),
+            readonly(self.amm_config),
+            writable(self.pool),
+        ]
+    }
+}
+
+pub fn set_pool_fee_rate<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &SetPoolFeeRateAccounts<'_, 'info>,
+    trade_fee_rate: Option<u32>,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_clmm(
+        program,
+        accounts,
+        &ClmmInstruction::SetPoolFeeRate { trade_fee_rate },
+        signers_seeds,
+    )
+}
+
+pub struct InitializeTickArrayAccounts<'a, 'info> {
+    pub payer: &'a AccountInfo<'info>,
+    pub pool: &'a AccountInfo<'info>,
+    pub tick_array: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+impl<'info> CpiAccounts<'info> for InitializeTickArrayAccounts<'_, 'info> {
+    fn to_account_metas(&self) -> Vec<(AccountMeta, AccountInfo<'info>)> {
+        vec![
+            signer_writable(self.payer),
+            readonly(self.pool),
+            writable(self.tick_array),
+            readonly(self.system_program),
+        ]
+    }
+}
+
+pub fn initialize_tick_array<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &InitializeTickArrayAccounts<'_, 'info>,
+    start_tick_index: i32,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_clmm(
+        program,
+        accounts,
+        &ClmmInstruction::InitializeTickArray { start_tick_index },
+        signers_seeds,
+    )
+}
+
+pub struct QuoteSwapAccounts<'a, 'info> {
+    pub amm_config: &'a AccountInfo<'info>,
+    pub pool: &'a AccountInfo<'info>,
+    /// Starting with the array holding the current tick, in the swap direction
+    pub tick_arrays: &'a [AccountInfo<'info>],
+}
+
+impl<'info> CpiAccounts<'info> for QuoteSwapAccounts<'_, 'info> {
+    fn to_account_metas(&self) -> Vec<(AccountMeta, AccountInfo<'info>)> {
+        let mut accounts = vec![readonly(self.amm_config), readonly(self.pool)];
+        accounts.extend(self.tick_arrays.iter().map(readonly));
+        accounts
+    }
+}
+
+/// Quotes a swap and decodes the [`SwapQuote`] the program returns.
+pub fn quote_swap<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &QuoteSwapAccounts<'_, 'info>,
+    amount: u64,
+    sqrt_price_limit_x64: u128,
+    zero_for_one: bool,
+    is_base_input: bool,
+) -> Result<SwapQuote, ProgramError> {
+    invoke_clmm(
+        program,
+        accounts,
+        &ClmmInstruction::QuoteSwap {
+            amount,
+            sqrt_price_limit_x64,
+            zero_for_one,
+            is_base_input,
+        },
+        &[],
+    )?;
+    match get_return_data() {
+        Some((program_id, data)) if program_id == crate::ID => {
+            SwapQuote::try_from_slice(&data).map_err(|_| ClmmError::InvalidAccountData.into())
+        }
+        _ => Err(ClmmError::InvalidAccountData.into()),
+    }
+}
+
+pub struct SetMaxReferralFeeRateAccounts<'a, 'info> {
+    pub admin: &'a AccountInfo<'info>,
+    pub amm_config: &'a AccountInfo<'info>,
+}
+
+impl<'info> CpiAccounts<'info> for SetMaxReferralFeeRateAccounts<'_, 'info> {
+    fn to_account_metas(&self) -> Vec<(AccountMeta, AccountInfo<'info>)> {
+        vec![signer(self.admin), writable(self.amm_config)]
+    }
+}
+
+pub fn set_max_referral_fee_rate<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &SetMaxReferralFeeRateAccounts<'_, 'info>,
+    max_referral_fee_rate: u32,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_clmm(
+        program,
+        accounts,
+        &ClmmInstruction::SetMaxReferralFeeRate {
+            max_referral_fee_rate,
+        },
+        signers_seeds,
+    )
+}
@@ -1,7 +1,11 @@
+use num_derive::FromPrimitive;
 use solana_program_error::ProgramError;
 use thiserror::Error;
 
-#[derive(Error, Debug, Clone, PartialEq)]
+/// Program errors, surfaced as `ProgramError::Custom` with the variant index as code.
+///
+/// Codes decode back with `num_traits::FromPrimitive::from_u32`.
+#[derive(Error, Debug, Clone, PartialEq, FromPrimitive)]
 pub enum ClmmError {
     #[error("Invalid tick range: lower must be less than upper")]
     InvalidTickRange,
@@ -35,6 +39,56 @@ pub enum ClmmError {
     InvalidAccountData,
     #[error("Insufficient funds")]
     InsufficientFunds,
+    #[error("Account address does not match the expected PDA")]
+    InvalidPda,
+    #[error("Account is not writable")]
+    AccountNotWritable,
+    #[error("Account layout is outdated and must be migrated")]
+    AccountMigrationRequired,
+    #[error("Unsupported account layout version")]
+    UnsupportedAccountVersion,
+    #[error("Signer is not authorized for this action")]
+    Unauthorized,
+    #[error("Account already initialized")]
+    AccountAlreadyInitialized,
+    #[error("Tick array does not match the pool or expected start index")]
+    InvalidTickArray,
+    #[error("Amount must be greater than zero")]
+    ZeroAmount,
+    #[error("Invalid fee rate")]
+    InvalidFeeRate,
+    #[error("Swap needs tick arrays beyond the ones provided")]
+    NotEnoughTickArrays,
+    #[error("Fee tier is disabled for new pools")]
+    FeeTierDisabled,
+    #[error("A fee tier with this tick spacing already exists")]
+    DuplicateTickSpacing,
+    #[error("Fee tier registry is full")]
+    FeeTierRegistryFull,
+    #[error("Position still holds liquidity or owed fees")]
+    PositionNotEmpty,
+    #[error("Tick array still holds initialized ticks")]
+    TickArrayNotEmpty,
+    #[error("Position bundle index out of range")]
+    InvalidBundleIndex,
+    #[error("Position bundle slot already holds a position")]
+    BundleSlotInUse,
+    #[error("Position bundle still holds positions")]
+    PositionBundleNotEmpty,
+    #[error("Wallet is not allowlisted for this pool")]
+    NotAllowlisted,
+    #[error("Pool is not permissioned")]
+    PoolNotPermissioned,
+    #[error("Invalid allowlist permissions")]
+    InvalidPermissions,
+    #[error("Pool is not open for swaps yet")]
+    PoolNotOpen,
+    #[error("Swap exceeds the launch window limit")]
+    LaunchSwapTooLarge,
+    #[error("Transaction deadline has passed")]
+    DeadlineExceeded,
+    #[error("Pool price is outside the expected band")]
+    PriceOutOfBand,
 }
 
 impl From<ClmmError> for ProgramError {
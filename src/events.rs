@@ -0,0 +1,289 @@
+use borsh::io::{Error, ErrorKind, Result};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_pubkey::Pubkey;
+
+/// A program event with a stable binary encoding.
+///
+/// Events are logged as a single `Program data:` entry holding the 8-byte
+/// discriminator followed by the borsh-encoded body.
+pub trait Event: BorshSerialize + BorshDeserialize {
+    const DISCRIMINATOR: [u8; 8];
+
+    fn encode(&self) -> Vec<u8> {
+        let mut data = Self::DISCRIMINATOR.to_vec();
+        // Writing into a Vec cannot fail.
+        self.serialize(&mut data).unwrap();
+        data
+    }
+}
+
+/// Logs `event` through `sol_log_data`.
+pub fn emit<E: Event>(event: &E) {
+    log_data(&[&event.encode()]);
+}
+
+#[allow(unused_variables)]
+fn log_data(data: &[&[u8]]) {
+    #[cfg(target_os = "solana")]
+    unsafe {
+        solana_define_syscall::definitions::sol_log_data(
+            data as *const _ as *const u8,
+            data.len() as u64,
+        )
+    };
+
+    #[cfg(not(target_os = "solana"))]
+    solana_sysvar::program_stubs::sol_log_data(data);
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, PartialEq)]
+pub struct PoolCreatedEvent {
+    pub pool: Pubkey,
+    pub amm_config: Pubkey,
+    pub creator: Pubkey,
+    pub token_mint_0: Pubkey,
+    pub token_mint_1: Pubkey,
+    pub token_vault_0: Pubkey,
+    pub token_vault_1: Pubkey,
+    pub tick_spacing: u16,
+    pub sqrt_price_x64: u128,
+    pub tick: i32,
+    pub open_time: u64,
+}
+
+impl Event for PoolCreatedEvent {
+    const DISCRIMINATOR: [u8; 8] = [0x11, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, PartialEq)]
+pub struct PositionOpenedEvent {
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub nft_mint: Pubkey,
+    pub owner: Pubkey,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+}
+
+impl Event for PositionOpenedEvent {
+    const DISCRIMINATOR: [u8; 8] = [0x12, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, PartialEq)]
+pub struct PositionClosedEvent {
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub nft_mint: Pubkey,
+    pub owner: Pubkey,
+}
+
+impl Event for PositionClosedEvent {
+    const DISCRIMINATOR: [u8; 8] = [0x13, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, PartialEq)]
+pub struct LiquidityIncreasedEvent {
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    pub liquidity: u128,
+    pub amount_0: u64,
+    pub amount_1: u64,
+}
+
+impl Event for LiquidityIncreasedEvent {
+    const DISCRIMINATOR: [u8; 8] = [0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, PartialEq)]
+pub struct LiquidityDecreasedEvent {
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    pub liquidity: u128,
+    pub amount_0: u64,
+    pub amount_1: u64,
+}
+
+impl Event for LiquidityDecreasedEvent {
+    const DISCRIMINATOR: [u8; 8] = [0x15, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, PartialEq)]
+pub struct SwapEvent {
+    pub pool: Pubkey,
+    pub sender: Pubkey,
+    pub zero_for_one: bool,
+    pub is_base_input: bool,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+    pub protocol_fee_amount: u64,
+    pub fund_fee_amount: u64,
+    pub referral_fee_amount: u64,
+    pub sqrt_price_before_x64: u128,
+    pub sqrt_price_after_x64: u128,
+    pub tick_after: i32,
+    pub liquidity_after: u128,
+}
+
+impl Event for SwapEvent {
+    const DISCRIMINATOR: [u8; 8] = [0x16, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, PartialEq)]
+pub struct CollectFeesEvent {
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub amount_0: u64,
+    pub amount_1: u64,
+}
+
+impl Event for CollectFeesEvent {
+    const DISCRIMINATOR: [u8; 8] = [0x17, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, PartialEq)]
+pub struct CollectFundFeesEvent {
+    pub pool: Pubkey,
+    pub fund_owner: Pubkey,
+    pub amount_0: u64,
+    pub amount_1: u64,
+}
+
+impl Event for CollectFundFeesEvent {
+    const DISCRIMINATOR: [u8; 8] = [0x18, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+}
+
+/// Off-chain view of every event the program emits.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClmmEvent {
+    PoolCreated(PoolCreatedEvent),
+    PositionOpened(PositionOpenedEvent),
+    PositionClosed(PositionClosedEvent),
+    LiquidityIncreased(LiquidityIncreasedEvent),
+    LiquidityDecreased(LiquidityDecreasedEvent),
+    Swap(SwapEvent),
+    CollectFees(CollectFeesEvent),
+    CollectFundFees(CollectFundFeesEvent),
+}
+
+impl ClmmEvent {
+    /// Decodes the base64-decoded payload of a `Program data:` log line.
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        if data.len() < 8 {
+            return Err(Error::new(ErrorKind::InvalidData, "event too short"));
+        }
+        let (discriminator, mut body) = data.split_at(8);
+
+        let event = match discriminator {
+            d if d == PoolCreatedEvent::DISCRIMINATOR => {
+                ClmmEvent::PoolCreated(BorshDeserialize::deserialize(&mut body)?)
+            }
+            d if d == PositionOpenedEvent::DISCRIMINATOR => {
+                ClmmEvent::PositionOpened(BorshDeserialize::deserialize(&mut body)?)
+            }
+            d if d == PositionClosedEvent::DISCRIMINATOR => {
+                ClmmEvent::PositionClosed(BorshDeserialize::deserialize(&mut body)?)
+            }
+            d if d == LiquidityIncreasedEvent::DISCRIMINATOR => {
+                ClmmEvent::LiquidityIncreased(BorshDeserialize::deserialize(&mut body)?)
+            }
+            d if d == LiquidityDecreasedEvent::DISCRIMINATOR => {
+                ClmmEvent::LiquidityDecreased(BorshDeserialize::deserialize(&mut body)?)
+            }
+            d if d == SwapEvent::DISCRIMINATOR => {
+                ClmmEvent::Swap(BorshDeserialize::deserialize(&mut body)?)
+            }
+            d if d == CollectFeesEvent::DISCRIMINATOR => {
+                ClmmEvent::CollectFees(BorshDeserialize::deserialize(&mut body)?)
+            }
+            d if d == CollectFundFeesEvent::DISCRIMINATOR => {
+                ClmmEvent::CollectFundFees(BorshDeserialize::deserialize(&mut body)?)
+            }
+            _ => return Err(Error::new(ErrorKind::InvalidData, "unknown event")),
+        };
+
+        if !body.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidData, "trailing event bytes"));
+        }
+        Ok(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_swap_event_roundtrip() {
+        let event = SwapEvent {
+            pool: Pubkey::new_unique(),
+            sender: Pubkey::new_unique(),
+            zero_for_one: true,
+            is_base_input: true,
+            amount_in: 1_000,
+            amount_out: 990,
+            fee_amount: 3,
+            protocol_fee_amount: 1,
+            fund_fee_amount: 1,
+            referral_fee_amount: 0,
+            sqrt_price_before_x64: 1 << 64,
+            sqrt_price_after_x64: (1 << 64) - 12345,
+            tick_after: -2,
+            liquidity_after: 1_000_000,
+        };
+
+        let decoded = ClmmEvent::decode(&event.encode()).unwrap();
+        assert_eq!(decoded, ClmmEvent::Swap(event));
+    }
+
+    #[test]
+    fn test_collect_fund_fees_event_roundtrip() {
+        let event = CollectFundFeesEvent {
+            pool: Pubkey::new_unique(),
+            fund_owner: Pubkey::new_unique(),
+            amount_0: 11,
+            amount_1: 13,
+        };
+
+        let decoded = ClmmEvent::decode(&event.encode()).unwrap();
+        assert_eq!(decoded, ClmmEvent::CollectFundFees(event));
+    }
+
+    #[test]
+    fn test_decode_rejects_unknown_and_truncated() {
+        let event = CollectFeesEvent {
+            pool: Pubkey::new_unique(),
+            position: Pubkey::new_unique(),
+            amount_0: 5,
+            amount_1: 7,
+        };
+        let data = event.encode();
+
+        assert!(ClmmEvent::decode(&data[..data.len() - 1]).is_err());
+        assert!(ClmmEvent::decode(&[0xff; 16]).is_err());
+        assert!(ClmmEvent::decode(&[0x17]).is_err());
+    }
+
+    #[test]
+    fn test_discriminators_are_unique() {
+        let discriminators = [
+            PoolCreatedEvent::DISCRIMINATOR,
+            PositionOpenedEvent::DISCRIMINATOR,
+            PositionClosedEvent::DISCRIMINATOR,
+            LiquidityIncreasedEvent::DISCRIMINATOR,
+            LiquidityDecreasedEvent::DISCRIMINATOR,
+            SwapEvent::DISCRIMINATOR,
+            CollectFeesEvent::DISCRIMINATOR,
+            CollectFundFeesEvent::DISCRIMINATOR,
+        ];
+        for (i, a) in discriminators.iter().enumerate() {
+            for b in &discriminators[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+    }
+}
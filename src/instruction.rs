@@ -1,44 +1,156 @@
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_pubkey::Pubkey;
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+use crate::instructions::{Deadline, SqrtPriceBand};
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone)]
 pub enum ClmmInstruction {
     InitializeConfig {
         index: u16,
         trade_fee_rate: u32,
         protocol_fee_rate: u32,
         tick_spacing: u16,
+        fund_fee_rate: u32,
+        fund_owner: Pubkey,
+        max_referral_fee_rate: u32,
     },
 
+    /// A `permission_authority` makes the pool permissioned.
+    /// Zero launch caps leave that token uncapped during the launch window.
     CreatePool {
         sqrt_price_x64: u128,
+        permission_authority: Option<Pubkey>,
+        open_time: u64,
+        launch_duration: u64,
+        launch_max_amount_0: u64,
+        launch_max_amount_1: u64,
     },
 
+    /// The deadline and price band guards of liquidity ops are skipped when `None`.
     OpenPosition {
         tick_lower: i32,
         tick_upper: i32,
         liquidity: u128,
         amount_0_max: u64,
         amount_1_max: u64,
+        deadline: Option<Deadline>,
+        sqrt_price_band: Option<SqrtPriceBand>,
     },
 
     IncreaseLiquidity {
         liquidity: u128,
         amount_0_max: u64,
         amount_1_max: u64,
+        deadline: Option<Deadline>,
+        sqrt_price_band: Option<SqrtPriceBand>,
     },
 
     DecreaseLiquidity {
         liquidity: u128,
         amount_0_min: u64,
         amount_1_min: u64,
+        deadline: Option<Deadline>,
+        sqrt_price_band: Option<SqrtPriceBand>,
     },
 
+    /// With `is_base_input` false, `amount_in` is the exact output wanted
+    /// and `minimum_amount_out` the most input the payer accepts.
+    /// A referral token account may follow the token program account. It is paid
+    /// `referral_fee_rate` of the trade fee, at most the config's `max_referral_fee_rate`.
     Swap {
         amount_in: u64,
         minimum_amount_out: u64,
         sqrt_price_limit_x64: u128,
         is_base_input: bool,
+        deadline: Option<Deadline>,
+        referral_fee_rate: u32,
+    },
+
+    CollectFees,
+
+    MigrateAccount,
+
+    InitializeFeeTierRegistry,
+
+    /// Disabled fee tiers reject new pools, existing pools are unaffected.
+    SetFeeTierEnabled {
+        enabled: bool,
+    },
+
+    ClosePosition,
+
+    CloseTickArray,
+
+    InitializePositionBundle,
+
+    /// Bundled positions have no instructions of their own for increase, decrease and
+    /// collect: the regular ones take the bundled position and the bundle NFT token account.
+    OpenBundledPosition {
+        bundle_index: u16,
+        tick_lower: i32,
+        tick_upper: i32,
+        liquidity: u128,
+        amount_0_max: u64,
+        amount_1_max: u64,
+        deadline: Option<Deadline>,
+        sqrt_price_band: Option<SqrtPriceBand>,
+    },
+
+    CloseBundledPosition {
+        bundle_index: u16,
     },
 
-    CollectFees
+    ClosePositionBundle,
+
+    CompoundFees {
+        deadline: Option<Deadline>,
+        sqrt_price_band: Option<SqrtPriceBand>,
+    },
+
+    /// Leftovers are the released amounts and fees that do not fit the new range.
+    Reposition {
+        tick_lower: i32,
+        tick_upper: i32,
+        liquidity_min: u128,
+        amount_0_leftover_max: u64,
+        amount_1_leftover_max: u64,
+        deadline: Option<Deadline>,
+        sqrt_price_band: Option<SqrtPriceBand>,
+    },
+
+    /// Amounts above what the pool has accrued are capped.
+    CollectFundFees {
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    },
+
+    SetMaxReferralFeeRate {
+        max_referral_fee_rate: u32,
+    },
+
+    /// Adds `wallet` to a permissioned pool's allowlist, or replaces its permissions.
+    AddToAllowlist {
+        wallet: Pubkey,
+        permissions: u8,
+    },
+
+    RemoveFromAllowlist,
+
+    /// `None` clears the override, so the pool follows its config again.
+    SetPoolFeeRate {
+        trade_fee_rate: Option<u32>,
+    },
+
+    /// Does nothing when the array already exists.
+    InitializeTickArray {
+        start_tick_index: i32,
+    },
+
+    /// Read-only, returns a borsh `SwapQuote` through return data.
+    QuoteSwap {
+        amount: u64,
+        sqrt_price_limit_x64: u128,
+        zero_for_one: bool,
+        is_base_input: bool,
+    },
 }
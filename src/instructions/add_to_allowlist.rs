@@ -0,0 +1,95 @@
+use solana_account_info::{next_account_info, AccountInfo};
+use solana_program_error::ProgramResult;
+use solana_pubkey::Pubkey;
+
+use crate::error::ClmmError;
+use crate::state::{AccountMut, AccountRef, AllowlistEntry, PoolState, ALLOWLIST_ENTRY_SEED};
+use crate::utils::{check_signer, check_system_program, create_account};
+
+/// Allowlists `wallet` on a permissioned pool with `permissions`.
+///
+/// An existing entry keeps its account and only has its permissions replaced.
+///
+/// Accounts:
+/// 0. `[signer, writable]` pool permission authority, pays for the entry
+/// 1. `[]` pool
+/// 2. `[writable]` allowlist entry PDA for the pool and wallet
+/// 3. `[]` system program
+pub fn process_add_to_allowlist(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    wallet: Pubkey,
+    permissions: u8,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let authority = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let entry_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    check_signer(authority)?;
+    check_system_program(system_program)?;
+
+    let pool = AccountRef::<PoolState>::load(pool_info, program_id)?;
+    if !pool.is_permissioned() {
+        return Err(ClmmError::PoolNotPermissioned.into());
+    }
+    if pool.permission_authority != *authority.key {
+        return Err(ClmmError::Unauthorized.into());
+    }
+    if permissions == 0 || permissions & !AllowlistEntry::PERMISSION_ALL != 0 {
+        return Err(ClmmError::InvalidPermissions.into());
+    }
+
+    if entry_info.owner == program_id {
+        let mut entry = AccountMut::<AllowlistEntry>::load(entry_info, program_id)?.verify_pda(
+            program_id,
+            &[
+                ALLOWLIST_ENTRY_SEED,
+                pool_info.key.as_ref(),
+                wallet.as_ref(),
+            ],
+        )?;
+        entry.permissions = permissions;
+        return entry.exit();
+    }
+
+    let (expected, bump) = Pubkey::find_program_address(
+        &[
+            ALLOWLIST_ENTRY_SEED,
+            pool_info.key.as_ref(),
+            wallet.as_ref(),
+        ],
+        program_id,
+    );
+    if expected != *entry_info.key {
+        return Err(ClmmError::InvalidPda.into());
+    }
+    create_account(
+        authority,
+        entry_info,
+        system_program,
+        program_id,
+        AllowlistEntry::LEN,
+        &[&[
+            ALLOWLIST_ENTRY_SEED,
+            pool_info.key.as_ref(),
+            wallet.as_ref(),
+            &[bump],
+        ]],
+    )?;
+    let entry = AccountMut::init(
+        entry_info,
+        program_id,
+        AllowlistEntry {
+            discriminator: AllowlistEntry::DISCRIMINATOR,
+            version: AllowlistEntry::VERSION,
+            bump,
+            permissions,
+            pool: *pool_info.key,
+            wallet,
+            ..Default::default()
+        },
+    )?;
+    entry.exit()
+}
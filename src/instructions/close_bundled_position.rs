@@ -0,0 +1,59 @@
+use solana_account_info::{next_account_info, AccountInfo};
+use solana_program_error::ProgramResult;
+use solana_pubkey::Pubkey;
+
+use crate::error::ClmmError;
+use crate::events::{emit, PositionClosedEvent};
+use crate::state::{
+    AccountMut, PositionBundleState, PositionState, BUNDLED_POSITION_SEED, POSITION_BUNDLE_SEED,
+};
+use crate::utils::{check_position_nft, check_signer};
+
+/// Closes the empty position in slot `bundle_index`, freeing the slot and refunding the rent.
+///
+/// Accounts:
+/// 0. `[signer, writable]` bundle owner, receives the rent
+/// 1. `[]` bundle NFT token account
+/// 2. `[writable]` position bundle
+/// 3. `[writable]` bundled position for `bundle_index`
+pub fn process_close_bundled_position(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    bundle_index: u16,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let bundle_token_account = next_account_info(account_info_iter)?;
+    let bundle_info = next_account_info(account_info_iter)?;
+    let position_info = next_account_info(account_info_iter)?;
+
+    check_signer(owner)?;
+    let bundle = AccountMut::<PositionBundleState>::load(bundle_info, program_id)?;
+    let bundle_mint = bundle.bundle_mint;
+    let mut bundle =
+        bundle.verify_pda(program_id, &[POSITION_BUNDLE_SEED, bundle_mint.as_ref()])?;
+    check_position_nft(bundle_token_account, &bundle_mint, owner.key)?;
+
+    let position = AccountMut::<PositionState>::load(position_info, program_id)?.verify_pda(
+        program_id,
+        &[
+            BUNDLED_POSITION_SEED,
+            bundle_mint.as_ref(),
+            &bundle_index.to_be_bytes(),
+        ],
+    )?;
+    if position.liquidity != 0 || position.token_fees_owed_0 != 0 || position.token_fees_owed_1 != 0
+    {
+        return Err(ClmmError::PositionNotEmpty.into());
+    }
+    bundle.close_slot(bundle_index)?;
+
+    emit(&PositionClosedEvent {
+        pool: position.pool_id,
+        position: *position_info.key,
+        nft_mint: bundle_mint,
+        owner: *owner.key,
+    });
+    position.close(owner)?;
+    bundle.exit()
+}
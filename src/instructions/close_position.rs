@@ -0,0 +1,53 @@
+use solana_account_info::{next_account_info, AccountInfo};
+use solana_program_error::ProgramResult;
+use solana_pubkey::Pubkey;
+
+use crate::error::ClmmError;
+use crate::events::{emit, PositionClosedEvent};
+use crate::state::{AccountMut, PositionState, POSITION_SEED};
+use crate::utils::{burn_position_nft, check_position_nft, check_signer, check_token_program};
+
+/// Closes an empty position, burning its NFT and refunding the rent to the owner.
+///
+/// The position must have no liquidity and no owed fees left. The NFT token account
+/// is closed as well. The NFT mint stays behind with zero supply, since the SPL Token
+/// program cannot close mints. Positions in a bundle are closed through the bundle instead.
+///
+/// Accounts:
+/// 0. `[signer, writable]` position owner, receives the rent
+/// 1. `[writable]` position NFT mint
+/// 2. `[writable]` position NFT token account
+/// 3. `[writable]` position
+/// 4. `[]` token program
+pub fn process_close_position(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let nft_mint = next_account_info(account_info_iter)?;
+    let nft_account = next_account_info(account_info_iter)?;
+    let position_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    check_signer(owner)?;
+    check_token_program(token_program)?;
+
+    let position = AccountMut::<PositionState>::load(position_info, program_id)?
+        .verify_pda(program_id, &[POSITION_SEED, nft_mint.key.as_ref()])?;
+    if *nft_mint.key != position.nft_mint {
+        return Err(ClmmError::InvalidAccountData.into());
+    }
+    check_position_nft(nft_account, nft_mint.key, owner.key)?;
+    if position.liquidity != 0 || position.token_fees_owed_0 != 0 || position.token_fees_owed_1 != 0
+    {
+        return Err(ClmmError::PositionNotEmpty.into());
+    }
+
+    burn_position_nft(owner, nft_mint, nft_account, token_program)?;
+
+    emit(&PositionClosedEvent {
+        pool: position.pool_id,
+        position: *position_info.key,
+        nft_mint: *nft_mint.key,
+        owner: *owner.key,
+    });
+    position.close(owner)
+}
@@ -0,0 +1,47 @@
+use solana_account_info::{next_account_info, AccountInfo};
+use solana_program_error::ProgramResult;
+use solana_pubkey::Pubkey;
+
+use crate::error::ClmmError;
+use crate::state::{AccountMut, PositionBundleState, POSITION_BUNDLE_SEED};
+use crate::utils::{burn_position_nft, check_position_nft, check_signer, check_token_program};
+
+/// Closes an empty position bundle, burning its NFT and refunding the rent to the owner.
+///
+/// As with single positions, the bundle mint stays behind with zero supply.
+///
+/// Accounts:
+/// 0. `[signer, writable]` bundle owner, receives the rent
+/// 1. `[writable]` bundle NFT mint
+/// 2. `[writable]` bundle NFT token account
+/// 3. `[writable]` position bundle
+/// 4. `[]` token program
+pub fn process_close_position_bundle(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let bundle_mint = next_account_info(account_info_iter)?;
+    let bundle_token_account = next_account_info(account_info_iter)?;
+    let bundle_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    check_signer(owner)?;
+    check_token_program(token_program)?;
+
+    let bundle = AccountMut::<PositionBundleState>::load(bundle_info, program_id)?.verify_pda(
+        program_id,
+        &[POSITION_BUNDLE_SEED, bundle_mint.key.as_ref()],
+    )?;
+    if bundle.bundle_mint != *bundle_mint.key {
+        return Err(ClmmError::InvalidAccountData.into());
+    }
+    check_position_nft(bundle_token_account, bundle_mint.key, owner.key)?;
+    if !bundle.is_empty() {
+        return Err(ClmmError::PositionBundleNotEmpty.into());
+    }
+
+    burn_position_nft(owner, bundle_mint, bundle_token_account, token_program)?;
+    bundle.close(owner)
+}
@@ -0,0 +1,39 @@
+use solana_account_info::{next_account_info, AccountInfo};
+use solana_program_error::ProgramResult;
+use solana_pubkey::Pubkey;
+
+use crate::error::ClmmError;
+use crate::state::{AccountMut, PoolState, TickArrayState};
+
+/// Closes a tick array without initialized ticks and refunds its rent.
+///
+/// Anyone may call this, the rent always goes back to whoever paid for the array.
+/// A closed array can be created again when a position needs it. Swaps walk through
+/// a closed array as an empty one, so closing it inside a liquidity range does not block them.
+///
+/// Accounts:
+/// 0. `[writable]` pool
+/// 1. `[writable]` tick array
+/// 2. `[writable]` rent recipient, the account that paid for the array
+pub fn process_close_tick_array(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let pool_info = next_account_info(account_info_iter)?;
+    let tick_array_info = next_account_info(account_info_iter)?;
+    let recipient = next_account_info(account_info_iter)?;
+
+    let mut pool = AccountMut::<PoolState>::load(pool_info, program_id)?;
+    let tick_array = AccountMut::<TickArrayState>::load(tick_array_info, program_id)?;
+    if tick_array.pool_id != *pool_info.key {
+        return Err(ClmmError::InvalidTickArray.into());
+    }
+    if tick_array.initialized_tick_count != 0 {
+        return Err(ClmmError::TickArrayNotEmpty.into());
+    }
+    if tick_array.payer != *recipient.key {
+        return Err(ClmmError::Unauthorized.into());
+    }
+
+    pool.set_tick_array_initialized(tick_array.start_tick_index, false);
+    tick_array.close(recipient)?;
+    pool.exit()
+}
@@ -0,0 +1,98 @@
+use solana_account_info::{next_account_info, AccountInfo};
+use solana_program_error::ProgramResult;
+use solana_pubkey::Pubkey;
+
+use crate::error::ClmmError;
+use crate::events::{emit, CollectFeesEvent};
+use crate::instructions::{modify_position, PositionTickArrays};
+use crate::state::{AccountMut, PoolState, PositionState};
+use crate::utils::{check_position_nft, check_signer, check_token_program, transfer_from_pool};
+
+/// Pays out every fee owed to a position.
+///
+/// Fees earned since the position was last touched are settled first,
+/// which is why the position's tick arrays are required.
+///
+/// Accounts:
+/// 0. `[signer]` position owner
+/// 1. `[]` position NFT token account
+/// 2. `[writable]` pool
+/// 3. `[writable]` position
+/// 4. `[writable]` tick array holding the lower tick
+/// 5. `[writable]` tick array holding the upper tick, may repeat account 4
+/// 6. `[writable]` recipient token account 0
+/// 7. `[writable]` recipient token account 1
+/// 8. `[writable]` token vault 0
+/// 9. `[writable]` token vault 1
+/// 10. `[]` token program
+pub fn process_collect_fees(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let nft_account = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let position_info = next_account_info(account_info_iter)?;
+    let tick_array_lower_info = next_account_info(account_info_iter)?;
+    let tick_array_upper_info = next_account_info(account_info_iter)?;
+    let recipient_token_account_0 = next_account_info(account_info_iter)?;
+    let recipient_token_account_1 = next_account_info(account_info_iter)?;
+    let token_vault_0 = next_account_info(account_info_iter)?;
+    let token_vault_1 = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    check_signer(owner)?;
+    check_token_program(token_program)?;
+
+    let mut pool = AccountMut::<PoolState>::load(pool_info, program_id)?;
+    let mut position = AccountMut::<PositionState>::load(position_info, program_id)?;
+    if position.pool_id != *pool_info.key {
+        return Err(ClmmError::PositionNotFound.into());
+    }
+    check_position_nft(nft_account, &position.nft_mint, owner.key)?;
+    if *token_vault_0.key != pool.token_vault_0 || *token_vault_1.key != pool.token_vault_1 {
+        return Err(ClmmError::InvalidAccountData.into());
+    }
+
+    if position.liquidity > 0 {
+        let mut tick_arrays = PositionTickArrays::load(
+            program_id,
+            pool_info.key,
+            pool.tick_spacing,
+            position.tick_lower_index,
+            position.tick_upper_index,
+            tick_array_lower_info,
+            tick_array_upper_info,
+        )?;
+        modify_position(&mut pool, &mut position, &mut tick_arrays, 0)?;
+    }
+
+    let amount_0 = position.token_fees_owed_0;
+    let amount_1 = position.token_fees_owed_1;
+    position.token_fees_owed_0 = 0;
+    position.token_fees_owed_1 = 0;
+
+    let pool_seeds = pool.seeds();
+    transfer_from_pool(
+        pool_info,
+        &pool_seeds,
+        token_vault_0,
+        recipient_token_account_0,
+        token_program,
+        amount_0,
+    )?;
+    transfer_from_pool(
+        pool_info,
+        &pool_seeds,
+        token_vault_1,
+        recipient_token_account_1,
+        token_program,
+        amount_1,
+    )?;
+
+    emit(&CollectFeesEvent {
+        pool: *pool_info.key,
+        position: *position_info.key,
+        amount_0,
+        amount_1,
+    });
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use solana_account_info::{next_account_info, AccountInfo};
+use solana_program_error::ProgramResult;
+use solana_pubkey::Pubkey;
+
+use crate::error::ClmmError;
+use crate::events::{emit, CollectFundFeesEvent};
+use crate::state::{AccountMut, AccountRef, AmmConfig, PoolState};
+use crate::utils::{check_signer, check_token_program, transfer_from_pool};
+
+/// Pays out the fund's share of a pool's trade fees to the config's fund owner.
+///
+/// At most the requested amounts are paid, capped by what the pool has accrued.
+///
+/// Accounts:
+/// 0. `[signer]` fund owner
+/// 1. `[]` amm config
+/// 2. `[writable]` pool
+/// 3. `[writable]` recipient token account 0
+/// 4. `[writable]` recipient token account 1
+/// 5. `[writable]` token vault 0
+/// 6. `[writable]` token vault 1
+/// 7. `[]` token program
+pub fn process_collect_fund_fees(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount_0_requested: u64,
+    amount_1_requested: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let fund_owner = next_account_info(account_info_iter)?;
+    let amm_config_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let recipient_token_account_0 = next_account_info(account_info_iter)?;
+    let recipient_token_account_1 = next_account_info(account_info_iter)?;
+    let token_vault_0 = next_account_info(account_info_iter)?;
+    let token_vault_1 = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    check_signer(fund_owner)?;
+    check_token_program(token_program)?;
+
+    let amm_config = AccountRef::<AmmConfig>::load(amm_config_info, program_id)?;
+    if amm_config.fund_owner != *fund_owner.key {
+        return Err(ClmmError::Unauthorized.into());
+    }
+    let mut pool = AccountMut::<PoolState>::load(pool_info, program_id)?;
+    if pool.amm_config != *amm_config_info.key {
+        return Err(ClmmError::InvalidAccountData.into());
+    }
+    if *token_vault_0.key != pool.token_vault_0 || *token_vault_1.key != pool.token_vault_1 {
+        return Err(ClmmError::InvalidAccountData.into());
+    }
+
+    let amount_0 = amount_0_requested.min(pool.fund_fees_token_0);
+    let amount_1 = amount_1_requested.min(pool.fund_fees_token_1);
+    pool.fund_fees_token_0 -= amount_0;
+    pool.fund_fees_token_1 -= amount_1;
+
+    let pool_seeds = pool.seeds();
+    transfer_from_pool(
+        pool_info,
+        &pool_seeds,
+        token_vault_0,
+        recipient_token_account_0,
+        token_program,
+        amount_0,
+    )?;
+    transfer_from_pool(
+        pool_info,
+        &pool_seeds,
+        token_vault_1,
+        recipient_token_account_1,
+        token_program,
+        amount_1,
+    )?;
+
+    emit(&CollectFundFeesEvent {
+        pool: *pool_info.key,
+        fund_owner: *fund_owner.key,
+        amount_0,
+        amount_1,
+    });
+    Ok(())
+}
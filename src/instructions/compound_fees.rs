@@ -0,0 +1,111 @@
+use solana_account_info::{next_account_info, AccountInfo};
+use solana_program_error::ProgramResult;
+use solana_pubkey::Pubkey;
+
+use crate::error::ClmmError;
+use crate::events::{emit, LiquidityIncreasedEvent};
+use crate::instructions::{
+    check_deadline, check_sqrt_price_band, modify_position, Deadline, PositionTickArrays,
+    SqrtPriceBand,
+};
+use crate::libraries::liquidity_math::LiquidityQuote;
+use crate::state::{check_allowlisted, AccountMut, AllowlistEntry, PoolState, PositionState};
+use crate::utils::{check_position_nft, check_signer};
+
+/// Reinvests a position's owed fees as liquidity in the same range.
+///
+/// The fees already sit in the vaults, so no tokens move. As much of them as the
+/// current price allows becomes liquidity, and whatever is left stays owed.
+/// Fails after `deadline`, or when the pool price sits outside `sqrt_price_band`.
+///
+/// Accounts:
+/// 0. `[signer]` position owner
+/// 1. `[]` position NFT token account
+/// 2. `[writable]` pool
+/// 3. `[writable]` position
+/// 4. `[writable]` tick array holding the lower tick
+/// 5. `[writable]` tick array holding the upper tick, may repeat account 4
+/// 6. `[]` owner allowlist entry, only for permissioned pools
+pub fn process_compound_fees(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    deadline: Option<Deadline>,
+    sqrt_price_band: Option<SqrtPriceBand>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let nft_account = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let position_info = next_account_info(account_info_iter)?;
+    let tick_array_lower_info = next_account_info(account_info_iter)?;
+    let tick_array_upper_info = next_account_info(account_info_iter)?;
+    let allowlist_entry = next_account_info(account_info_iter).ok();
+
+    check_signer(owner)?;
+    check_deadline(deadline)?;
+
+    let mut pool = AccountMut::<PoolState>::load(pool_info, program_id)?;
+    let mut position = AccountMut::<PositionState>::load(position_info, program_id)?;
+    if position.pool_id != *pool_info.key {
+        return Err(ClmmError::PositionNotFound.into());
+    }
+    check_position_nft(nft_account, &position.nft_mint, owner.key)?;
+    check_allowlisted(
+        program_id,
+        pool_info.key,
+        &pool,
+        owner.key,
+        allowlist_entry,
+        AllowlistEntry::PERMISSION_LIQUIDITY,
+    )?;
+    check_sqrt_price_band(sqrt_price_band, pool.sqrt_price_x64)?;
+
+    let mut tick_arrays = PositionTickArrays::load(
+        program_id,
+        pool_info.key,
+        pool.tick_spacing,
+        position.tick_lower_index,
+        position.tick_upper_index,
+        tick_array_lower_info,
+        tick_array_upper_info,
+    )?;
+    if position.liquidity > 0 {
+        modify_position(&mut pool, &mut position, &mut tick_arrays, 0)?;
+    }
+
+    let quote = LiquidityQuote::from_amounts(
+        pool.tick_current,
+        pool.sqrt_price_x64,
+        position.tick_lower_index,
+        position.tick_upper_index,
+        position.token_fees_owed_0,
+        position.token_fees_owed_1,
+    )?;
+    if quote.liquidity == 0 {
+        return Ok(());
+    }
+    let liquidity_delta = i128::try_from(quote.liquidity).map_err(|_| ClmmError::MathOverflow)?;
+    let (amount_0, amount_1) =
+        modify_position(&mut pool, &mut position, &mut tick_arrays, liquidity_delta)?;
+
+    // The quote never charges more than the fees it was computed from
+    position.token_fees_owed_0 = position
+        .token_fees_owed_0
+        .checked_sub(amount_0)
+        .ok_or(ClmmError::MathOverflow)?;
+    position.token_fees_owed_1 = position
+        .token_fees_owed_1
+        .checked_sub(amount_1)
+        .ok_or(ClmmError::MathOverflow)?;
+
+    emit(&LiquidityIncreasedEvent {
+        pool: *pool_info.key,
+        position: *position_info.key,
+        tick_lower: position.tick_lower_index,
+        tick_upper: position.tick_upper_index,
+        liquidity: quote.liquidity,
+        amount_0,
+        amount_1,
+    });
+    Ok(())
+}
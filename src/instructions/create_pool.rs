@@ -0,0 +1,163 @@
+use solana_account_info::{next_account_info, AccountInfo};
+use solana_program_error::ProgramResult;
+use solana_program_pack::Pack;
+use solana_pubkey::Pubkey;
+use spl_token_interface::instruction as token_instruction;
+use spl_token_interface::state::Account as TokenAccount;
+
+use crate::error::ClmmError;
+use crate::events::{emit, PoolCreatedEvent};
+use crate::libraries::tick_math::get_tick_at_sqrt_price;
+use crate::state::{AccountMut, AccountRef, AmmConfig, PoolState, POOL_SEED, POOL_VAULT_SEED};
+use crate::utils::{
+    check_signer, check_system_program, check_token_program, create_account, invoke, unpack_mint,
+};
+
+/// Creates a pool for a token pair under a fee tier, along with its two vaults.
+///
+/// With a `permission_authority`, the pool is permissioned: only wallets that
+/// authority allowlists may swap or add liquidity, while withdrawals stay open.
+///
+/// Swaps are rejected before `open_time`, and for `launch_duration` seconds after it
+/// each swap may move at most `launch_max_amount_0` and `launch_max_amount_1`.
+///
+/// Accounts:
+/// 0. `[signer, writable]` creator, pays for the accounts
+/// 1. `[]` amm config, must not be disabled
+/// 2. `[writable]` pool PDA
+/// 3. `[]` token mint 0
+/// 4. `[]` token mint 1, must sort after mint 0
+/// 5. `[writable]` token vault 0 PDA
+/// 6. `[writable]` token vault 1 PDA
+/// 7. `[]` token program
+/// 8. `[]` system program
+#[allow(clippy::too_many_arguments)]
+pub fn process_create_pool(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    sqrt_price_x64: u128,
+    permission_authority: Option<Pubkey>,
+    open_time: u64,
+    launch_duration: u64,
+    launch_max_amount_0: u64,
+    launch_max_amount_1: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let creator = next_account_info(account_info_iter)?;
+    let amm_config_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let token_mint_0 = next_account_info(account_info_iter)?;
+    let token_mint_1 = next_account_info(account_info_iter)?;
+    let token_vault_0 = next_account_info(account_info_iter)?;
+    let token_vault_1 = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    check_signer(creator)?;
+    check_token_program(token_program)?;
+    check_system_program(system_program)?;
+    if token_mint_0.key >= token_mint_1.key {
+        return Err(ClmmError::InvalidTokenOrder.into());
+    }
+    let mint_0 = unpack_mint(token_mint_0)?;
+    let mint_1 = unpack_mint(token_mint_1)?;
+
+    let amm_config = AccountRef::<AmmConfig>::load(amm_config_info, program_id)?;
+    if amm_config.is_disabled() {
+        return Err(ClmmError::FeeTierDisabled.into());
+    }
+    let tick = get_tick_at_sqrt_price(sqrt_price_x64)?;
+
+    let pool_seeds: &[&[u8]] = &[
+        POOL_SEED,
+        amm_config_info.key.as_ref(),
+        token_mint_0.key.as_ref(),
+        token_mint_1.key.as_ref(),
+    ];
+    let (expected, pool_bump) = Pubkey::find_program_address(pool_seeds, program_id);
+    if expected != *pool_info.key {
+        return Err(ClmmError::InvalidPda.into());
+    }
+    if !pool_info.data_is_empty() {
+        return Err(ClmmError::PoolAlreadyInitialized.into());
+    }
+    create_account(
+        creator,
+        pool_info,
+        system_program,
+        program_id,
+        PoolState::LEN,
+        &[&[pool_seeds, &[&[pool_bump]]].concat()],
+    )?;
+
+    for (vault, mint) in [(token_vault_0, token_mint_0), (token_vault_1, token_mint_1)] {
+        let vault_seeds: &[&[u8]] = &[POOL_VAULT_SEED, pool_info.key.as_ref(), mint.key.as_ref()];
+        let (expected, vault_bump) = Pubkey::find_program_address(vault_seeds, program_id);
+        if expected != *vault.key {
+            return Err(ClmmError::InvalidPda.into());
+        }
+        create_account(
+            creator,
+            vault,
+            system_program,
+            token_program.key,
+            TokenAccount::LEN,
+            &[&[vault_seeds, &[&[vault_bump]]].concat()],
+        )?;
+        invoke(
+            &token_instruction::initialize_account3(
+                token_program.key,
+                vault.key,
+                mint.key,
+                pool_info.key,
+            )?,
+            &[vault.clone(), mint.clone(), token_program.clone()],
+        )?;
+    }
+
+    let pool = AccountMut::init(
+        pool_info,
+        program_id,
+        PoolState {
+            bump: pool_bump,
+            amm_config: *amm_config.key(),
+            creator: *creator.key,
+            token_mint_0: *token_mint_0.key,
+            token_mint_1: *token_mint_1.key,
+            token_vault_0: *token_vault_0.key,
+            token_vault_1: *token_vault_1.key,
+            mint_decimals_0: mint_0.decimals,
+            mint_decimals_1: mint_1.decimals,
+            tick_spacing: amm_config.tick_spacing,
+            sqrt_price_x64,
+            tick_current: tick,
+            status: if permission_authority.is_some() {
+                PoolState::STATUS_PERMISSIONED
+            } else {
+                0
+            },
+            permission_authority: permission_authority.unwrap_or_default(),
+            open_time,
+            launch_duration,
+            launch_max_amount_0,
+            launch_max_amount_1,
+            ..Default::default()
+        },
+    )?;
+    pool.exit()?;
+
+    emit(&PoolCreatedEvent {
+        pool: *pool_info.key,
+        amm_config: *amm_config_info.key,
+        creator: *creator.key,
+        token_mint_0: *token_mint_0.key,
+        token_mint_1: *token_mint_1.key,
+        token_vault_0: *token_vault_0.key,
+        token_vault_1: *token_vault_1.key,
+        tick_spacing: amm_config.tick_spacing,
+        sqrt_price_x64,
+        tick,
+        open_time,
+    });
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use solana_account_info::{next_account_info, AccountInfo};
+use solana_program_error::ProgramResult;
+use solana_pubkey::Pubkey;
+
+use crate::error::ClmmError;
+use crate::events::{emit, LiquidityDecreasedEvent};
+use crate::instructions::{
+    check_deadline, check_sqrt_price_band, modify_position, Deadline, PositionTickArrays,
+    SqrtPriceBand,
+};
+use crate::state::{AccountMut, PoolState, PositionState};
+use crate::utils::{check_position_nft, check_signer, check_token_program, transfer_from_pool};
+
+/// Removes `liquidity` from a position and pays out the principal.
+///
+/// Fees earned so far are credited to the position and stay owed until collected.
+/// Fails after `deadline`, or when the pool price sits outside `sqrt_price_band`.
+///
+/// Accounts:
+/// 0. `[signer]` position owner
+/// 1. `[]` position NFT token account
+/// 2. `[writable]` pool
+/// 3. `[writable]` position
+/// 4. `[writable]` tick array holding the lower tick
+/// 5. `[writable]` tick array holding the upper tick, may repeat account 4
+/// 6. `[writable]` recipient token account 0
+/// 7. `[writable]` recipient token account 1
+/// 8. `[writable]` token vault 0
+/// 9. `[writable]` token vault 1
+/// 10. `[]` token program
+pub fn process_decrease_liquidity(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    liquidity: u128,
+    amount_0_min: u64,
+    amount_1_min: u64,
+    deadline: Option<Deadline>,
+    sqrt_price_band: Option<SqrtPriceBand>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let nft_account = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let position_info = next_account_info(account_info_iter)?;
+    let tick_array_lower_info = next_account_info(account_info_iter)?;
+    let tick_array_upper_info = next_account_info(account_info_iter)?;
+    let recipient_token_account_0 = next_account_info(account_info_iter)?;
+    let recipient_token_account_1 = next_account_info(account_info_iter)?;
+    let token_vault_0 = next_account_info(account_info_iter)?;
+    let token_vault_1 = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    check_signer(owner)?;
+    check_token_program(token_program)?;
+    check_deadline(deadline)?;
+
+    let mut pool = AccountMut::<PoolState>::load(pool_info, program_id)?;
+    let mut position = AccountMut::<PositionState>::load(position_info, program_id)?;
+    if position.pool_id != *pool_info.key {
+        return Err(ClmmError::PositionNotFound.into());
+    }
+    check_position_nft(nft_account, &position.nft_mint, owner.key)?;
+    if *token_vault_0.key != pool.token_vault_0 || *token_vault_1.key != pool.token_vault_1 {
+        return Err(ClmmError::InvalidAccountData.into());
+    }
+    check_sqrt_price_band(sqrt_price_band, pool.sqrt_price_x64)?;
+    if liquidity == 0 {
+        return Err(ClmmError::ZeroAmount.into());
+    }
+    if liquidity > position.liquidity {
+        return Err(ClmmError::InsufficientLiquidity.into());
+    }
+    let liquidity_delta = -i128::try_from(liquidity).map_err(|_| ClmmError::MathOverflow)?;
+
+    let mut tick_arrays = PositionTickArrays::load(
+        program_id,
+        pool_info.key,
+        pool.tick_spacing,
+        position.tick_lower_index,
+        position.tick_upper_index,
+        tick_array_lower_info,
+        tick_array_upper_info,
+    )?;
+    let (amount_0, amount_1) =
+        modify_position(&mut pool, &mut position, &mut tick_arrays, liquidity_delta)?;
+    if amount_0 < amount_0_min || amount_1 < amount_1_min {
+        return Err(ClmmError::SlippageExceeded.into());
+    }
+
+    let pool_seeds = pool.seeds();
+    transfer_from_pool(
+        pool_info,
+        &pool_seeds,
+        token_vault_0,
+        recipient_token_account_0,
+        token_program,
+        amount_0,
+    )?;
+    transfer_from_pool(
+        pool_info,
+        &pool_seeds,
+        token_vault_1,
+        recipient_token_account_1,
+        token_program,
+        amount_1,
+    )?;
+
+    emit(&LiquidityDecreasedEvent {
+        pool: *pool_info.key,
+        position: *position_info.key,
+        tick_lower: position.tick_lower_index,
+        tick_upper: position.tick_upper_index,
+        liquidity,
+        amount_0,
+        amount_1,
+    });
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_clock::Clock;
+use solana_program_error::ProgramResult;
+use solana_sysvar::Sysvar;
+
+use crate::error::ClmmError;
+
+/// Last moment a signed instruction may still execute, inclusive.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Deadline {
+    UnixTimestamp(i64),
+    Slot(u64),
+}
+
+impl Deadline {
+    pub fn check(&self, clock: &Clock) -> Result<(), ClmmError> {
+        let expired = match *self {
+            Deadline::UnixTimestamp(deadline) => clock.unix_timestamp > deadline,
+            Deadline::Slot(deadline) => clock.slot > deadline,
+        };
+        if expired {
+            return Err(ClmmError::DeadlineExceeded);
+        }
+        Ok(())
+    }
+}
+
+/// Pool prices a liquidity op accepts, both bounds inclusive.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SqrtPriceBand {
+    pub min_sqrt_price_x64: u128,
+    pub max_sqrt_price_x64: u128,
+}
+
+impl SqrtPriceBand {
+    pub fn check(&self, sqrt_price_x64: u128) -> Result<(), ClmmError> {
+        if sqrt_price_x64 < self.min_sqrt_price_x64 || sqrt_price_x64 > self.max_sqrt_price_x64 {
+            return Err(ClmmError::PriceOutOfBand);
+        }
+        Ok(())
+    }
+}
+
+/// Fails once `deadline` has passed. The clock is only read when a deadline is set.
+pub fn check_deadline(deadline: Option<Deadline>) -> ProgramResult {
+    if let Some(deadline) = deadline {
+        deadline.check(&Clock::get()?)?;
+    }
+    Ok(())
+}
+
+pub fn check_sqrt_price_band(band: Option<SqrtPriceBand>, sqrt_price_x64: u128) -> ProgramResult {
+    if let Some(band) = band {
+        band.check(sqrt_price_x64)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deadline() {
+        let clock = Clock {
+            slot: 100,
+            unix_timestamp: 1_000,
+            ..Default::default()
+        };
+        assert_eq!(Deadline::UnixTimestamp(1_000).check(&clock), Ok(()));
+        assert_eq!(
+            Deadline::UnixTimestamp(999).check(&clock),
+            Err(ClmmError::DeadlineExceeded)
+        );
+        assert_eq!(Deadline::Slot(100).check(&clock), Ok(()));
+        assert_eq!(
+            Deadline::Slot(99).check(&clock),
+            Err(ClmmError::DeadlineExceeded)
+        );
+    }
+
+    #[test]
+    fn test_sqrt_price_band() {
+        let band = SqrtPriceBand {
+            min_sqrt_price_x64: 10,
+            max_sqrt_price_x64: 20,
+        };
+        assert_eq!(band.check(10), Ok(()));
+        assert_eq!(band.check(20), Ok(()));
+        assert_eq!(band.check(9), Err(ClmmError::PriceOutOfBand));
+        assert_eq!(band.check(21), Err(ClmmError::PriceOutOfBand));
+    }
+}
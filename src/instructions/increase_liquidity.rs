@@ -0,0 +1,121 @@
+use solana_account_info::{next_account_info, AccountInfo};
+use solana_program_error::ProgramResult;
+use solana_pubkey::Pubkey;
+
+use crate::error::ClmmError;
+use crate::events::{emit, LiquidityIncreasedEvent};
+use crate::instructions::{
+    check_deadline, check_sqrt_price_band, modify_position, Deadline, PositionTickArrays,
+    SqrtPriceBand,
+};
+use crate::state::{check_allowlisted, AccountMut, AllowlistEntry, PoolState, PositionState};
+use crate::utils::{check_position_nft, check_signer, check_token_program, transfer_from_user};
+
+/// Adds `liquidity` to an existing position.
+///
+/// Fails after `deadline`, or when the pool price sits outside `sqrt_price_band`.
+///
+/// Accounts:
+/// 0. `[signer]` position owner
+/// 1. `[]` position NFT token account
+/// 2. `[writable]` pool
+/// 3. `[writable]` position
+/// 4. `[writable]` tick array holding the lower tick
+/// 5. `[writable]` tick array holding the upper tick, may repeat account 4
+/// 6. `[writable]` owner token account 0
+/// 7. `[writable]` owner token account 1
+/// 8. `[writable]` token vault 0
+/// 9. `[writable]` token vault 1
+/// 10. `[]` token program
+/// 11. `[]` owner allowlist entry, only for permissioned pools
+pub fn process_increase_liquidity(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    liquidity: u128,
+    amount_0_max: u64,
+    amount_1_max: u64,
+    deadline: Option<Deadline>,
+    sqrt_price_band: Option<SqrtPriceBand>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let nft_account = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let position_info = next_account_info(account_info_iter)?;
+    let tick_array_lower_info = next_account_info(account_info_iter)?;
+    let tick_array_upper_info = next_account_info(account_info_iter)?;
+    let token_account_0 = next_account_info(account_info_iter)?;
+    let token_account_1 = next_account_info(account_info_iter)?;
+    let token_vault_0 = next_account_info(account_info_iter)?;
+    let token_vault_1 = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let allowlist_entry = next_account_info(account_info_iter).ok();
+
+    check_signer(owner)?;
+    check_token_program(token_program)?;
+    check_deadline(deadline)?;
+
+    let mut pool = AccountMut::<PoolState>::load(pool_info, program_id)?;
+    let mut position = AccountMut::<PositionState>::load(position_info, program_id)?;
+    if position.pool_id != *pool_info.key {
+        return Err(ClmmError::PositionNotFound.into());
+    }
+    check_position_nft(nft_account, &position.nft_mint, owner.key)?;
+    check_allowlisted(
+        program_id,
+        pool_info.key,
+        &pool,
+        owner.key,
+        allowlist_entry,
+        AllowlistEntry::PERMISSION_LIQUIDITY,
+    )?;
+    if *token_vault_0.key != pool.token_vault_0 || *token_vault_1.key != pool.token_vault_1 {
+        return Err(ClmmError::InvalidAccountData.into());
+    }
+    check_sqrt_price_band(sqrt_price_band, pool.sqrt_price_x64)?;
+    if liquidity == 0 {
+        return Err(ClmmError::ZeroAmount.into());
+    }
+    let liquidity_delta = i128::try_from(liquidity).map_err(|_| ClmmError::MathOverflow)?;
+
+    let mut tick_arrays = PositionTickArrays::load(
+        program_id,
+        pool_info.key,
+        pool.tick_spacing,
+        position.tick_lower_index,
+        position.tick_upper_index,
+        tick_array_lower_info,
+        tick_array_upper_info,
+    )?;
+    let (amount_0, amount_1) =
+        modify_position(&mut pool, &mut position, &mut tick_arrays, liquidity_delta)?;
+    if amount_0 > amount_0_max || amount_1 > amount_1_max {
+        return Err(ClmmError::SlippageExceeded.into());
+    }
+
+    transfer_from_user(
+        owner,
+        token_account_0,
+        token_vault_0,
+        token_program,
+        amount_0,
+    )?;
+    transfer_from_user(
+        owner,
+        token_account_1,
+        token_vault_1,
+        token_program,
+        amount_1,
+    )?;
+
+    emit(&LiquidityIncreasedEvent {
+        pool: *pool_info.key,
+        position: *position_info.key,
+        tick_lower: position.tick_lower_index,
+        tick_upper: position.tick_upper_index,
+        liquidity,
+        amount_0,
+        amount_1,
+    });
+    Ok(())
+}
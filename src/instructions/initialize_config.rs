@@ -0,0 +1,101 @@
+use solana_account_info::{next_account_info, AccountInfo};
+use solana_program_error::ProgramResult;
+use solana_pubkey::Pubkey;
+
+use crate::error::ClmmError;
+use crate::state::{
+    AccountMut, AmmConfig, FeeTier, FeeTierRegistry, CONFIG_SEED, FEE_RATE_DENOMINATOR,
+    FEE_TIER_REGISTRY_SEED,
+};
+use crate::utils::{check_signer, check_system_program, create_account};
+
+/// Creates a fee tier and lists it in the registry. The signer becomes the config admin.
+///
+/// The protocol, fund and referral fee rates are shares of the trade fee, LPs keep the rest.
+/// Each swap picks its own referral share, up to `max_referral_fee_rate`.
+///
+/// Accounts:
+/// 0. `[signer, writable]` registry admin, pays for the account
+/// 1. `[writable]` amm config PDA
+/// 2. `[writable]` fee tier registry
+/// 3. `[]` system program
+#[allow(clippy::too_many_arguments)]
+pub fn process_initialize_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    index: u16,
+    trade_fee_rate: u32,
+    protocol_fee_rate: u32,
+    fund_fee_rate: u32,
+    fund_owner: Pubkey,
+    max_referral_fee_rate: u32,
+    tick_spacing: u16,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let admin = next_account_info(account_info_iter)?;
+    let amm_config_info = next_account_info(account_info_iter)?;
+    let registry_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    check_signer(admin)?;
+    check_system_program(system_program)?;
+    let mut registry = AccountMut::<FeeTierRegistry>::load(registry_info, program_id)?
+        .verify_pda(program_id, &[FEE_TIER_REGISTRY_SEED])?;
+    if registry.admin != *admin.key {
+        return Err(ClmmError::Unauthorized.into());
+    }
+    if tick_spacing == 0 {
+        return Err(ClmmError::InvalidTickSpacing.into());
+    }
+    if trade_fee_rate >= FEE_RATE_DENOMINATOR
+        || protocol_fee_rate as u64 + fund_fee_rate as u64 + max_referral_fee_rate as u64
+            > FEE_RATE_DENOMINATOR as u64
+    {
+        return Err(ClmmError::InvalidFeeRate.into());
+    }
+
+    let index_bytes = index.to_be_bytes();
+    let (expected, bump) = Pubkey::find_program_address(&[CONFIG_SEED, &index_bytes], program_id);
+    if expected != *amm_config_info.key {
+        return Err(ClmmError::InvalidPda.into());
+    }
+    registry.add_tier(FeeTier {
+        amm_config: expected,
+        index,
+        tick_spacing,
+        trade_fee_rate,
+        ..Default::default()
+    })?;
+
+    create_account(
+        admin,
+        amm_config_info,
+        system_program,
+        program_id,
+        AmmConfig::LEN,
+        &[&[CONFIG_SEED, &index_bytes, &[bump]]],
+    )?;
+
+    let amm_config = AccountMut::init(
+        amm_config_info,
+        program_id,
+        AmmConfig {
+            discriminator: AmmConfig::DISCRIMINATOR,
+            version: AmmConfig::VERSION,
+            bump,
+            index,
+            admin: *admin.key,
+            trade_fee_rate,
+            protocol_fee_rate,
+            tick_spacing,
+            status: 0,
+            padding: [0; 7],
+            fund_fee_rate,
+            fund_owner,
+            max_referral_fee_rate,
+            padding_1: [0; 16],
+        },
+    )?;
+    amm_config.exit()?;
+    registry.exit()
+}
@@ -0,0 +1,59 @@
+use solana_account_info::{next_account_info, AccountInfo};
+use solana_program_error::ProgramResult;
+use solana_pubkey::Pubkey;
+
+use crate::error::ClmmError;
+use crate::state::{AccountMut, FeeTierRegistry, FEE_TIER_REGISTRY_SEED};
+use crate::utils::{check_signer, check_system_program, check_upgrade_authority, create_account};
+
+/// Creates the fee tier registry. The signer becomes the admin allowed to add fee tiers.
+///
+/// Only the program's upgrade authority may create it, so the admin cannot be
+/// claimed by whoever calls first after a deploy.
+///
+/// Accounts:
+/// 0. `[signer, writable]` admin, the program's upgrade authority, pays for the account
+/// 1. `[writable]` fee tier registry PDA
+/// 2. `[]` system program
+/// 3. `[]` this program's ProgramData account
+pub fn process_initialize_fee_tier_registry(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let admin = next_account_info(account_info_iter)?;
+    let registry_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let program_data = next_account_info(account_info_iter)?;
+
+    check_signer(admin)?;
+    check_system_program(system_program)?;
+    check_upgrade_authority(program_id, program_data, admin)?;
+
+    let (expected, bump) = Pubkey::find_program_address(&[FEE_TIER_REGISTRY_SEED], program_id);
+    if expected != *registry_info.key {
+        return Err(ClmmError::InvalidPda.into());
+    }
+
+    create_account(
+        admin,
+        registry_info,
+        system_program,
+        program_id,
+        FeeTierRegistry::LEN,
+        &[&[FEE_TIER_REGISTRY_SEED, &[bump]]],
+    )?;
+
+    let registry = AccountMut::init(
+        registry_info,
+        program_id,
+        FeeTierRegistry {
+            discriminator: FeeTierRegistry::DISCRIMINATOR,
+            version: FeeTierRegistry::VERSION,
+            bump,
+            admin: *admin.key,
+            ..Default::default()
+        },
+    )?;
+    registry.exit()
+}
@@ -0,0 +1,79 @@
+use solana_account_info::{next_account_info, AccountInfo};
+use solana_program_error::ProgramResult;
+use solana_pubkey::Pubkey;
+
+use crate::error::ClmmError;
+use crate::state::{AccountMut, PositionBundleState, POSITION_BUNDLE_SEED};
+use crate::utils::{
+    check_signer, check_system_program, check_token_program, create_account, mint_position_nft,
+};
+
+/// Creates a position bundle and mints its NFT to the payer.
+///
+/// Whoever holds the bundle NFT owns every position in the bundle.
+///
+/// Accounts:
+/// 0. `[signer, writable]` payer, receives the bundle NFT
+/// 1. `[signer, writable]` bundle NFT mint, a new account
+/// 2. `[signer, writable]` bundle NFT token account, a new account
+/// 3. `[writable]` position bundle PDA
+/// 4. `[]` token program
+/// 5. `[]` system program
+pub fn process_initialize_position_bundle(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let payer = next_account_info(account_info_iter)?;
+    let bundle_mint = next_account_info(account_info_iter)?;
+    let bundle_token_account = next_account_info(account_info_iter)?;
+    let bundle_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    check_signer(payer)?;
+    check_signer(bundle_mint)?;
+    check_signer(bundle_token_account)?;
+    check_token_program(token_program)?;
+    check_system_program(system_program)?;
+
+    let (expected, bump) = Pubkey::find_program_address(
+        &[POSITION_BUNDLE_SEED, bundle_mint.key.as_ref()],
+        program_id,
+    );
+    if expected != *bundle_info.key {
+        return Err(ClmmError::InvalidPda.into());
+    }
+    let bundle_seeds: &[&[u8]] = &[POSITION_BUNDLE_SEED, bundle_mint.key.as_ref(), &[bump]];
+    create_account(
+        payer,
+        bundle_info,
+        system_program,
+        program_id,
+        PositionBundleState::LEN,
+        &[bundle_seeds],
+    )?;
+    let bundle = AccountMut::init(
+        bundle_info,
+        program_id,
+        PositionBundleState {
+            discriminator: PositionBundleState::DISCRIMINATOR,
+            version: PositionBundleState::VERSION,
+            bump,
+            bundle_mint: *bundle_mint.key,
+            ..Default::default()
+        },
+    )?;
+    bundle.exit()?;
+
+    mint_position_nft(
+        payer,
+        payer.key,
+        bundle_mint,
+        bundle_token_account,
+        bundle_info,
+        bundle_seeds,
+        token_program,
+        system_program,
+    )
+}
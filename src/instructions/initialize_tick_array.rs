@@ -0,0 +1,54 @@
+use solana_account_info::{next_account_info, AccountInfo};
+use solana_program_error::ProgramResult;
+use solana_pubkey::Pubkey;
+
+use crate::error::ClmmError;
+use crate::instructions::init_tick_array;
+use crate::state::{AccountRef, PoolState, TickArrayState};
+use crate::utils::{check_signer, check_system_program};
+
+/// Creates the pool's tick array starting at `start_tick_index`, at the payer's expense.
+///
+/// Anyone may call this, and calling it for an array that already exists does nothing.
+/// The array holds no initialized ticks until a position uses them, so the pool's
+/// tick array bitmap is left untouched.
+///
+/// Accounts:
+/// 0. `[signer, writable]` payer, refunded when the array is closed
+/// 1. `[]` pool
+/// 2. `[writable]` tick array PDA
+/// 3. `[]` system program
+pub fn process_initialize_tick_array(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    start_tick_index: i32,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let payer = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let tick_array_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    check_signer(payer)?;
+    check_system_program(system_program)?;
+    let pool = AccountRef::<PoolState>::load(pool_info, program_id)?;
+
+    if tick_array_info.owner == program_id {
+        let tick_array = AccountRef::<TickArrayState>::load(tick_array_info, program_id)?;
+        if tick_array.pool_id != *pool_info.key || tick_array.start_tick_index != start_tick_index {
+            return Err(ClmmError::InvalidTickArray.into());
+        }
+        return Ok(());
+    }
+
+    init_tick_array(
+        program_id,
+        pool_info.key,
+        tick_array_info,
+        start_tick_index,
+        pool.tick_spacing,
+        payer,
+        system_program,
+    )?
+    .exit()
+}
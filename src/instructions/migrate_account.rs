@@ -0,0 +1,251 @@
+use solana_account_info::{next_account_info, AccountInfo};
+use solana_program_error::{ProgramError, ProgramResult};
+use solana_pubkey::Pubkey;
+use solana_rent::Rent;
+use solana_system_interface::instruction as system_instruction;
+use solana_sysvar::Sysvar;
+
+use crate::error::ClmmError;
+use crate::state::{
+    AccountRef, AccountState, AmmConfig, PoolState, PositionState, TickArrayState, VERSION_OFFSET,
+};
+use crate::utils::invoke;
+
+/// Upgrades a program account by one layout version.
+///
+/// A layout becomes migratable once its type implements [`AccountState::migrate`]
+/// for the stored version and raises its `VERSION`.
+///
+/// Accounts:
+/// 0. `[signer]` config admin
+/// 1. `[signer, writable]` payer for any extra rent
+/// 2. `[]` amm config the target belongs to
+/// 3. `[writable]` account to migrate
+/// 4. `[]` system program
+/// 5. `[]` pool, required when migrating a position or tick array
+pub fn process_migrate_account(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let authority = next_account_info(account_info_iter)?;
+    let payer = next_account_info(account_info_iter)?;
+    let amm_config_info = next_account_info(account_info_iter)?;
+    let target = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter).ok();
+
+    if !authority.is_signer || !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if target.owner != program_id {
+        return Err(ClmmError::InvalidAccountOwner.into());
+    }
+    if !target.is_writable {
+        return Err(ClmmError::AccountNotWritable.into());
+    }
+
+    let discriminator: [u8; 8] = target
+        .try_borrow_data()?
+        .get(..8)
+        .and_then(|d| d.try_into().ok())
+        .ok_or(ClmmError::InvalidAccountData)?;
+
+    let upgraded = if discriminator == AmmConfig::DISCRIMINATOR {
+        upgrade::<AmmConfig>(target)?
+    } else if discriminator == PoolState::DISCRIMINATOR {
+        upgrade::<PoolState>(target)?
+    } else if discriminator == PositionState::DISCRIMINATOR {
+        upgrade::<PositionState>(target)?
+    } else if discriminator == TickArrayState::DISCRIMINATOR {
+        upgrade::<TickArrayState>(target)?
+    } else {
+        return Err(ClmmError::InvalidAccountData.into());
+    };
+
+    // Authority is settled before anything is written. When the target is the
+    // config or pool itself it is read from its upgraded layout.
+    let admin = if discriminator == AmmConfig::DISCRIMINATOR {
+        if target.key != amm_config_info.key {
+            return Err(ClmmError::InvalidAccountData.into());
+        }
+        decode::<AmmConfig>(&upgraded)?.admin
+    } else {
+        AccountRef::<AmmConfig>::load(amm_config_info, program_id)?.admin
+    };
+    if admin != *authority.key {
+        return Err(ClmmError::Unauthorized.into());
+    }
+
+    if discriminator != AmmConfig::DISCRIMINATOR {
+        let pool_amm_config = if discriminator == PoolState::DISCRIMINATOR {
+            decode::<PoolState>(&upgraded)?.amm_config
+        } else {
+            let pool_id = if discriminator == PositionState::DISCRIMINATOR {
+                decode::<PositionState>(&upgraded)?.pool_id
+            } else {
+                decode::<TickArrayState>(&upgraded)?.pool_id
+            };
+            let pool_info = pool_info.ok_or(ProgramError::NotEnoughAccountKeys)?;
+            if *pool_info.key != pool_id {
+                return Err(ClmmError::InvalidAccountData.into());
+            }
+            AccountRef::<PoolState>::load(pool_info, program_id)?.amm_config
+        };
+        if pool_amm_config != *amm_config_info.key {
+            return Err(ClmmError::InvalidAccountData.into());
+        }
+    }
+
+    store(target, payer, system_program, &upgraded)
+}
+
+/// Produces `target`'s data in the layout one version above the stored one.
+fn upgrade<T: AccountState>(target: &AccountInfo) -> Result<Vec<u8>, ProgramError> {
+    let data = target.try_borrow_data()?;
+    let version = *data
+        .get(VERSION_OFFSET)
+        .ok_or(ClmmError::InvalidAccountData)?;
+    if version >= T::VERSION {
+        return Err(ClmmError::UnsupportedAccountVersion.into());
+    }
+    T::migrate(version, &data)
+}
+
+/// Deserializes upgraded account data that has not been written back yet.
+fn decode<T: AccountState>(data: &[u8]) -> Result<Box<T>, ProgramError> {
+    let state = T::deserialize(&mut &data[..]).map_err(|_| ClmmError::InvalidAccountData)?;
+    Ok(Box::new(state))
+}
+
+/// Writes `upgraded` over `target`, growing the account and topping up
+/// rent when the new layout is larger.
+fn store<'info>(
+    target: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    upgraded: &[u8],
+) -> ProgramResult {
+    if upgraded.len() > target.data_len() {
+        let required = Rent::get()?.minimum_balance(upgraded.len());
+        let top_up = required.saturating_sub(target.lamports());
+        if top_up > 0 {
+            invoke(
+                &system_instruction::transfer(payer.key, target.key, top_up),
+                &[payer.clone(), target.clone(), system_program.clone()],
+            )?;
+        }
+        target.resize(upgraded.len())?;
+    }
+
+    target.try_borrow_mut_data()?[..upgraded.len()].copy_from_slice(upgraded);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use borsh::{BorshDeserialize, BorshSerialize};
+
+    use super::*;
+
+    /// Layout version 0 of [`Fixture`], before `scale` was carved out of padding.
+    #[derive(BorshSerialize, BorshDeserialize)]
+    struct FixtureV0 {
+        discriminator: [u8; 8],
+        version: u8,
+        amount: u64,
+        padding: [u8; 16],
+    }
+
+    #[derive(BorshSerialize, BorshDeserialize)]
+    struct Fixture {
+        discriminator: [u8; 8],
+        version: u8,
+        amount: u64,
+        scale: u64,
+        padding: [u8; 8],
+    }
+
+    impl AccountState for Fixture {
+        const DISCRIMINATOR: [u8; 8] = *b"fixture_";
+        const LEN: usize = 8 + 1 + 8 + 8 + 8;
+        const VERSION: u8 = 1;
+
+        fn migrate(from_version: u8, data: &[u8]) -> Result<Vec<u8>, ProgramError> {
+            if from_version != 0 {
+                return Err(ClmmError::UnsupportedAccountVersion.into());
+            }
+            let old = FixtureV0::deserialize(&mut &data[..])
+                .map_err(|_| ClmmError::InvalidAccountData)?;
+            let new = Fixture {
+                discriminator: old.discriminator,
+                version: 1,
+                amount: old.amount,
+                scale: 1,
+                padding: [0; 8],
+            };
+            borsh::to_vec(&new).map_err(|_| ClmmError::InvalidAccountData.into())
+        }
+    }
+
+    #[test]
+    fn test_upgrade_rewrites_stale_layout_once() {
+        let program_id = Pubkey::new_unique();
+        let key = Pubkey::new_unique();
+        let payer_key = Pubkey::new_unique();
+        let system_key = Pubkey::default();
+        let mut lamports = 0;
+        let mut payer_lamports = 0;
+        let mut system_lamports = 0;
+        let mut data = borsh::to_vec(&FixtureV0 {
+            discriminator: Fixture::DISCRIMINATOR,
+            version: 0,
+            amount: 42,
+            padding: [0; 16],
+        })
+        .unwrap();
+        let mut payer_data = vec![];
+        let mut system_data = vec![];
+        let target = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &program_id,
+            false,
+        );
+        let payer = AccountInfo::new(
+            &payer_key,
+            true,
+            true,
+            &mut payer_lamports,
+            &mut payer_data,
+            &system_key,
+            false,
+        );
+        let system_program = AccountInfo::new(
+            &system_key,
+            false,
+            false,
+            &mut system_lamports,
+            &mut system_data,
+            &system_key,
+            true,
+        );
+
+        let err = AccountRef::<Fixture>::load(&target, &program_id)
+            .err()
+            .unwrap();
+        assert_eq!(err, ClmmError::AccountMigrationRequired.into());
+
+        let upgraded = upgrade::<Fixture>(&target).unwrap();
+        store(&target, &payer, &system_program, &upgraded).unwrap();
+
+        let fixture = AccountRef::<Fixture>::load(&target, &program_id).unwrap();
+        assert_eq!(fixture.version, Fixture::VERSION);
+        assert_eq!(fixture.amount, 42);
+        assert_eq!(fixture.scale, 1);
+        drop(fixture);
+
+        let err = upgrade::<Fixture>(&target).unwrap_err();
+        assert_eq!(err, ClmmError::UnsupportedAccountVersion.into());
+    }
+}
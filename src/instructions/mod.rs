@@ -1,7 +1,55 @@
+pub mod add_to_allowlist;
+pub mod close_bundled_position;
+pub mod close_position;
+pub mod close_position_bundle;
+pub mod close_tick_array;
+pub mod collect_fees;
+pub mod collect_fund_fees;
+pub mod compound_fees;
 pub mod create_pool;
+pub mod decrease_liquidity;
+pub mod guards;
+pub mod increase_liquidity;
+pub mod initialize_config;
+pub mod initialize_fee_tier_registry;
+pub mod initialize_position_bundle;
+pub mod initialize_tick_array;
+pub mod migrate_account;
+pub mod modify_position;
+pub mod open_bundled_position;
 pub mod open_position;
+pub mod quote_swap;
+pub mod remove_from_allowlist;
+pub mod reposition;
+pub mod set_fee_tier_enabled;
+pub mod set_max_referral_fee_rate;
+pub mod set_pool_fee_rate;
 pub mod swap;
 
+pub use add_to_allowlist::*;
+pub use close_bundled_position::*;
+pub use close_position::*;
+pub use close_position_bundle::*;
+pub use close_tick_array::*;
+pub use collect_fees::*;
+pub use collect_fund_fees::*;
+pub use compound_fees::*;
 pub use create_pool::*;
+pub use decrease_liquidity::*;
+pub use guards::*;
+pub use increase_liquidity::*;
+pub use initialize_config::*;
+pub use initialize_fee_tier_registry::*;
+pub use initialize_position_bundle::*;
+pub use initialize_tick_array::*;
+pub use migrate_account::*;
+pub use modify_position::*;
+pub use open_bundled_position::*;
 pub use open_position::*;
+pub use quote_swap::*;
+pub use remove_from_allowlist::*;
+pub use reposition::*;
+pub use set_fee_tier_enabled::*;
+pub use set_max_referral_fee_rate::*;
+pub use set_pool_fee_rate::*;
 pub use swap::*;
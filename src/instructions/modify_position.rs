@@ -0,0 +1,323 @@
+use solana_account_info::AccountInfo;
+use solana_program_error::{ProgramError, ProgramResult};
+use solana_pubkey::Pubkey;
+
+use crate::error::ClmmError;
+use crate::libraries::full_math::mul_div;
+use crate::libraries::liquidity_math::{add_delta, get_delta_amounts_signed, Q64};
+use crate::libraries::tick_math::{check_tick_aligned, check_tick_in_bounds};
+use crate::state::{
+    get_fee_growth_inside, AccountMut, PoolState, PositionState, TickArrayState, TICK_ARRAY_SEED,
+};
+use crate::utils::create_account;
+
+/// Tick arrays holding a position's lower and upper tick.
+///
+/// Both ticks may live in the same array, which is then loaded only once.
+pub struct PositionTickArrays<'a, 'info> {
+    lower: AccountMut<'a, 'info, TickArrayState>,
+    upper: Option<AccountMut<'a, 'info, TickArrayState>>,
+}
+
+impl<'a, 'info> PositionTickArrays<'a, 'info> {
+    /// Loads the arrays for `[tick_lower, tick_upper)` and checks they belong to `pool`.
+    pub fn load(
+        program_id: &Pubkey,
+        pool: &Pubkey,
+        tick_spacing: u16,
+        tick_lower: i32,
+        tick_upper: i32,
+        lower_info: &'a AccountInfo<'info>,
+        upper_info: &'a AccountInfo<'info>,
+    ) -> Result<Self, ProgramError> {
+        Self::load_with(
+            tick_spacing,
+            tick_lower,
+            tick_upper,
+            lower_info,
+            upper_info,
+            |info, start| load_tick_array(program_id, pool, info, start),
+        )
+    }
+
+    /// Like [`PositionTickArrays::load`], creating arrays that do not exist yet.
+    #[allow(clippy::too_many_arguments)]
+    pub fn load_or_create(
+        program_id: &Pubkey,
+        pool: &Pubkey,
+        tick_spacing: u16,
+        tick_lower: i32,
+        tick_upper: i32,
+        lower_info: &'a AccountInfo<'info>,
+        upper_info: &'a AccountInfo<'info>,
+        payer: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+    ) -> Result<Self, ProgramError> {
+        Self::load_with(
+            tick_spacing,
+            tick_lower,
+            tick_upper,
+            lower_info,
+            upper_info,
+            |info, start| {
+                if info.owner == program_id {
+                    load_tick_array(program_id, pool, info, start)
+                } else {
+                    init_tick_array(
+                        program_id,
+                        pool,
+                        info,
+                        start,
+                        tick_spacing,
+                        payer,
+                        system_program,
+                    )
+                }
+            },
+        )
+    }
+
+    fn load_with(
+        tick_spacing: u16,
+        tick_lower: i32,
+        tick_upper: i32,
+        lower_info: &'a AccountInfo<'info>,
+        upper_info: &'a AccountInfo<'info>,
+        mut load: impl FnMut(
+            &'a AccountInfo<'info>,
+            i32,
+        ) -> Result<AccountMut<'a, 'info, TickArrayState>, ProgramError>,
+    ) -> Result<Self, ProgramError> {
+        let start_lower = TickArrayState::get_array_start_index(tick_lower, tick_spacing as i32);
+        let start_upper = TickArrayState::get_array_start_index(tick_upper, tick_spacing as i32);
+
+        let lower = load(lower_info, start_lower)?;
+        let upper = if lower_info.key == upper_info.key {
+            if start_lower != start_upper {
+                return Err(ClmmError::InvalidTickArray.into());
+            }
+            None
+        } else {
+            Some(load(upper_info, start_upper)?)
+        };
+        Ok(Self { lower, upper })
+    }
+
+    pub fn lower_mut(&mut self) -> &mut TickArrayState {
+        &mut self.lower
+    }
+
+    pub fn upper_mut(&mut self) -> &mut TickArrayState {
+        match &mut self.upper {
+            Some(upper) => upper,
+            None => &mut self.lower,
+        }
+    }
+
+    /// Writes both arrays back, so the same accounts can be loaded again.
+    pub fn exit(self) -> ProgramResult {
+        self.lower.exit()?;
+        match self.upper {
+            Some(upper) => upper.exit(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Checks `[tick_lower, tick_upper)` is a valid position range for the tick spacing.
+pub fn check_position_range(
+    tick_lower: i32,
+    tick_upper: i32,
+    tick_spacing: u16,
+) -> Result<(), ClmmError> {
+    if tick_lower >= tick_upper {
+        return Err(ClmmError::InvalidTickRange);
+    }
+    if !check_tick_in_bounds(tick_lower) || !check_tick_in_bounds(tick_upper) {
+        return Err(ClmmError::TickOutOfBounds);
+    }
+    let tick_spacing = tick_spacing as i32;
+    if !check_tick_aligned(tick_lower, tick_spacing)
+        || !check_tick_aligned(tick_upper, tick_spacing)
+    {
+        return Err(ClmmError::TickNotAligned);
+    }
+    Ok(())
+}
+
+/// Loads a tick array and checks it is the pool's array starting at `start_tick_index`.
+pub fn load_tick_array<'a, 'info>(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    info: &'a AccountInfo<'info>,
+    start_tick_index: i32,
+) -> Result<AccountMut<'a, 'info, TickArrayState>, ProgramError> {
+    let tick_array = AccountMut::<TickArrayState>::load(info, program_id)?;
+    if tick_array.pool_id != *pool || tick_array.start_tick_index != start_tick_index {
+        return Err(ClmmError::InvalidTickArray.into());
+    }
+    Ok(tick_array)
+}
+
+/// Creates the pool's tick array starting at `start_tick_index` at its PDA.
+///
+/// `payer` is recorded so the rent goes back to it when the array is closed.
+pub fn init_tick_array<'a, 'info>(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    info: &'a AccountInfo<'info>,
+    start_tick_index: i32,
+    tick_spacing: u16,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<AccountMut<'a, 'info, TickArrayState>, ProgramError> {
+    if !TickArrayState::check_is_valid_start_index(start_tick_index, tick_spacing) {
+        return Err(ClmmError::InvalidTickArray.into());
+    }
+    let start = start_tick_index.to_be_bytes();
+    let (expected, bump) =
+        Pubkey::find_program_address(&[TICK_ARRAY_SEED, pool.as_ref(), &start], program_id);
+    if expected != *info.key {
+        return Err(ClmmError::InvalidPda.into());
+    }
+
+    create_account(
+        payer,
+        info,
+        system_program,
+        program_id,
+        TickArrayState::LEN,
+        &[&[TICK_ARRAY_SEED, pool.as_ref(), &start, &[bump]]],
+    )?;
+    AccountMut::init(
+        info,
+        program_id,
+        TickArrayState {
+            payer: *payer.key,
+            ..TickArrayState::new(*pool, start_tick_index, tick_spacing)
+        },
+    )
+}
+
+/// Applies a liquidity change to a position, its ticks and the pool.
+///
+/// Fees earned since the last update are credited to the position first.
+/// Returns the token amounts owed by (adding) or to (removing) the position owner,
+/// rounded in the pool's favor.
+pub fn modify_position(
+    pool: &mut PoolState,
+    position: &mut PositionState,
+    tick_arrays: &mut PositionTickArrays,
+    liquidity_delta: i128,
+) -> Result<(u64, u64), ClmmError> {
+    let tick_spacing = pool.tick_spacing;
+    let tick_current = pool.tick_current;
+    let fee_growth_global_0_x64 = pool.fee_growth_global_0_x64;
+    let fee_growth_global_1_x64 = pool.fee_growth_global_1_x64;
+
+    let mut tick_lower = *tick_arrays
+        .lower_mut()
+        .get_tick_state_mut(position.tick_lower_index, tick_spacing)?;
+    let mut tick_upper = *tick_arrays
+        .upper_mut()
+        .get_tick_state_mut(position.tick_upper_index, tick_spacing)?;
+
+    let (mut flipped_lower, mut flipped_upper) = (false, false);
+    if liquidity_delta != 0 {
+        flipped_lower = tick_lower.update(
+            tick_current,
+            liquidity_delta,
+            fee_growth_global_0_x64,
+            fee_growth_global_1_x64,
+            false,
+        )?;
+        flipped_upper = tick_upper.update(
+            tick_current,
+            liquidity_delta,
+            fee_growth_global_0_x64,
+            fee_growth_global_1_x64,
+            true,
+        )?;
+    }
+
+    let (fee_growth_inside_0_x64, fee_growth_inside_1_x64) = get_fee_growth_inside(
+        &tick_lower,
+        &tick_upper,
+        tick_current,
+        fee_growth_global_0_x64,
+        fee_growth_global_1_x64,
+    );
+
+    let fees_0 = mul_div(
+        fee_growth_inside_0_x64.wrapping_sub(position.fee_growth_inside_0_last_x64),
+        position.liquidity,
+        Q64,
+    )?;
+    let fees_1 = mul_div(
+        fee_growth_inside_1_x64.wrapping_sub(position.fee_growth_inside_1_last_x64),
+        position.liquidity,
+        Q64,
+    )?;
+    position.token_fees_owed_0 = u64::try_from(fees_0)
+        .ok()
+        .and_then(|fees| position.token_fees_owed_0.checked_add(fees))
+        .ok_or(ClmmError::MathOverflow)?;
+    position.token_fees_owed_1 = u64::try_from(fees_1)
+        .ok()
+        .and_then(|fees| position.token_fees_owed_1.checked_add(fees))
+        .ok_or(ClmmError::MathOverflow)?;
+    position.fee_growth_inside_0_last_x64 = fee_growth_inside_0_x64;
+    position.fee_growth_inside_1_last_x64 = fee_growth_inside_1_x64;
+    position.liquidity = add_delta(position.liquidity, liquidity_delta)?;
+
+    // Ticks no longer referenced by any position are reset
+    if liquidity_delta < 0 {
+        if flipped_lower {
+            tick_lower.clear();
+        }
+        if flipped_upper {
+            tick_upper.clear();
+        }
+    }
+
+    *tick_arrays
+        .lower_mut()
+        .get_tick_state_mut(position.tick_lower_index, tick_spacing)? = tick_lower;
+    *tick_arrays
+        .upper_mut()
+        .get_tick_state_mut(position.tick_upper_index, tick_spacing)? = tick_upper;
+
+    if flipped_lower {
+        update_initialized_ticks(pool, tick_arrays.lower_mut(), liquidity_delta > 0)?;
+    }
+    if flipped_upper {
+        update_initialized_ticks(pool, tick_arrays.upper_mut(), liquidity_delta > 0)?;
+    }
+
+    let (amount_0, amount_1) = get_delta_amounts_signed(
+        tick_current,
+        pool.sqrt_price_x64,
+        position.tick_lower_index,
+        position.tick_upper_index,
+        liquidity_delta,
+    )?;
+    // Only in-range liquidity is active
+    if position.tick_lower_index <= tick_current && tick_current < position.tick_upper_index {
+        pool.liquidity = add_delta(pool.liquidity, liquidity_delta)?;
+    }
+    Ok((amount_0.unsigned_abs(), amount_1.unsigned_abs()))
+}
+
+/// Keeps the array's initialized tick count and the pool bitmap in sync after a tick flips.
+fn update_initialized_ticks(
+    pool: &mut PoolState,
+    tick_array: &mut TickArrayState,
+    initialized: bool,
+) -> Result<(), ClmmError> {
+    tick_array.update_initialized_tick_count(initialized)?;
+    pool.set_tick_array_initialized(
+        tick_array.start_tick_index,
+        tick_array.initialized_tick_count > 0,
+    );
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use solana_account_info::{next_account_info, AccountInfo};
+use solana_program_error::ProgramResult;
+use solana_pubkey::Pubkey;
+
+use crate::error::ClmmError;
+use crate::instructions::{
+    check_deadline, open_position_account, Deadline, OpenPositionAccounts, SqrtPriceBand,
+};
+use crate::state::{AccountMut, PositionBundleState, BUNDLED_POSITION_SEED, POSITION_BUNDLE_SEED};
+use crate::utils::check_position_nft;
+
+/// Opens a position in slot `bundle_index` of a bundle and deposits `liquidity` into it.
+///
+/// The position belongs to the bundle NFT holder, so increase, decrease and collect
+/// take the bundled position and the bundle NFT token account like any other position.
+/// Fails after `deadline`, or when the pool price sits outside `sqrt_price_band`.
+///
+/// Accounts:
+/// 0. `[signer, writable]` bundle owner, pays for the position and the deposit
+/// 1. `[]` bundle NFT token account
+/// 2. `[writable]` position bundle
+/// 3. `[writable]` pool
+/// 4. `[writable]` bundled position PDA for `bundle_index`
+/// 5. `[writable]` tick array holding `tick_lower`
+/// 6. `[writable]` tick array holding `tick_upper`, may repeat account 5
+/// 7. `[writable]` owner token account 0
+/// 8. `[writable]` owner token account 1
+/// 9. `[writable]` token vault 0
+/// 10. `[writable]` token vault 1
+/// 11. `[]` token program
+/// 12. `[]` system program
+/// 13. `[]` owner allowlist entry, only for permissioned pools
+#[allow(clippy::too_many_arguments)]
+pub fn process_open_bundled_position(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    bundle_index: u16,
+    tick_lower: i32,
+    tick_upper: i32,
+    liquidity: u128,
+    amount_0_max: u64,
+    amount_1_max: u64,
+    deadline: Option<Deadline>,
+    sqrt_price_band: Option<SqrtPriceBand>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let bundle_token_account = next_account_info(account_info_iter)?;
+    let bundle_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let position_info = next_account_info(account_info_iter)?;
+    let tick_array_lower_info = next_account_info(account_info_iter)?;
+    let tick_array_upper_info = next_account_info(account_info_iter)?;
+    let token_account_0 = next_account_info(account_info_iter)?;
+    let token_account_1 = next_account_info(account_info_iter)?;
+    let token_vault_0 = next_account_info(account_info_iter)?;
+    let token_vault_1 = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let allowlist_entry = next_account_info(account_info_iter).ok();
+
+    check_deadline(deadline)?;
+
+    let bundle = AccountMut::<PositionBundleState>::load(bundle_info, program_id)?;
+    let bundle_mint = bundle.bundle_mint;
+    let mut bundle =
+        bundle.verify_pda(program_id, &[POSITION_BUNDLE_SEED, bundle_mint.as_ref()])?;
+    check_position_nft(bundle_token_account, &bundle_mint, owner.key)?;
+    bundle.open_slot(bundle_index)?;
+
+    let index_bytes = bundle_index.to_be_bytes();
+    let (expected, position_bump) = Pubkey::find_program_address(
+        &[BUNDLED_POSITION_SEED, bundle_mint.as_ref(), &index_bytes],
+        program_id,
+    );
+    if expected != *position_info.key {
+        return Err(ClmmError::InvalidPda.into());
+    }
+
+    open_position_account(
+        program_id,
+        &OpenPositionAccounts {
+            payer: owner,
+            pool: pool_info,
+            position: position_info,
+            tick_array_lower: tick_array_lower_info,
+            tick_array_upper: tick_array_upper_info,
+            token_account_0,
+            token_account_1,
+            token_vault_0,
+            token_vault_1,
+            token_program,
+            system_program,
+            allowlist_entry,
+        },
+        &bundle_mint,
+        &[
+            BUNDLED_POSITION_SEED,
+            bundle_mint.as_ref(),
+            &index_bytes,
+            &[position_bump],
+        ],
+        tick_lower,
+        tick_upper,
+        liquidity,
+        amount_0_max,
+        amount_1_max,
+        sqrt_price_band,
+    )?;
+    bundle.exit()
+}
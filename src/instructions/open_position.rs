@@ -0,0 +1,269 @@
+use solana_account_info::{next_account_info, AccountInfo};
+use solana_program_error::ProgramResult;
+use solana_pubkey::Pubkey;
+
+use crate::error::ClmmError;
+use crate::events::{emit, LiquidityIncreasedEvent, PositionOpenedEvent};
+use crate::instructions::{
+    check_deadline, check_position_range, check_sqrt_price_band, modify_position, Deadline,
+    PositionTickArrays, SqrtPriceBand,
+};
+use crate::state::{
+    check_allowlisted, AccountMut, AllowlistEntry, PoolState, PositionState, POSITION_SEED,
+};
+use crate::utils::{
+    check_signer, check_system_program, check_token_program, create_account, mint_position_nft,
+    transfer_from_user,
+};
+
+/// Opens a position in `[tick_lower, tick_upper)` and deposits `liquidity` into it.
+///
+/// The position is owned by whoever holds its NFT. The NFT mint is created here
+/// with the position PDA as mint authority, which is revoked after the single token is minted.
+/// Missing tick arrays for the two ticks are created at the payer's expense.
+/// Fails after `deadline`, or when the pool price sits outside `sqrt_price_band`.
+///
+/// Accounts:
+/// 0. `[signer, writable]` payer, receives the position NFT
+/// 1. `[writable]` pool
+/// 2. `[signer, writable]` position NFT mint, a new account
+/// 3. `[signer, writable]` position NFT token account, a new account
+/// 4. `[writable]` position PDA
+/// 5. `[writable]` tick array holding `tick_lower`
+/// 6. `[writable]` tick array holding `tick_upper`, may repeat account 5
+/// 7. `[writable]` payer token account 0
+/// 8. `[writable]` payer token account 1
+/// 9. `[writable]` token vault 0
+/// 10. `[writable]` token vault 1
+/// 11. `[]` token program
+/// 12. `[]` system program
+/// 13. `[]` payer allowlist entry, only for permissioned pools
+#[allow(clippy::too_many_arguments)]
+pub fn process_open_position(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    tick_lower: i32,
+    tick_upper: i32,
+    liquidity: u128,
+    amount_0_max: u64,
+    amount_1_max: u64,
+    deadline: Option<Deadline>,
+    sqrt_price_band: Option<SqrtPriceBand>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let payer = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let nft_mint = next_account_info(account_info_iter)?;
+    let nft_account = next_account_info(account_info_iter)?;
+    let position_info = next_account_info(account_info_iter)?;
+    let tick_array_lower_info = next_account_info(account_info_iter)?;
+    let tick_array_upper_info = next_account_info(account_info_iter)?;
+    let token_account_0 = next_account_info(account_info_iter)?;
+    let token_account_1 = next_account_info(account_info_iter)?;
+    let token_vault_0 = next_account_info(account_info_iter)?;
+    let token_vault_1 = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let allowlist_entry = next_account_info(account_info_iter).ok();
+
+    check_signer(nft_mint)?;
+    check_signer(nft_account)?;
+    check_deadline(deadline)?;
+
+    let (expected, position_bump) =
+        Pubkey::find_program_address(&[POSITION_SEED, nft_mint.key.as_ref()], program_id);
+    if expected != *position_info.key {
+        return Err(ClmmError::InvalidPda.into());
+    }
+    let position_seeds: &[&[u8]] = &[POSITION_SEED, nft_mint.key.as_ref(), &[position_bump]];
+    open_position_account(
+        program_id,
+        &OpenPositionAccounts {
+            payer,
+            pool: pool_info,
+            position: position_info,
+            tick_array_lower: tick_array_lower_info,
+            tick_array_upper: tick_array_upper_info,
+            token_account_0,
+            token_account_1,
+            token_vault_0,
+            token_vault_1,
+            token_program,
+            system_program,
+            allowlist_entry,
+        },
+        nft_mint.key,
+        position_seeds,
+        tick_lower,
+        tick_upper,
+        liquidity,
+        amount_0_max,
+        amount_1_max,
+        sqrt_price_band,
+    )?;
+
+    // The position PDA briefly acts as mint authority for its own NFT
+    mint_position_nft(
+        payer,
+        payer.key,
+        nft_mint,
+        nft_account,
+        position_info,
+        position_seeds,
+        token_program,
+        system_program,
+    )
+}
+
+/// Accounts needed to create a position and make its first deposit.
+pub struct OpenPositionAccounts<'a, 'info> {
+    /// Pays for the new accounts and the deposit, and becomes the position owner
+    pub payer: &'a AccountInfo<'info>,
+    pub pool: &'a AccountInfo<'info>,
+    pub position: &'a AccountInfo<'info>,
+    pub tick_array_lower: &'a AccountInfo<'info>,
+    pub tick_array_upper: &'a AccountInfo<'info>,
+    pub token_account_0: &'a AccountInfo<'info>,
+    pub token_account_1: &'a AccountInfo<'info>,
+    pub token_vault_0: &'a AccountInfo<'info>,
+    pub token_vault_1: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    /// Payer's allowlist entry, required by permissioned pools
+    pub allowlist_entry: Option<&'a AccountInfo<'info>>,
+}
+
+/// Creates the position account at the PDA signed by `position_seeds`, owned by
+/// whoever holds `nft_mint`, and deposits `liquidity` into it.
+///
+/// Fails when the pool price sits outside `sqrt_price_band`.
+#[allow(clippy::too_many_arguments)]
+pub fn open_position_account(
+    program_id: &Pubkey,
+    accounts: &OpenPositionAccounts,
+    nft_mint: &Pubkey,
+    position_seeds: &[&[u8]],
+    tick_lower: i32,
+    tick_upper: i32,
+    liquidity: u128,
+    amount_0_max: u64,
+    amount_1_max: u64,
+    sqrt_price_band: Option<SqrtPriceBand>,
+) -> ProgramResult {
+    let OpenPositionAccounts {
+        payer,
+        pool: pool_info,
+        position: position_info,
+        tick_array_lower: tick_array_lower_info,
+        tick_array_upper: tick_array_upper_info,
+        token_account_0,
+        token_account_1,
+        token_vault_0,
+        token_vault_1,
+        token_program,
+        system_program,
+        allowlist_entry,
+    } = *accounts;
+
+    check_signer(payer)?;
+    check_token_program(token_program)?;
+    check_system_program(system_program)?;
+
+    let mut pool = AccountMut::<PoolState>::load(pool_info, program_id)?;
+    if *token_vault_0.key != pool.token_vault_0 || *token_vault_1.key != pool.token_vault_1 {
+        return Err(ClmmError::InvalidAccountData.into());
+    }
+    check_allowlisted(
+        program_id,
+        pool_info.key,
+        &pool,
+        payer.key,
+        allowlist_entry,
+        AllowlistEntry::PERMISSION_LIQUIDITY,
+    )?;
+    check_sqrt_price_band(sqrt_price_band, pool.sqrt_price_x64)?;
+
+    check_position_range(tick_lower, tick_upper, pool.tick_spacing)?;
+    if liquidity == 0 {
+        return Err(ClmmError::ZeroAmount.into());
+    }
+    let liquidity_delta = i128::try_from(liquidity).map_err(|_| ClmmError::MathOverflow)?;
+
+    create_account(
+        payer,
+        position_info,
+        system_program,
+        program_id,
+        PositionState::LEN,
+        &[position_seeds],
+    )?;
+    let position_bump = *position_seeds
+        .last()
+        .and_then(|bump| bump.first())
+        .ok_or(ClmmError::InvalidPda)?;
+    let mut position = AccountMut::init(
+        position_info,
+        program_id,
+        PositionState {
+            discriminator: PositionState::DISCRIMINATOR,
+            version: PositionState::VERSION,
+            bump: position_bump,
+            nft_mint: *nft_mint,
+            pool_id: *pool_info.key,
+            tick_lower_index: tick_lower,
+            tick_upper_index: tick_upper,
+            ..Default::default()
+        },
+    )?;
+
+    let mut tick_arrays = PositionTickArrays::load_or_create(
+        program_id,
+        pool_info.key,
+        pool.tick_spacing,
+        tick_lower,
+        tick_upper,
+        tick_array_lower_info,
+        tick_array_upper_info,
+        payer,
+        system_program,
+    )?;
+    let (amount_0, amount_1) =
+        modify_position(&mut pool, &mut position, &mut tick_arrays, liquidity_delta)?;
+    if amount_0 > amount_0_max || amount_1 > amount_1_max {
+        return Err(ClmmError::SlippageExceeded.into());
+    }
+
+    transfer_from_user(
+        payer,
+        token_account_0,
+        token_vault_0,
+        token_program,
+        amount_0,
+    )?;
+    transfer_from_user(
+        payer,
+        token_account_1,
+        token_vault_1,
+        token_program,
+        amount_1,
+    )?;
+
+    emit(&PositionOpenedEvent {
+        pool: *pool_info.key,
+        position: *position_info.key,
+        nft_mint: *nft_mint,
+        owner: *payer.key,
+        tick_lower,
+        tick_upper,
+    });
+    emit(&LiquidityIncreasedEvent {
+        pool: *pool_info.key,
+        position: *position_info.key,
+        tick_lower,
+        tick_upper,
+        liquidity,
+        amount_0,
+        amount_1,
+    });
+    Ok(())
+}
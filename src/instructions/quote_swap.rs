@@ -0,0 +1,95 @@
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_account_info::{next_account_info, AccountInfo};
+use solana_program_error::{ProgramError, ProgramResult};
+use solana_pubkey::Pubkey;
+
+use crate::error::ClmmError;
+use crate::instructions::{closed_tick_array, resolve_sqrt_price_limit, swap_internal};
+use crate::state::{AccountRef, AmmConfig, PoolState, TickArrayState};
+use crate::utils::set_return_data;
+
+/// Outcome of a simulated swap, returned by `QuoteSwap` as borsh return data
+#[derive(
+    BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, Copy, PartialEq, Eq, Default,
+)]
+pub struct SwapQuote {
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// Total fee charged on the input
+    pub fee_amount: u64,
+    pub sqrt_price_after_x64: u128,
+    pub tick_after: i32,
+}
+
+/// Quotes a swap without executing it, and returns a [`SwapQuote`] through return data.
+///
+/// Runs the same engine as `Swap` on copies of the pool and tick arrays, so no
+/// account is written and none needs to be writable. The amount and price limit
+/// follow `Swap`. Open time, launch caps and the allowlist are not checked.
+///
+/// Accounts:
+/// 0. `[]` amm config
+/// 1. `[]` pool
+/// 2. `[]` tick arrays, any number of them, starting with the one holding the current tick.
+///    Closed arrays are passed at their address and walked through as empty.
+pub fn process_quote_swap(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    sqrt_price_limit_x64: u128,
+    zero_for_one: bool,
+    is_base_input: bool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let amm_config_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+
+    let amm_config = AccountRef::<AmmConfig>::load(amm_config_info, program_id)?;
+    let mut pool = AccountRef::<PoolState>::load(pool_info, program_id)?.into_inner();
+    if pool.amm_config != *amm_config_info.key {
+        return Err(ClmmError::InvalidAccountData.into());
+    }
+
+    let mut tick_arrays = account_info_iter
+        .enumerate()
+        .map(|(position, info)| {
+            if info.owner != program_id {
+                return closed_tick_array(
+                    program_id,
+                    pool_info.key,
+                    &pool,
+                    info,
+                    position,
+                    zero_for_one,
+                );
+            }
+            let tick_array = AccountRef::<TickArrayState>::load(info, program_id)?;
+            if tick_array.pool_id != *pool_info.key {
+                return Err(ClmmError::InvalidTickArray.into());
+            }
+            Ok(tick_array.into_inner())
+        })
+        .collect::<Result<Vec<_>, ProgramError>>()?;
+
+    let result = swap_internal(
+        &amm_config,
+        &mut pool,
+        &mut tick_arrays,
+        amount,
+        resolve_sqrt_price_limit(sqrt_price_limit_x64, zero_for_one),
+        zero_for_one,
+        is_base_input,
+        0,
+    )?;
+
+    let quote = SwapQuote {
+        amount_in: result.amount_in,
+        amount_out: result.amount_out,
+        fee_amount: result.fee_amount,
+        sqrt_price_after_x64: pool.sqrt_price_x64,
+        tick_after: pool.tick_current,
+    };
+    // Writing into a Vec cannot fail.
+    set_return_data(&borsh::to_vec(&quote).unwrap());
+    Ok(())
+}
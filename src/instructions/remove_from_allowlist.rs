@@ -0,0 +1,37 @@
+use solana_account_info::{next_account_info, AccountInfo};
+use solana_program_error::ProgramResult;
+use solana_pubkey::Pubkey;
+
+use crate::error::ClmmError;
+use crate::state::{AccountMut, AccountRef, AllowlistEntry, PoolState};
+use crate::utils::check_signer;
+
+/// Removes a wallet from a pool's allowlist, closing its entry.
+///
+/// The wallet keeps any position it holds and can still withdraw from it.
+///
+/// Accounts:
+/// 0. `[signer, writable]` pool permission authority, receives the rent
+/// 1. `[]` pool
+/// 2. `[writable]` allowlist entry
+pub fn process_remove_from_allowlist(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let authority = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let entry_info = next_account_info(account_info_iter)?;
+
+    check_signer(authority)?;
+
+    let pool = AccountRef::<PoolState>::load(pool_info, program_id)?;
+    if pool.permission_authority != *authority.key {
+        return Err(ClmmError::Unauthorized.into());
+    }
+    let entry = AccountMut::<AllowlistEntry>::load(entry_info, program_id)?;
+    if entry.pool != *pool_info.key {
+        return Err(ClmmError::InvalidAccountData.into());
+    }
+    entry.close(authority)
+}
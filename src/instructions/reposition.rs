@@ -0,0 +1,204 @@
+use solana_account_info::{next_account_info, AccountInfo};
+use solana_program_error::ProgramResult;
+use solana_pubkey::Pubkey;
+
+use crate::error::ClmmError;
+use crate::events::{emit, LiquidityDecreasedEvent, LiquidityIncreasedEvent};
+use crate::instructions::{
+    check_deadline, check_position_range, check_sqrt_price_band, modify_position, Deadline,
+    PositionTickArrays, SqrtPriceBand,
+};
+use crate::libraries::liquidity_math::LiquidityQuote;
+use crate::state::{check_allowlisted, AccountMut, AllowlistEntry, PoolState, PositionState};
+use crate::utils::{
+    check_position_nft, check_signer, check_system_program, check_token_program, transfer_from_pool,
+};
+
+/// Moves a position to `[tick_lower, tick_upper)` without tokens leaving the pool in between.
+///
+/// All liquidity is removed from the old range, and the released amounts plus the
+/// owed fees are redeposited as the most liquidity the new range allows at the
+/// current price. Whatever cannot be redeposited is paid out to the owner.
+/// Missing tick arrays for the new range are created at the owner's expense.
+/// Fails after `deadline`, or when the pool price sits outside `sqrt_price_band`.
+///
+/// Accounts:
+/// 0. `[signer, writable]` position owner
+/// 1. `[]` position NFT token account
+/// 2. `[writable]` pool
+/// 3. `[writable]` position
+/// 4. `[writable]` tick array holding the current lower tick
+/// 5. `[writable]` tick array holding the current upper tick, may repeat account 4
+/// 6. `[writable]` tick array holding `tick_lower`, may repeat accounts 4 or 5
+/// 7. `[writable]` tick array holding `tick_upper`, may repeat any of accounts 4 to 6
+/// 8. `[writable]` recipient token account 0
+/// 9. `[writable]` recipient token account 1
+/// 10. `[writable]` token vault 0
+/// 11. `[writable]` token vault 1
+/// 12. `[]` token program
+/// 13. `[]` system program
+/// 14. `[]` owner allowlist entry, only for permissioned pools
+#[allow(clippy::too_many_arguments)]
+pub fn process_reposition(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    tick_lower: i32,
+    tick_upper: i32,
+    liquidity_min: u128,
+    amount_0_leftover_max: u64,
+    amount_1_leftover_max: u64,
+    deadline: Option<Deadline>,
+    sqrt_price_band: Option<SqrtPriceBand>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let nft_account = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let position_info = next_account_info(account_info_iter)?;
+    let old_tick_array_lower_info = next_account_info(account_info_iter)?;
+    let old_tick_array_upper_info = next_account_info(account_info_iter)?;
+    let tick_array_lower_info = next_account_info(account_info_iter)?;
+    let tick_array_upper_info = next_account_info(account_info_iter)?;
+    let recipient_token_account_0 = next_account_info(account_info_iter)?;
+    let recipient_token_account_1 = next_account_info(account_info_iter)?;
+    let token_vault_0 = next_account_info(account_info_iter)?;
+    let token_vault_1 = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let allowlist_entry = next_account_info(account_info_iter).ok();
+
+    check_signer(owner)?;
+    check_token_program(token_program)?;
+    check_system_program(system_program)?;
+    check_deadline(deadline)?;
+
+    let mut pool = AccountMut::<PoolState>::load(pool_info, program_id)?;
+    let mut position = AccountMut::<PositionState>::load(position_info, program_id)?;
+    if position.pool_id != *pool_info.key {
+        return Err(ClmmError::PositionNotFound.into());
+    }
+    check_position_nft(nft_account, &position.nft_mint, owner.key)?;
+    check_allowlisted(
+        program_id,
+        pool_info.key,
+        &pool,
+        owner.key,
+        allowlist_entry,
+        AllowlistEntry::PERMISSION_LIQUIDITY,
+    )?;
+    if *token_vault_0.key != pool.token_vault_0 || *token_vault_1.key != pool.token_vault_1 {
+        return Err(ClmmError::InvalidAccountData.into());
+    }
+    check_sqrt_price_band(sqrt_price_band, pool.sqrt_price_x64)?;
+    check_position_range(tick_lower, tick_upper, pool.tick_spacing)?;
+
+    // Withdraw everything from the old range, crediting fees on the way
+    let mut old_tick_arrays = PositionTickArrays::load(
+        program_id,
+        pool_info.key,
+        pool.tick_spacing,
+        position.tick_lower_index,
+        position.tick_upper_index,
+        old_tick_array_lower_info,
+        old_tick_array_upper_info,
+    )?;
+    let removed_liquidity = position.liquidity;
+    let liquidity_delta =
+        -i128::try_from(removed_liquidity).map_err(|_| ClmmError::MathOverflow)?;
+    let (removed_0, removed_1) = modify_position(
+        &mut pool,
+        &mut position,
+        &mut old_tick_arrays,
+        liquidity_delta,
+    )?;
+    // The new arrays may be the same accounts
+    old_tick_arrays.exit()?;
+    emit(&LiquidityDecreasedEvent {
+        pool: *pool_info.key,
+        position: *position_info.key,
+        tick_lower: position.tick_lower_index,
+        tick_upper: position.tick_upper_index,
+        liquidity: removed_liquidity,
+        amount_0: removed_0,
+        amount_1: removed_1,
+    });
+
+    let available_0 = removed_0
+        .checked_add(position.token_fees_owed_0)
+        .ok_or(ClmmError::MathOverflow)?;
+    let available_1 = removed_1
+        .checked_add(position.token_fees_owed_1)
+        .ok_or(ClmmError::MathOverflow)?;
+    position.token_fees_owed_0 = 0;
+    position.token_fees_owed_1 = 0;
+
+    // With no liquidity left, moving the range credits no fees
+    position.tick_lower_index = tick_lower;
+    position.tick_upper_index = tick_upper;
+    let quote = LiquidityQuote::from_amounts(
+        pool.tick_current,
+        pool.sqrt_price_x64,
+        tick_lower,
+        tick_upper,
+        available_0,
+        available_1,
+    )?;
+    if quote.liquidity < liquidity_min {
+        return Err(ClmmError::SlippageExceeded.into());
+    }
+
+    let mut tick_arrays = PositionTickArrays::load_or_create(
+        program_id,
+        pool_info.key,
+        pool.tick_spacing,
+        tick_lower,
+        tick_upper,
+        tick_array_lower_info,
+        tick_array_upper_info,
+        owner,
+        system_program,
+    )?;
+    let liquidity_delta = i128::try_from(quote.liquidity).map_err(|_| ClmmError::MathOverflow)?;
+    let (amount_0, amount_1) =
+        modify_position(&mut pool, &mut position, &mut tick_arrays, liquidity_delta)?;
+
+    // The quote never charges more than the amounts it was computed from
+    let leftover_0 = available_0
+        .checked_sub(amount_0)
+        .ok_or(ClmmError::MathOverflow)?;
+    let leftover_1 = available_1
+        .checked_sub(amount_1)
+        .ok_or(ClmmError::MathOverflow)?;
+    if leftover_0 > amount_0_leftover_max || leftover_1 > amount_1_leftover_max {
+        return Err(ClmmError::SlippageExceeded.into());
+    }
+
+    let pool_seeds = pool.seeds();
+    transfer_from_pool(
+        pool_info,
+        &pool_seeds,
+        token_vault_0,
+        recipient_token_account_0,
+        token_program,
+        leftover_0,
+    )?;
+    transfer_from_pool(
+        pool_info,
+        &pool_seeds,
+        token_vault_1,
+        recipient_token_account_1,
+        token_program,
+        leftover_1,
+    )?;
+
+    emit(&LiquidityIncreasedEvent {
+        pool: *pool_info.key,
+        position: *position_info.key,
+        tick_lower,
+        tick_upper,
+        liquidity: quote.liquidity,
+        amount_0,
+        amount_1,
+    });
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use solana_account_info::{next_account_info, AccountInfo};
+use solana_program_error::ProgramResult;
+use solana_pubkey::Pubkey;
+
+use crate::error::ClmmError;
+use crate::state::{AccountMut, AmmConfig, FeeTierRegistry, FEE_TIER_REGISTRY_SEED};
+use crate::utils::check_signer;
+
+/// Enables or disables a fee tier for new pools. Existing pools keep trading.
+///
+/// Accounts:
+/// 0. `[signer]` registry admin
+/// 1. `[writable]` fee tier registry
+/// 2. `[writable]` amm config of the tier
+pub fn process_set_fee_tier_enabled(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    enabled: bool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let admin = next_account_info(account_info_iter)?;
+    let registry_info = next_account_info(account_info_iter)?;
+    let amm_config_info = next_account_info(account_info_iter)?;
+
+    check_signer(admin)?;
+    let mut registry = AccountMut::<FeeTierRegistry>::load(registry_info, program_id)?
+        .verify_pda(program_id, &[FEE_TIER_REGISTRY_SEED])?;
+    if registry.admin != *admin.key {
+        return Err(ClmmError::Unauthorized.into());
+    }
+    let mut amm_config = AccountMut::<AmmConfig>::load(amm_config_info, program_id)?;
+
+    amm_config.set_disabled(!enabled);
+    registry
+        .get_tier_mut(amm_config_info.key)
+        .ok_or(ClmmError::InvalidAccountData)?
+        .status = amm_config.status;
+
+    amm_config.exit()?;
+    registry.exit()
+}
@@ -0,0 +1,40 @@
+use solana_account_info::{next_account_info, AccountInfo};
+use solana_program_error::ProgramResult;
+use solana_pubkey::Pubkey;
+
+use crate::error::ClmmError;
+use crate::state::{AccountMut, AmmConfig, FEE_RATE_DENOMINATOR};
+use crate::utils::check_signer;
+
+/// Sets the largest share of the trade fee a swap may pay its referrer.
+///
+/// Together with the protocol and fund shares the cap must leave LPs a non-negative share.
+///
+/// Accounts:
+/// 0. `[signer]` config admin
+/// 1. `[writable]` amm config
+pub fn process_set_max_referral_fee_rate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    max_referral_fee_rate: u32,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let admin = next_account_info(account_info_iter)?;
+    let amm_config_info = next_account_info(account_info_iter)?;
+
+    check_signer(admin)?;
+    let mut amm_config = AccountMut::<AmmConfig>::load(amm_config_info, program_id)?;
+    if amm_config.admin != *admin.key {
+        return Err(ClmmError::Unauthorized.into());
+    }
+    if amm_config.protocol_fee_rate as u64
+        + amm_config.fund_fee_rate as u64
+        + max_referral_fee_rate as u64
+        > FEE_RATE_DENOMINATOR as u64
+    {
+        return Err(ClmmError::InvalidFeeRate.into());
+    }
+
+    amm_config.max_referral_fee_rate = max_referral_fee_rate;
+    amm_config.exit()
+}
@@ -0,0 +1,43 @@
+use solana_account_info::{next_account_info, AccountInfo};
+use solana_program_error::ProgramResult;
+use solana_pubkey::Pubkey;
+
+use crate::error::ClmmError;
+use crate::state::{AccountMut, AccountRef, AmmConfig, PoolState, FEE_RATE_DENOMINATOR};
+use crate::utils::check_signer;
+
+/// Sets a pool's own trade fee rate, or clears it with `None` to follow the config again.
+///
+/// The override obeys the same bound as config fee rates. The protocol and fund shares and
+/// the referral cap still come from the config.
+///
+/// Accounts:
+/// 0. `[signer]` config admin
+/// 1. `[]` amm config of the pool
+/// 2. `[writable]` pool
+pub fn process_set_pool_fee_rate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    trade_fee_rate: Option<u32>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let admin = next_account_info(account_info_iter)?;
+    let amm_config_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+
+    check_signer(admin)?;
+    let amm_config = AccountRef::<AmmConfig>::load(amm_config_info, program_id)?;
+    if amm_config.admin != *admin.key {
+        return Err(ClmmError::Unauthorized.into());
+    }
+    let mut pool = AccountMut::<PoolState>::load(pool_info, program_id)?;
+    if pool.amm_config != *amm_config_info.key {
+        return Err(ClmmError::InvalidAccountData.into());
+    }
+    if trade_fee_rate.is_some_and(|rate| rate >= FEE_RATE_DENOMINATOR) {
+        return Err(ClmmError::InvalidFeeRate.into());
+    }
+
+    pool.set_trade_fee_rate_override(trade_fee_rate);
+    pool.exit()
+}
@@ -0,0 +1,555 @@
+use std::ops::{Deref, DerefMut};
+
+use solana_account_info::{next_account_info, AccountInfo};
+use solana_clock::Clock;
+use solana_program_error::{ProgramError, ProgramResult};
+use solana_pubkey::Pubkey;
+use solana_sysvar::Sysvar;
+
+use crate::error::ClmmError;
+use crate::events::{emit, SwapEvent};
+use crate::instructions::Deadline;
+use crate::libraries::full_math::mul_div;
+use crate::libraries::liquidity_math::{add_delta, Q64};
+use crate::libraries::swap_math::compute_swap_step;
+use crate::libraries::tick_math::{
+    get_sqrt_price_at_tick, get_tick_at_sqrt_price, MAX_SQRT_PRICE_X64, MAX_TICK,
+    MIN_SQRT_PRICE_X64, MIN_TICK,
+};
+use crate::state::{
+    check_allowlisted, AccountMut, AccountRef, AllowlistEntry, AmmConfig, PoolState,
+    TickArrayState, FEE_RATE_DENOMINATOR, TICK_ARRAY_SEED, TICK_ARRAY_SIZE,
+};
+use crate::utils::{
+    check_signer, check_token_program, transfer_from_pool, transfer_from_user, unpack_token_account,
+};
+
+/// Totals of a swap across every step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SwapResult {
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// Total fee charged on the input, protocol, fund and referral shares included
+    pub fee_amount: u64,
+    pub protocol_fee_amount: u64,
+    pub fund_fee_amount: u64,
+    /// Owed to the referrer, not kept in the pool
+    pub referral_fee_amount: u64,
+}
+
+/// Runs the swap engine against `pool`, crossing initialized ticks in `tick_arrays`.
+///
+/// `tick_arrays` start with the array holding the current tick and continue in the
+/// swap direction. The swap stops once `amount_specified` is used up or the price
+/// reaches `sqrt_price_limit_x64`, so it may fill partially.
+/// `referral_fee_rate` of each step fee is set aside for the referrer, zero without one.
+#[allow(clippy::too_many_arguments)]
+pub fn swap_internal<T: DerefMut<Target = TickArrayState>>(
+    amm_config: &AmmConfig,
+    pool: &mut PoolState,
+    tick_arrays: &mut [T],
+    amount_specified: u64,
+    sqrt_price_limit_x64: u128,
+    zero_for_one: bool,
+    is_base_input: bool,
+    referral_fee_rate: u32,
+) -> Result<SwapResult, ClmmError> {
+    if amount_specified == 0 {
+        return Err(ClmmError::ZeroAmount);
+    }
+    let price_limit_valid = if zero_for_one {
+        sqrt_price_limit_x64 < pool.sqrt_price_x64 && sqrt_price_limit_x64 > MIN_SQRT_PRICE_X64
+    } else {
+        sqrt_price_limit_x64 > pool.sqrt_price_x64 && sqrt_price_limit_x64 < MAX_SQRT_PRICE_X64
+    };
+    if !price_limit_valid {
+        return Err(ClmmError::InvalidSqrtPrice);
+    }
+
+    let tick_spacing = pool.tick_spacing;
+    let ticks_in_array = TICK_ARRAY_SIZE * tick_spacing as i32;
+    let first_array = tick_arrays.first().ok_or(ClmmError::NotEnoughTickArrays)?;
+    if first_array.start_tick_index
+        != TickArrayState::get_array_start_index(pool.tick_current, tick_spacing as i32)
+    {
+        return Err(ClmmError::InvalidTickArray);
+    }
+
+    let mut amount_remaining = amount_specified;
+    let mut amount_calculated = 0u64;
+    let mut sqrt_price_x64 = pool.sqrt_price_x64;
+    let mut tick = pool.tick_current;
+    let mut liquidity = pool.liquidity;
+    let mut fee_growth_global_x64 = if zero_for_one {
+        pool.fee_growth_global_0_x64
+    } else {
+        pool.fee_growth_global_1_x64
+    };
+    let mut fee_amount = 0u64;
+    let mut protocol_fee_amount = 0u64;
+    let mut fund_fee_amount = 0u64;
+    let mut referral_fee_amount = 0u64;
+    let mut array_index = 0;
+
+    while amount_remaining != 0 && sqrt_price_x64 != sqrt_price_limit_x64 {
+        let sqrt_price_start_x64 = sqrt_price_x64;
+
+        // Next initialized tick, moving on to the following array when this one has none left
+        let (tick_next, initialized) = loop {
+            let tick_array = &tick_arrays[array_index];
+            if let Some(offset) =
+                tick_array.next_initialized_tick_offset(tick, tick_spacing, zero_for_one)
+            {
+                break (tick_array.ticks[offset].tick, true);
+            }
+
+            let start = tick_array.start_tick_index;
+            let next_start = if zero_for_one {
+                start - ticks_in_array
+            } else {
+                start + ticks_in_array
+            };
+            match tick_arrays.get(array_index + 1) {
+                Some(next) if next.start_tick_index == next_start => array_index += 1,
+                Some(_) => return Err(ClmmError::InvalidTickArray),
+                None => {
+                    // Run up to the edge of the last array provided
+                    if zero_for_one && tick < start {
+                        return Err(ClmmError::NotEnoughTickArrays);
+                    }
+                    if !zero_for_one && tick >= next_start {
+                        return Err(ClmmError::NotEnoughTickArrays);
+                    }
+                    break (if zero_for_one { start } else { next_start }, false);
+                }
+            }
+        };
+        let tick_next = tick_next.clamp(MIN_TICK, MAX_TICK);
+        let sqrt_price_next_x64 = get_sqrt_price_at_tick(tick_next)?;
+
+        let sqrt_price_target_x64 = if zero_for_one {
+            sqrt_price_next_x64.max(sqrt_price_limit_x64)
+        } else {
+            sqrt_price_next_x64.min(sqrt_price_limit_x64)
+        };
+        let step = compute_swap_step(
+            sqrt_price_x64,
+            sqrt_price_target_x64,
+            liquidity,
+            amount_remaining,
+            pool.trade_fee_rate(amm_config),
+            is_base_input,
+            zero_for_one,
+        )?;
+        sqrt_price_x64 = step.sqrt_price_next_x64;
+
+        let step_amount_in = step
+            .amount_in
+            .checked_add(step.fee_amount)
+            .ok_or(ClmmError::MathOverflow)?;
+        let (used, calculated) = if is_base_input {
+            (step_amount_in, step.amount_out)
+        } else {
+            (step.amount_out, step_amount_in)
+        };
+        amount_remaining = amount_remaining
+            .checked_sub(used)
+            .ok_or(ClmmError::MathOverflow)?;
+        amount_calculated = amount_calculated
+            .checked_add(calculated)
+            .ok_or(ClmmError::MathOverflow)?;
+
+        // Every share rounds down, so LPs keep the remainder
+        let protocol_fee = mul_div(
+            step.fee_amount as u128,
+            amm_config.protocol_fee_rate as u128,
+            FEE_RATE_DENOMINATOR as u128,
+        )? as u64;
+        let fund_fee = mul_div(
+            step.fee_amount as u128,
+            amm_config.fund_fee_rate as u128,
+            FEE_RATE_DENOMINATOR as u128,
+        )? as u64;
+        let referral_fee = mul_div(
+            step.fee_amount as u128,
+            referral_fee_rate as u128,
+            FEE_RATE_DENOMINATOR as u128,
+        )? as u64;
+        fee_amount = fee_amount
+            .checked_add(step.fee_amount)
+            .ok_or(ClmmError::MathOverflow)?;
+        protocol_fee_amount = protocol_fee_amount
+            .checked_add(protocol_fee)
+            .ok_or(ClmmError::MathOverflow)?;
+        fund_fee_amount = fund_fee_amount
+            .checked_add(fund_fee)
+            .ok_or(ClmmError::MathOverflow)?;
+        referral_fee_amount = referral_fee_amount
+            .checked_add(referral_fee)
+            .ok_or(ClmmError::MathOverflow)?;
+        if liquidity > 0 {
+            let lp_fee = step
+                .fee_amount
+                .checked_sub(protocol_fee)
+                .and_then(|fee| fee.checked_sub(fund_fee))
+                .and_then(|fee| fee.checked_sub(referral_fee))
+                .ok_or(ClmmError::MathOverflow)? as u128;
+            fee_growth_global_x64 =
+                fee_growth_global_x64.wrapping_add(mul_div(lp_fee, Q64, liquidity)?);
+        }
+
+        if sqrt_price_x64 == sqrt_price_next_x64 {
+            if initialized {
+                let (fee_growth_global_0_x64, fee_growth_global_1_x64) = if zero_for_one {
+                    (fee_growth_global_x64, pool.fee_growth_global_1_x64)
+                } else {
+                    (pool.fee_growth_global_0_x64, fee_growth_global_x64)
+                };
+                let tick_state =
+                    tick_arrays[array_index].get_tick_state_mut(tick_next, tick_spacing)?;
+                let mut liquidity_net =
+                    tick_state.cross(fee_growth_global_0_x64, fee_growth_global_1_x64);
+                // Moving leftwards the net liquidity is removed rather than added
+                if zero_for_one {
+                    liquidity_net = liquidity_net.checked_neg().ok_or(ClmmError::MathOverflow)?;
+                }
+                liquidity = add_delta(liquidity, liquidity_net)?;
+            }
+            tick = if zero_for_one {
+                tick_next - 1
+            } else {
+                tick_next
+            };
+        } else if sqrt_price_x64 != sqrt_price_start_x64 {
+            tick = get_tick_at_sqrt_price(sqrt_price_x64)?;
+        }
+    }
+
+    pool.sqrt_price_x64 = sqrt_price_x64;
+    pool.tick_current = tick;
+    pool.liquidity = liquidity;
+    if zero_for_one {
+        pool.fee_growth_global_0_x64 = fee_growth_global_x64;
+        pool.protocol_fees_token_0 = pool
+            .protocol_fees_token_0
+            .checked_add(protocol_fee_amount)
+            .ok_or(ClmmError::MathOverflow)?;
+        pool.fund_fees_token_0 = pool
+            .fund_fees_token_0
+            .checked_add(fund_fee_amount)
+            .ok_or(ClmmError::MathOverflow)?;
+    } else {
+        pool.fee_growth_global_1_x64 = fee_growth_global_x64;
+        pool.protocol_fees_token_1 = pool
+            .protocol_fees_token_1
+            .checked_add(protocol_fee_amount)
+            .ok_or(ClmmError::MathOverflow)?;
+        pool.fund_fees_token_1 = pool
+            .fund_fees_token_1
+            .checked_add(fund_fee_amount)
+            .ok_or(ClmmError::MathOverflow)?;
+    }
+
+    let amount_used = amount_specified - amount_remaining;
+    let (amount_in, amount_out) = if is_base_input {
+        (amount_used, amount_calculated)
+    } else {
+        (amount_calculated, amount_used)
+    };
+    Ok(SwapResult {
+        amount_in,
+        amount_out,
+        fee_amount,
+        protocol_fee_amount,
+        fund_fee_amount,
+        referral_fee_amount,
+    })
+}
+
+/// A tick array a swap walks through.
+pub enum SwapTickArray<'a, 'info> {
+    Account(AccountMut<'a, 'info, TickArrayState>),
+    /// A closed array standing in as empty. It has no initialized ticks to cross,
+    /// so the swap never writes to it.
+    Closed(Box<TickArrayState>),
+}
+
+impl Deref for SwapTickArray<'_, '_> {
+    type Target = TickArrayState;
+
+    fn deref(&self) -> &TickArrayState {
+        match self {
+            Self::Account(tick_array) => tick_array,
+            Self::Closed(tick_array) => tick_array,
+        }
+    }
+}
+
+impl DerefMut for SwapTickArray<'_, '_> {
+    fn deref_mut(&mut self) -> &mut TickArrayState {
+        match self {
+            Self::Account(tick_array) => tick_array,
+            Self::Closed(tick_array) => tick_array,
+        }
+    }
+}
+
+/// Empty stand-in for a closed tick array passed as the `position`-th array of a swap.
+///
+/// Arrays are walked contiguously from the one holding the current tick, so the
+/// account must be the PDA of the array at that position and no longer exist.
+/// Closing an empty array in the middle of a liquidity range thus never blocks swaps.
+pub fn closed_tick_array(
+    program_id: &Pubkey,
+    pool_key: &Pubkey,
+    pool: &PoolState,
+    info: &AccountInfo,
+    position: usize,
+    zero_for_one: bool,
+) -> Result<Box<TickArrayState>, ProgramError> {
+    if info.owner == program_id || !info.data_is_empty() {
+        return Err(ClmmError::InvalidTickArray.into());
+    }
+    let tick_spacing = pool.tick_spacing;
+    let offset = i32::try_from(position)
+        .ok()
+        .and_then(|position| position.checked_mul(TICK_ARRAY_SIZE * tick_spacing as i32))
+        .ok_or(ClmmError::InvalidTickArray)?;
+    let first_start = TickArrayState::get_array_start_index(pool.tick_current, tick_spacing as i32);
+    let start = if zero_for_one {
+        first_start.checked_sub(offset)
+    } else {
+        first_start.checked_add(offset)
+    }
+    .filter(|start| TickArrayState::check_is_valid_start_index(*start, tick_spacing))
+    .ok_or(ClmmError::InvalidTickArray)?;
+
+    let (expected, _) = Pubkey::find_program_address(
+        &[TICK_ARRAY_SEED, pool_key.as_ref(), &start.to_be_bytes()],
+        program_id,
+    );
+    if expected != *info.key {
+        return Err(ClmmError::InvalidTickArray.into());
+    }
+    Ok(Box::new(TickArrayState::new(
+        *pool_key,
+        start,
+        tick_spacing,
+    )))
+}
+
+/// Replaces a zero `sqrt_price_limit_x64` with the furthest price the swap can reach.
+pub fn resolve_sqrt_price_limit(sqrt_price_limit_x64: u128, zero_for_one: bool) -> u128 {
+    match sqrt_price_limit_x64 {
+        0 if zero_for_one => MIN_SQRT_PRICE_X64 + 1,
+        0 => MAX_SQRT_PRICE_X64 - 1,
+        limit => limit,
+    }
+}
+
+/// Swaps against the pool. The direction follows from which vault receives the input.
+///
+/// With `is_base_input`, `amount` is the exact input and `other_amount_threshold` the
+/// minimum output. Otherwise `amount` is the exact output and `other_amount_threshold`
+/// the maximum input. A zero `sqrt_price_limit_x64` means no price limit.
+/// Fails once `deadline` has passed. With a referral token account, `referral_fee_rate`
+/// of the trade fee goes to the referrer, up to the config's `max_referral_fee_rate`.
+///
+/// Accounts:
+/// 0. `[signer]` payer
+/// 1. `[]` amm config
+/// 2. `[writable]` pool
+/// 3. `[writable]` payer input token account
+/// 4. `[writable]` payer output token account
+/// 5. `[writable]` input token vault
+/// 6. `[writable]` output token vault
+/// 7. `[]` token program
+/// 8. `[]` payer allowlist entry, only for permissioned pools
+/// 9. `[writable]` optional referral token account for the input token
+/// 10. `[writable]` tick arrays, any number of them, starting with the one holding the current tick.
+///     Closed arrays are passed at their address and walked through as empty.
+#[allow(clippy::too_many_arguments)]
+pub fn process_swap(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    other_amount_threshold: u64,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+    deadline: Option<Deadline>,
+    referral_fee_rate: u32,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let payer = next_account_info(account_info_iter)?;
+    let amm_config_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let input_token_account = next_account_info(account_info_iter)?;
+    let output_token_account = next_account_info(account_info_iter)?;
+    let input_vault = next_account_info(account_info_iter)?;
+    let output_vault = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    check_signer(payer)?;
+    check_token_program(token_program)?;
+
+    let amm_config = AccountRef::<AmmConfig>::load(amm_config_info, program_id)?;
+    let mut pool = AccountMut::<PoolState>::load(pool_info, program_id)?;
+    if pool.amm_config != *amm_config_info.key {
+        return Err(ClmmError::InvalidAccountData.into());
+    }
+    let clock = Clock::get()?;
+    if let Some(deadline) = deadline {
+        deadline.check(&clock)?;
+    }
+    let now = clock.unix_timestamp;
+    pool.check_open(now)?;
+    if pool.is_permissioned() {
+        check_allowlisted(
+            program_id,
+            pool_info.key,
+            &pool,
+            payer.key,
+            Some(next_account_info(account_info_iter)?),
+            AllowlistEntry::PERMISSION_SWAP,
+        )?;
+    }
+    // Tick arrays are owned by this program, so a token account here can only be the referral
+    let referral_token_account = match account_info_iter.as_slice().first() {
+        Some(info) if *info.owner == spl_token_interface::ID => {
+            next_account_info(account_info_iter).ok()
+        }
+        _ => None,
+    };
+
+    let zero_for_one = if *input_vault.key == pool.token_vault_0
+        && *output_vault.key == pool.token_vault_1
+    {
+        true
+    } else if *input_vault.key == pool.token_vault_1 && *output_vault.key == pool.token_vault_0 {
+        false
+    } else {
+        return Err(ClmmError::InvalidAccountData.into());
+    };
+
+    let referral_fee_rate = match referral_token_account {
+        Some(_) if referral_fee_rate > amm_config.max_referral_fee_rate => {
+            return Err(ClmmError::InvalidFeeRate.into())
+        }
+        Some(_) => referral_fee_rate,
+        None => 0,
+    };
+    if let Some(referral_token_account) = referral_token_account {
+        let input_mint = if zero_for_one {
+            pool.token_mint_0
+        } else {
+            pool.token_mint_1
+        };
+        if unpack_token_account(referral_token_account)?.mint != input_mint {
+            return Err(ClmmError::InvalidAccountData.into());
+        }
+    }
+
+    let sqrt_price_limit_x64 = resolve_sqrt_price_limit(sqrt_price_limit_x64, zero_for_one);
+
+    // A repeated array would be swapped through twice from the same starting state
+    let tick_array_infos = account_info_iter.as_slice();
+    for (index, info) in tick_array_infos.iter().enumerate() {
+        if tick_array_infos[..index]
+            .iter()
+            .any(|other| other.key == info.key)
+        {
+            return Err(ClmmError::InvalidTickArray.into());
+        }
+    }
+    let mut tick_arrays = account_info_iter
+        .enumerate()
+        .map(|(position, info)| {
+            if info.owner != program_id {
+                return closed_tick_array(
+                    program_id,
+                    pool_info.key,
+                    &pool,
+                    info,
+                    position,
+                    zero_for_one,
+                )
+                .map(SwapTickArray::Closed);
+            }
+            let tick_array = AccountMut::<TickArrayState>::load(info, program_id)?;
+            if tick_array.pool_id != *pool_info.key {
+                return Err(ClmmError::InvalidTickArray.into());
+            }
+            Ok(SwapTickArray::Account(tick_array))
+        })
+        .collect::<Result<Vec<_>, ProgramError>>()?;
+
+    let sqrt_price_before_x64 = pool.sqrt_price_x64;
+    let result = swap_internal(
+        &amm_config,
+        &mut pool,
+        &mut tick_arrays,
+        amount,
+        sqrt_price_limit_x64,
+        zero_for_one,
+        is_base_input,
+        referral_fee_rate,
+    )?;
+
+    let (amount_0, amount_1) = if zero_for_one {
+        (result.amount_in, result.amount_out)
+    } else {
+        (result.amount_out, result.amount_in)
+    };
+    pool.check_launch_limits(now, amount_0, amount_1)?;
+
+    if is_base_input && result.amount_out < other_amount_threshold {
+        return Err(ClmmError::SlippageExceeded.into());
+    }
+    if !is_base_input && result.amount_in > other_amount_threshold {
+        return Err(ClmmError::SlippageExceeded.into());
+    }
+
+    // The referral share goes straight from the payer to the referrer
+    if let Some(referral_token_account) = referral_token_account {
+        transfer_from_user(
+            payer,
+            input_token_account,
+            referral_token_account,
+            token_program,
+            result.referral_fee_amount,
+        )?;
+    }
+    transfer_from_user(
+        payer,
+        input_token_account,
+        input_vault,
+        token_program,
+        result.amount_in - result.referral_fee_amount,
+    )?;
+    transfer_from_pool(
+        pool_info,
+        &pool.seeds(),
+        output_vault,
+        output_token_account,
+        token_program,
+        result.amount_out,
+    )?;
+
+    emit(&SwapEvent {
+        pool: *pool_info.key,
+        sender: *payer.key,
+        zero_for_one,
+        is_base_input,
+        amount_in: result.amount_in,
+        amount_out: result.amount_out,
+        fee_amount: result.fee_amount,
+        protocol_fee_amount: result.protocol_fee_amount,
+        fund_fee_amount: result.fund_fee_amount,
+        referral_fee_amount: result.referral_fee_amount,
+        sqrt_price_before_x64,
+        sqrt_price_after_x64: pool.sqrt_price_x64,
+        tick_after: pool.tick_current,
+        liquidity_after: pool.liquidity,
+    });
+    Ok(())
+}
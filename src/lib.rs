@@ -1,8 +1,15 @@
+solana_pubkey::declare_id!("CLMMdemoa5MiRpdCc2y4bQdB7BPaQgfNs2exbBJKmoDu");
+
+#[cfg(not(feature = "no-entrypoint"))]
 pub mod entrypoint;
 pub mod error;
+pub mod events;
 pub mod processor;
 pub mod state;
 pub mod libraries;
 pub mod instructions;
-mod instruction;
+pub mod instruction;
+pub mod pda;
+pub mod cpi;
+pub mod utils;
 
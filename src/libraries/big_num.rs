@@ -1,3 +1,6 @@
+// The macro expansion trips lints we cannot fix from here
+#![allow(clippy::manual_div_ceil, clippy::reversed_empty_ranges)]
+
 use uint::construct_uint;
 
 construct_uint!{
@@ -6,4 +9,8 @@ construct_uint!{
 
 construct_uint! {
     pub struct U128(2);
-}
\ No newline at end of file
+}
+
+construct_uint! {
+    pub struct U512(8);
+}
@@ -33,3 +33,71 @@ pub fn mul_div_round_up(a: u128, b: u128, denominator: u128) -> Result<u128, Clm
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::libraries::big_num::U512;
+    use proptest::prelude::*;
+
+    /// a * b / denominator in 512 bits, rounded down or up.
+    fn reference(a: u128, b: u128, denominator: u128, round_up: bool) -> U512 {
+        let product = U512::from(a) * U512::from(b);
+        let denominator = U512::from(denominator);
+        let quotient = product / denominator;
+        if round_up && product % denominator != U512::zero() {
+            quotient + 1
+        } else {
+            quotient
+        }
+    }
+
+    fn expected(a: u128, b: u128, denominator: u128, round_up: bool) -> Result<u128, ClmmError> {
+        let result = reference(a, b, denominator, round_up);
+        if result > U512::from(u128::MAX) {
+            Err(ClmmError::MathOverflow)
+        } else {
+            Ok(result.as_u128())
+        }
+    }
+
+    #[test]
+    fn test_mul_div_zero_denominator() {
+        assert_eq!(mul_div(1, 1, 0), Err(ClmmError::DivisionByZero));
+        assert_eq!(mul_div_round_up(1, 1, 0), Err(ClmmError::DivisionByZero));
+    }
+
+    #[test]
+    fn test_mul_div_round_up_at_max() {
+        assert_eq!(mul_div_round_up(u128::MAX, 1, 1), Ok(u128::MAX));
+        assert_eq!(
+            mul_div_round_up(u128::MAX, 2, 2),
+            Ok(u128::MAX),
+            "exact division must not round up"
+        );
+        assert_eq!(
+            mul_div_round_up(u128::MAX, 3, 2),
+            Err(ClmmError::MathOverflow)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn prop_mul_div_matches_reference(a: u128, b: u128, denominator in 1..=u128::MAX) {
+            prop_assert_eq!(mul_div(a, b, denominator), expected(a, b, denominator, false));
+        }
+
+        #[test]
+        fn prop_mul_div_round_up_matches_reference(a: u128, b: u128, denominator in 1..=u128::MAX) {
+            prop_assert_eq!(mul_div_round_up(a, b, denominator), expected(a, b, denominator, true));
+        }
+
+        #[test]
+        fn prop_mul_div_small_denominator(a: u128, b: u64, denominator in 1u128..=u64::MAX as u128) {
+            // Small denominators exercise the overflow path far more often
+            let b = b as u128;
+            prop_assert_eq!(mul_div(a, b, denominator), expected(a, b, denominator, false));
+            prop_assert_eq!(mul_div_round_up(a, b, denominator), expected(a, b, denominator, true));
+        }
+    }
+}
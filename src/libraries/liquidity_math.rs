@@ -1,10 +1,13 @@
-use crate::{error::ClmmError, libraries::U256};
+use crate::{
+    error::ClmmError,
+    libraries::{tick_math::get_sqrt_price_at_tick, U256, U512},
+};
 
 pub const Q64: u128 = 1u128 << 64;
 
 pub fn add_delta(liquidity: u128, delta: i128) -> Result<u128, ClmmError> {
     if delta < 0 {
-        let abs_delta = (-delta) as u128;
+        let abs_delta = delta.unsigned_abs();
         liquidity
             .checked_sub(abs_delta)
             .ok_or(ClmmError::MathOverflow)
@@ -22,7 +25,7 @@ pub fn get_liquidity_from_amount_0(
     sqrt_price_lower_x64: u128,
     sqrt_price_upper_x64: u128,
     amount_0: u64,
-) -> u128 {
+) -> Result<u128, ClmmError> {
     // Ensure lower < upper
     let (sqrt_lower, sqrt_upper) = if sqrt_price_lower_x64 < sqrt_price_upper_x64 {
         (sqrt_price_lower_x64, sqrt_price_upper_x64)
@@ -32,13 +35,17 @@ pub fn get_liquidity_from_amount_0(
 
     let diff = sqrt_upper - sqrt_lower;
     if diff == 0 {
-        return 0;
+        return Ok(0);
     }
 
-    let numerator = U256::from(amount_0) * U256::from(sqrt_lower) * U256::from(sqrt_upper);
-    let denominator = U256::from(diff) * U256::from(Q64);
+    let numerator = U512::from(amount_0) * U512::from(sqrt_lower) * U512::from(sqrt_upper);
+    let denominator = U512::from(diff) * U512::from(Q64);
 
-    (numerator / denominator).as_u128()
+    let result = numerator / denominator;
+    if result > U512::from(u128::MAX) {
+        return Err(ClmmError::MathOverflow);
+    }
+    Ok(result.as_u128())
 }
 
 /// How much liquidity will I create, if I deposit token1
@@ -48,7 +55,7 @@ pub fn get_liquidity_from_amount_1(
     sqrt_price_lower_x64: u128,
     sqrt_price_upper_x64: u128,
     amount_1: u64,
-) -> u128 {
+) -> Result<u128, ClmmError> {
     // Ensure lower < upper
     let (sqrt_lower, sqrt_upper) = if sqrt_price_lower_x64 < sqrt_price_upper_x64 {
         (sqrt_price_lower_x64, sqrt_price_upper_x64)
@@ -58,11 +65,15 @@ pub fn get_liquidity_from_amount_1(
 
     let diff = sqrt_upper - sqrt_lower;
     if diff == 0 {
-        return 0;
+        return Ok(0);
     }
 
     let numerator = U256::from(amount_1) * U256::from(Q64);
-    (numerator / U256::from(diff)).as_u128()
+    let result = numerator / U256::from(diff);
+    if result > U256::from(u128::MAX) {
+        return Err(ClmmError::MathOverflow);
+    }
+    Ok(result.as_u128())
 }
 
 ///If I have L liquidity, how much token0 is worth
@@ -87,16 +98,16 @@ pub fn get_delta_amount_0_unsigned(
 
     let diff = sqrt_upper - sqrt_lower;
 
-    let numerator = U256::from(liquidity) * U256::from(diff) * U256::from(Q64);
-    let denominator = U256::from(sqrt_lower) * U256::from(sqrt_upper);
+    let numerator = U512::from(liquidity) * U512::from(diff) * U512::from(Q64);
+    let denominator = U512::from(sqrt_lower) * U512::from(sqrt_upper);
 
     let result = if round_up {
-        (numerator + denominator - U256::from(1u64)) / denominator
+        (numerator + denominator - U512::from(1u64)) / denominator
     } else {
         numerator / denominator
     };
 
-    if result > U256::from(u64::MAX) {
+    if result > U512::from(u64::MAX) {
         return Err(ClmmError::MathOverflow);
     }
 
@@ -140,6 +151,87 @@ pub fn get_delta_amount_1_unsigned(
     Ok(result.as_u64())
 }
 
+/// Signs an unsigned token amount: positive when paid into the pool, negative when paid out
+fn signed_amount(amount: u64, liquidity_delta: i128) -> Result<i64, ClmmError> {
+    let amount = i64::try_from(amount).map_err(|_| ClmmError::MathOverflow)?;
+    Ok(if liquidity_delta < 0 { -amount } else { amount })
+}
+
+/// Token 0 owed for a liquidity change between two prices
+///
+/// Adding liquidity rounds up and removing it rounds down, so rounding always favors the pool.
+/// The result is positive when the pool receives tokens and negative when it pays them out.
+pub fn get_delta_amount_0_signed(
+    sqrt_price_lower_x64: u128,
+    sqrt_price_upper_x64: u128,
+    liquidity_delta: i128,
+) -> Result<i64, ClmmError> {
+    let amount = get_delta_amount_0_unsigned(
+        sqrt_price_lower_x64,
+        sqrt_price_upper_x64,
+        liquidity_delta.unsigned_abs(),
+        liquidity_delta > 0,
+    )?;
+    signed_amount(amount, liquidity_delta)
+}
+
+/// Token 1 owed for a liquidity change between two prices
+///
+/// Same rounding and sign conventions as [`get_delta_amount_0_signed`].
+pub fn get_delta_amount_1_signed(
+    sqrt_price_lower_x64: u128,
+    sqrt_price_upper_x64: u128,
+    liquidity_delta: i128,
+) -> Result<i64, ClmmError> {
+    let amount = get_delta_amount_1_unsigned(
+        sqrt_price_lower_x64,
+        sqrt_price_upper_x64,
+        liquidity_delta.unsigned_abs(),
+        liquidity_delta > 0,
+    )?;
+    signed_amount(amount, liquidity_delta)
+}
+
+/// Both token deltas for changing a position's liquidity in [tick_lower, tick_upper)
+///
+/// Below the range the position holds only token 0, above it only token 1,
+/// and in range the current price splits it between the two.
+pub fn get_delta_amounts_signed(
+    tick_current: i32,
+    sqrt_price_current_x64: u128,
+    tick_lower: i32,
+    tick_upper: i32,
+    liquidity_delta: i128,
+) -> Result<(i64, i64), ClmmError> {
+    if liquidity_delta == 0 {
+        return Ok((0, 0));
+    }
+    let sqrt_price_lower_x64 = get_sqrt_price_at_tick(tick_lower)?;
+    let sqrt_price_upper_x64 = get_sqrt_price_at_tick(tick_upper)?;
+
+    if tick_current < tick_lower {
+        let amount_0 =
+            get_delta_amount_0_signed(sqrt_price_lower_x64, sqrt_price_upper_x64, liquidity_delta)?;
+        Ok((amount_0, 0))
+    } else if tick_current < tick_upper {
+        let amount_0 = get_delta_amount_0_signed(
+            sqrt_price_current_x64,
+            sqrt_price_upper_x64,
+            liquidity_delta,
+        )?;
+        let amount_1 = get_delta_amount_1_signed(
+            sqrt_price_lower_x64,
+            sqrt_price_current_x64,
+            liquidity_delta,
+        )?;
+        Ok((amount_0, amount_1))
+    } else {
+        let amount_1 =
+            get_delta_amount_1_signed(sqrt_price_lower_x64, sqrt_price_upper_x64, liquidity_delta)?;
+        Ok((0, amount_1))
+    }
+}
+
 /// Compute liquidity from both token amounts based on current price
 /// Return the minimum of the two computed liquidities
 /// to ensure we don't require more tokens than the user has
@@ -149,7 +241,7 @@ pub fn get_liquidity_from_amounts(
     sqrt_price_current_x64: u128,
     amount_0: u64,
     amount_1: u64,
-) -> u128 {
+) -> Result<u128, ClmmError> {
     // Ensure lower < upper
     let (sqrt_lower, sqrt_upper) = if sqrt_price_lower_x64 < sqrt_price_upper_x64 {
         (sqrt_price_lower_x64, sqrt_price_upper_x64)
@@ -158,14 +250,155 @@ pub fn get_liquidity_from_amounts(
     };
 
     if sqrt_price_current_x64 <= sqrt_lower {
-        get_liquidity_from_amount_0(sqrt_lower, sqrt_price_upper_x64, amount_0)
+        get_liquidity_from_amount_0(sqrt_lower, sqrt_upper, amount_0)
     } else if sqrt_price_current_x64 >= sqrt_upper {
         get_liquidity_from_amount_1(sqrt_lower, sqrt_upper, amount_1)
     } else {
-        let liquidity_0 = get_liquidity_from_amount_0(sqrt_price_current_x64, sqrt_upper, amount_0);
-        let liquidity_1 = get_liquidity_from_amount_1(sqrt_lower, sqrt_price_current_x64, amount_1);
+        let liquidity_0 =
+            get_liquidity_from_amount_0(sqrt_price_current_x64, sqrt_upper, amount_0)?;
+        let liquidity_1 =
+            get_liquidity_from_amount_1(sqrt_lower, sqrt_price_current_x64, amount_1)?;
 
-        liquidity_0.min(liquidity_1)
+        Ok(liquidity_0.min(liquidity_1))
+    }
+}
+
+/// Liquidity for a deposit together with the token amounts the program charges for it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LiquidityQuote {
+    pub liquidity: u128,
+    pub amount_0: u64,
+    pub amount_1: u64,
+}
+
+impl LiquidityQuote {
+    /// Quote for adding `liquidity` to [tick_lower, tick_upper)
+    ///
+    /// Amounts are rounded up exactly as when the position is opened or increased.
+    pub fn from_liquidity(
+        tick_current: i32,
+        sqrt_price_current_x64: u128,
+        tick_lower: i32,
+        tick_upper: i32,
+        liquidity: u128,
+    ) -> Result<Self, ClmmError> {
+        let liquidity_delta = i128::try_from(liquidity).map_err(|_| ClmmError::MathOverflow)?;
+        let (amount_0, amount_1) = get_delta_amounts_signed(
+            tick_current,
+            sqrt_price_current_x64,
+            tick_lower,
+            tick_upper,
+            liquidity_delta,
+        )?;
+        Ok(Self {
+            liquidity,
+            amount_0: amount_0 as u64,
+            amount_1: amount_1 as u64,
+        })
+    }
+
+    /// Largest liquidity both maximum amounts can pay for, with the amounts actually charged
+    ///
+    /// The charged amounts never exceed the maximums.
+    pub fn from_amounts(
+        tick_current: i32,
+        sqrt_price_current_x64: u128,
+        tick_lower: i32,
+        tick_upper: i32,
+        amount_0_max: u64,
+        amount_1_max: u64,
+    ) -> Result<Self, ClmmError> {
+        let sqrt_price_lower_x64 = get_sqrt_price_at_tick(tick_lower)?;
+        let sqrt_price_upper_x64 = get_sqrt_price_at_tick(tick_upper)?;
+
+        let liquidity = if tick_current < tick_lower {
+            get_liquidity_from_amount_0(sqrt_price_lower_x64, sqrt_price_upper_x64, amount_0_max)?
+        } else if tick_current < tick_upper {
+            let liquidity_0 = get_liquidity_from_amount_0(
+                sqrt_price_current_x64,
+                sqrt_price_upper_x64,
+                amount_0_max,
+            )?;
+            let liquidity_1 = get_liquidity_from_amount_1(
+                sqrt_price_lower_x64,
+                sqrt_price_current_x64,
+                amount_1_max,
+            )?;
+            liquidity_0.min(liquidity_1)
+        } else {
+            get_liquidity_from_amount_1(sqrt_price_lower_x64, sqrt_price_upper_x64, amount_1_max)?
+        };
+        Self::from_liquidity(
+            tick_current,
+            sqrt_price_current_x64,
+            tick_lower,
+            tick_upper,
+            liquidity,
+        )
+    }
+
+    /// Liquidity bought with `amount_0`, and the token 1 amount needed alongside it
+    ///
+    /// Above the range the position holds no token 0, so the quote is empty.
+    pub fn from_amount_0(
+        tick_current: i32,
+        sqrt_price_current_x64: u128,
+        tick_lower: i32,
+        tick_upper: i32,
+        amount_0: u64,
+    ) -> Result<Self, ClmmError> {
+        if tick_current >= tick_upper {
+            return Ok(Self::default());
+        }
+        let sqrt_price_lower_x64 = if tick_current < tick_lower {
+            get_sqrt_price_at_tick(tick_lower)?
+        } else {
+            sqrt_price_current_x64
+        };
+        let liquidity = get_liquidity_from_amount_0(
+            sqrt_price_lower_x64,
+            get_sqrt_price_at_tick(tick_upper)?,
+            amount_0,
+        )?;
+        Self::from_liquidity(
+            tick_current,
+            sqrt_price_current_x64,
+            tick_lower,
+            tick_upper,
+            liquidity,
+        )
+    }
+
+    /// Liquidity bought with `amount_1`, and the token 0 amount needed alongside it
+    ///
+    /// Below the range the position holds no token 1, so the quote is empty.
+    pub fn from_amount_1(
+        tick_current: i32,
+        sqrt_price_current_x64: u128,
+        tick_lower: i32,
+        tick_upper: i32,
+        amount_1: u64,
+    ) -> Result<Self, ClmmError> {
+        if tick_current < tick_lower {
+            return Ok(Self::default());
+        }
+        let sqrt_price_upper_x64 = if tick_current < tick_upper {
+            sqrt_price_current_x64
+        } else {
+            get_sqrt_price_at_tick(tick_upper)?
+        };
+        let liquidity = get_liquidity_from_amount_1(
+            get_sqrt_price_at_tick(tick_lower)?,
+            sqrt_price_upper_x64,
+            amount_1,
+        )?;
+        Self::from_liquidity(
+            tick_current,
+            sqrt_price_current_x64,
+            tick_lower,
+            tick_upper,
+            liquidity,
+        )
     }
 }
 
@@ -173,6 +406,7 @@ pub fn get_liquidity_from_amounts(
 mod tests {
     use super::*;
     use crate::libraries::tick_math;
+    use proptest::prelude::*;
 
     #[test]
     fn test_add_delta_positive() {
@@ -189,6 +423,32 @@ mod tests {
         assert!(add_delta(50, -100).is_err());
     }
 
+    #[test]
+    fn test_add_delta_min_delta() {
+        assert_eq!(add_delta(u128::MAX, i128::MIN).unwrap(), u128::MAX >> 1);
+    }
+
+    #[test]
+    fn test_liquidity_from_amount_0_overflow() {
+        // One unit of price range at the top: the liquidity no longer fits in a u128
+        let sqrt_price_upper = tick_math::MAX_SQRT_PRICE_X64;
+        let sqrt_price_lower = sqrt_price_upper - 1;
+        assert_eq!(
+            get_liquidity_from_amount_0(sqrt_price_lower, sqrt_price_upper, u64::MAX),
+            Err(ClmmError::MathOverflow)
+        );
+    }
+
+    #[test]
+    fn test_delta_amount_0_large_liquidity() {
+        let sqrt_price_lower = tick_math::MIN_SQRT_PRICE_X64;
+        let sqrt_price_upper = tick_math::MAX_SQRT_PRICE_X64;
+        assert_eq!(
+            get_delta_amount_0_unsigned(sqrt_price_lower, sqrt_price_upper, u128::MAX, true),
+            Err(ClmmError::MathOverflow)
+        );
+    }
+
     #[test]
     fn test_liquidity_from_amount_1() {
         // At tick 0, sqrt_price = 2^64
@@ -197,7 +457,8 @@ mod tests {
         let sqrt_price_upper = tick_math::get_sqrt_price_at_tick(100).unwrap();
 
         let amount_1 = 1_000_000u64; // 1M tokens
-        let liquidity = get_liquidity_from_amount_1(sqrt_price_lower, sqrt_price_upper, amount_1);
+        let liquidity =
+            get_liquidity_from_amount_1(sqrt_price_lower, sqrt_price_upper, amount_1).unwrap();
 
         // Liquidity should be positive
         assert!(liquidity > 0);
@@ -208,11 +469,7 @@ mod tests {
                 .unwrap();
 
         // Should be close to original amount (within rounding)
-        let diff = if computed_amount > amount_1 {
-            computed_amount - amount_1
-        } else {
-            amount_1 - computed_amount
-        };
+        let diff = computed_amount.abs_diff(amount_1);
         assert!(diff <= 1, "Roundtrip error too large: {}", diff);
     }
 
@@ -222,7 +479,8 @@ mod tests {
         let sqrt_price_upper = tick_math::get_sqrt_price_at_tick(100).unwrap();
 
         let amount_0 = 1_000_000u64;
-        let liquidity = get_liquidity_from_amount_0(sqrt_price_lower, sqrt_price_upper, amount_0);
+        let liquidity =
+            get_liquidity_from_amount_0(sqrt_price_lower, sqrt_price_upper, amount_0).unwrap();
 
         assert!(liquidity > 0);
     }
@@ -240,7 +498,8 @@ mod tests {
             sqrt_price_current,
             1_000_000, // amount_0
             0,         // amount_1 (ignored when price below)
-        );
+        )
+        .unwrap();
 
         assert!(liquidity > 0);
     }
@@ -258,8 +517,298 @@ mod tests {
             sqrt_price_current,
             0,         // amount_0 (ignored when price above)
             1_000_000, // amount_1
-        );
+        )
+        .unwrap();
 
         assert!(liquidity > 0);
     }
+
+    #[test]
+    fn test_delta_amount_signed_rounds_in_pool_favor() {
+        let sqrt_price_lower = tick_math::get_sqrt_price_at_tick(-7).unwrap();
+        let sqrt_price_upper = tick_math::get_sqrt_price_at_tick(13).unwrap();
+
+        let add_0 =
+            get_delta_amount_0_signed(sqrt_price_lower, sqrt_price_upper, 1_000_003).unwrap();
+        let remove_0 =
+            get_delta_amount_0_signed(sqrt_price_lower, sqrt_price_upper, -1_000_003).unwrap();
+        assert!(add_0 > 0 && remove_0 < 0);
+        assert_eq!(add_0 + remove_0, 1);
+
+        let add_1 =
+            get_delta_amount_1_signed(sqrt_price_lower, sqrt_price_upper, 1_000_003).unwrap();
+        let remove_1 =
+            get_delta_amount_1_signed(sqrt_price_lower, sqrt_price_upper, -1_000_003).unwrap();
+        assert_eq!(add_1 + remove_1, 1);
+    }
+
+    #[test]
+    fn test_delta_amount_signed_overflow() {
+        let sqrt_price_lower = tick_math::get_sqrt_price_at_tick(0).unwrap();
+        let sqrt_price_upper = tick_math::get_sqrt_price_at_tick(tick_math::MAX_TICK).unwrap();
+        // Fits a u64 but not an i64
+        let liquidity =
+            get_liquidity_from_amount_1(sqrt_price_lower, sqrt_price_upper, u64::MAX).unwrap();
+        assert_eq!(
+            get_delta_amount_1_signed(sqrt_price_lower, sqrt_price_upper, liquidity as i128),
+            Err(ClmmError::MathOverflow)
+        );
+    }
+
+    #[test]
+    fn test_delta_amounts_signed_by_range() {
+        let liquidity = 10_000_000_000i128;
+        let sqrt_price = |tick| tick_math::get_sqrt_price_at_tick(tick).unwrap();
+
+        // Below the range only token 0
+        let (amount_0, amount_1) =
+            get_delta_amounts_signed(-200, sqrt_price(-200), -100, 100, liquidity).unwrap();
+        assert!(amount_0 > 0);
+        assert_eq!(amount_1, 0);
+
+        // Above the range only token 1
+        let (amount_0, amount_1) =
+            get_delta_amounts_signed(100, sqrt_price(100), -100, 100, -liquidity).unwrap();
+        assert_eq!(amount_0, 0);
+        assert!(amount_1 < 0);
+
+        // In range both, split at the current price
+        let (amount_0, amount_1) =
+            get_delta_amounts_signed(0, sqrt_price(0), -100, 100, liquidity).unwrap();
+        assert_eq!(
+            amount_0 as u64,
+            get_delta_amount_0_unsigned(sqrt_price(0), sqrt_price(100), liquidity as u128, true)
+                .unwrap()
+        );
+        assert_eq!(
+            amount_1 as u64,
+            get_delta_amount_1_unsigned(sqrt_price(-100), sqrt_price(0), liquidity as u128, true)
+                .unwrap()
+        );
+
+        assert_eq!(
+            get_delta_amounts_signed(0, sqrt_price(0), -100, 100, 0).unwrap(),
+            (0, 0)
+        );
+    }
+
+    #[test]
+    fn test_get_liquidity_from_amounts_unsorted_prices() {
+        let sqrt_price_current = tick_math::get_sqrt_price_at_tick(-200).unwrap();
+        let sqrt_price_lower = tick_math::get_sqrt_price_at_tick(-100).unwrap();
+        let sqrt_price_upper = tick_math::get_sqrt_price_at_tick(100).unwrap();
+
+        let sorted = get_liquidity_from_amounts(
+            sqrt_price_lower,
+            sqrt_price_upper,
+            sqrt_price_current,
+            1_000_000,
+            0,
+        )
+        .unwrap();
+        let reversed = get_liquidity_from_amounts(
+            sqrt_price_upper,
+            sqrt_price_lower,
+            sqrt_price_current,
+            1_000_000,
+            0,
+        )
+        .unwrap();
+        assert!(sorted > 0);
+        assert_eq!(sorted, reversed);
+    }
+
+    #[test]
+    fn test_quote_matches_charged_amounts() {
+        let sqrt_price_current = tick_math::get_sqrt_price_at_tick(17).unwrap();
+        let quote =
+            LiquidityQuote::from_amounts(17, sqrt_price_current, -120, 240, 1_000_000, 1_000_000)
+                .unwrap();
+
+        let (amount_0, amount_1) =
+            get_delta_amounts_signed(17, sqrt_price_current, -120, 240, quote.liquidity as i128)
+                .unwrap();
+        assert!(quote.liquidity > 0);
+        assert_eq!(
+            (quote.amount_0, quote.amount_1),
+            (amount_0 as u64, amount_1 as u64)
+        );
+        assert!(quote.amount_0 <= 1_000_000 && quote.amount_1 <= 1_000_000);
+        // One side is the binding constraint
+        assert!(quote.amount_0 >= 999_999 || quote.amount_1 >= 999_999);
+    }
+
+    #[test]
+    fn test_quote_from_single_amount() {
+        let sqrt_price_current = tick_math::get_sqrt_price_at_tick(0).unwrap();
+
+        let quote =
+            LiquidityQuote::from_amount_0(0, sqrt_price_current, -100, 100, 500_000).unwrap();
+        assert!(quote.amount_0 <= 500_000 && quote.amount_0 >= 499_999);
+        assert!(quote.amount_1 > 0);
+        // Depositing the quoted pair buys back the same liquidity
+        let both = LiquidityQuote::from_amounts(
+            0,
+            sqrt_price_current,
+            -100,
+            100,
+            quote.amount_0,
+            quote.amount_1,
+        )
+        .unwrap();
+        assert!(both.liquidity >= quote.liquidity);
+
+        let quote =
+            LiquidityQuote::from_amount_1(0, sqrt_price_current, -100, 100, 500_000).unwrap();
+        assert!(quote.amount_1 <= 500_000 && quote.amount_1 >= 499_999);
+        assert!(quote.amount_0 > 0);
+    }
+
+    #[test]
+    fn test_quote_from_single_amount_out_of_range() {
+        let sqrt_price = |tick| tick_math::get_sqrt_price_at_tick(tick).unwrap();
+
+        // Below the range only token 0 is deposited
+        let quote =
+            LiquidityQuote::from_amount_0(-200, sqrt_price(-200), -100, 100, 1_000).unwrap();
+        assert!(quote.liquidity > 0);
+        assert_eq!(quote.amount_1, 0);
+        assert_eq!(
+            LiquidityQuote::from_amount_1(-200, sqrt_price(-200), -100, 100, 1_000).unwrap(),
+            LiquidityQuote::default()
+        );
+
+        // Above the range only token 1
+        let quote = LiquidityQuote::from_amount_1(100, sqrt_price(100), -100, 100, 1_000).unwrap();
+        assert!(quote.liquidity > 0);
+        assert_eq!(quote.amount_0, 0);
+        assert_eq!(
+            LiquidityQuote::from_amount_0(100, sqrt_price(100), -100, 100, 1_000).unwrap(),
+            LiquidityQuote::default()
+        );
+    }
+
+    /// Two distinct tick-aligned sqrt prices, sorted.
+    fn sqrt_price_range() -> impl Strategy<Value = (u128, u128)> {
+        (tick_math::MIN_TICK..tick_math::MAX_TICK)
+            .prop_flat_map(|lower| (Just(lower), lower + 1..=tick_math::MAX_TICK))
+            .prop_map(|(lower, upper)| {
+                (
+                    tick_math::get_sqrt_price_at_tick(lower).unwrap(),
+                    tick_math::get_sqrt_price_at_tick(upper).unwrap(),
+                )
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_deposit_amount_0_never_exceeds_input(
+            (sqrt_lower, sqrt_upper) in sqrt_price_range(),
+            amount_0: u64,
+        ) {
+            let liquidity = get_liquidity_from_amount_0(sqrt_lower, sqrt_upper, amount_0).unwrap();
+            let charged = get_delta_amount_0_unsigned(sqrt_lower, sqrt_upper, liquidity, true).unwrap();
+            prop_assert!(charged <= amount_0);
+        }
+
+        #[test]
+        fn prop_deposit_amount_1_never_exceeds_input(
+            (sqrt_lower, sqrt_upper) in sqrt_price_range(),
+            amount_1: u64,
+        ) {
+            let liquidity = get_liquidity_from_amount_1(sqrt_lower, sqrt_upper, amount_1).unwrap();
+            let charged = get_delta_amount_1_unsigned(sqrt_lower, sqrt_upper, liquidity, true).unwrap();
+            prop_assert!(charged <= amount_1);
+        }
+
+        #[test]
+        fn prop_amount_0_for_liquidity_buys_it_back(
+            (sqrt_lower, sqrt_upper) in sqrt_price_range(),
+            seed: u128,
+        ) {
+            // Keep the liquidity small enough for its token amount to fit in a u64
+            let max_liquidity = get_liquidity_from_amount_0(sqrt_lower, sqrt_upper, u64::MAX).unwrap();
+            let liquidity = seed % (max_liquidity + 1);
+
+            let up = get_delta_amount_0_unsigned(sqrt_lower, sqrt_upper, liquidity, true).unwrap();
+            let down = get_delta_amount_0_unsigned(sqrt_lower, sqrt_upper, liquidity, false).unwrap();
+
+            // Withdrawals round down, deposits round up, by at most one unit
+            prop_assert!(down <= up && up - down <= 1);
+            // Paying the rounded-up amount always mints at least the requested liquidity
+            prop_assert!(get_liquidity_from_amount_0(sqrt_lower, sqrt_upper, up).unwrap() >= liquidity);
+        }
+
+        #[test]
+        fn prop_amount_1_for_liquidity_buys_it_back(
+            (sqrt_lower, sqrt_upper) in sqrt_price_range(),
+            seed: u128,
+        ) {
+            // Keep the liquidity small enough for its token amount to fit in a u64
+            let max_liquidity = get_liquidity_from_amount_1(sqrt_lower, sqrt_upper, u64::MAX).unwrap();
+            let liquidity = seed % (max_liquidity + 1);
+
+            let up = get_delta_amount_1_unsigned(sqrt_lower, sqrt_upper, liquidity, true).unwrap();
+            let down = get_delta_amount_1_unsigned(sqrt_lower, sqrt_upper, liquidity, false).unwrap();
+
+            prop_assert!(down <= up && up - down <= 1);
+            prop_assert!(get_liquidity_from_amount_1(sqrt_lower, sqrt_upper, up).unwrap() >= liquidity);
+        }
+
+        #[test]
+        fn prop_liquidity_from_amounts_is_covered_by_inputs(
+            (sqrt_lower, sqrt_upper) in sqrt_price_range(),
+            current_tick in tick_math::MIN_TICK..=tick_math::MAX_TICK,
+            amount_0: u64,
+            amount_1: u64,
+        ) {
+            let sqrt_current = tick_math::get_sqrt_price_at_tick(current_tick).unwrap();
+            let liquidity = get_liquidity_from_amounts(
+                sqrt_lower,
+                sqrt_upper,
+                sqrt_current,
+                amount_0,
+                amount_1,
+            )
+            .unwrap();
+
+            let (charged_0, charged_1) = if sqrt_current <= sqrt_lower {
+                (get_delta_amount_0_unsigned(sqrt_lower, sqrt_upper, liquidity, true).unwrap(), 0)
+            } else if sqrt_current >= sqrt_upper {
+                (0, get_delta_amount_1_unsigned(sqrt_lower, sqrt_upper, liquidity, true).unwrap())
+            } else {
+                (
+                    get_delta_amount_0_unsigned(sqrt_current, sqrt_upper, liquidity, true).unwrap(),
+                    get_delta_amount_1_unsigned(sqrt_lower, sqrt_current, liquidity, true).unwrap(),
+                )
+            };
+            prop_assert!(charged_0 <= amount_0);
+            prop_assert!(charged_1 <= amount_1);
+        }
+
+        #[test]
+        fn prop_quote_never_exceeds_maximums(
+            (tick_lower, tick_upper) in (-50_000i32..50_000).prop_flat_map(|lower| (Just(lower), lower + 1..=50_001)),
+            tick_current in -60_000i32..60_000,
+            amount_0_max in 0..=i64::MAX as u64,
+            amount_1_max in 0..=i64::MAX as u64,
+        ) {
+            let sqrt_current = tick_math::get_sqrt_price_at_tick(tick_current).unwrap();
+            let quote = LiquidityQuote::from_amounts(
+                tick_current,
+                sqrt_current,
+                tick_lower,
+                tick_upper,
+                amount_0_max,
+                amount_1_max,
+            );
+            // Liquidity too large for a signed delta is rejected rather than truncated
+            if let Ok(quote) = quote {
+                prop_assert!(quote.amount_0 <= amount_0_max);
+                prop_assert!(quote.amount_1 <= amount_1_max);
+            } else {
+                prop_assert_eq!(quote, Err(ClmmError::MathOverflow));
+            }
+        }
+    }
 }
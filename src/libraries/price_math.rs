@@ -0,0 +1,267 @@
+use std::fmt;
+
+use crate::error::ClmmError;
+use crate::libraries::big_num::U512;
+use crate::libraries::tick_math::{
+    get_sqrt_price_at_tick, get_tick_at_sqrt_price, MAX_SQRT_PRICE_X64, MAX_TICK,
+    MIN_SQRT_PRICE_X64, MIN_TICK,
+};
+
+/// A decimal price, `mantissa / 10^scale`
+///
+/// Prices are quoted in whole tokens, token 1 per token 0 unless inverted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DecimalPrice {
+    pub mantissa: u128,
+    pub scale: u8,
+}
+
+impl DecimalPrice {
+    pub fn new(mantissa: u128, scale: u8) -> Self {
+        Self { mantissa, scale }
+    }
+}
+
+impl fmt::Display for DecimalPrice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.mantissa.to_string();
+        let scale = self.scale as usize;
+        if scale == 0 {
+            return f.write_str(&digits);
+        }
+        let digits = format!("{digits:0>width$}", width = scale + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - scale);
+        write!(f, "{integer}.{fraction}")
+    }
+}
+
+fn pow10(exp: u32) -> Result<U512, ClmmError> {
+    U512::from(10u8)
+        .checked_pow(U512::from(exp))
+        .ok_or(ClmmError::MathOverflow)
+}
+
+fn checked_mul(a: U512, b: U512) -> Result<U512, ClmmError> {
+    a.checked_mul(b).ok_or(ClmmError::MathOverflow)
+}
+
+/// Decimal price for a sqrt price, rounded to the nearest unit of `scale` decimals
+///
+/// Formula: price = (sqrt_price_x64 / 2^64)^2 × 10^(decimals_0 - decimals_1)
+pub fn sqrt_price_x64_to_price(
+    sqrt_price_x64: u128,
+    mint_decimals_0: u8,
+    mint_decimals_1: u8,
+    inverted: bool,
+    scale: u8,
+) -> Result<DecimalPrice, ClmmError> {
+    if sqrt_price_x64 == 0 {
+        return Err(ClmmError::InvalidSqrtPrice);
+    }
+    let price_x128 = U512::from(sqrt_price_x64) * U512::from(sqrt_price_x64);
+    let q128 = U512::one() << 128;
+
+    let (numerator, denominator) = if inverted {
+        (
+            checked_mul(q128, pow10(mint_decimals_1 as u32 + scale as u32)?)?,
+            checked_mul(price_x128, pow10(mint_decimals_0 as u32)?)?,
+        )
+    } else {
+        (
+            checked_mul(price_x128, pow10(mint_decimals_0 as u32 + scale as u32)?)?,
+            checked_mul(q128, pow10(mint_decimals_1 as u32)?)?,
+        )
+    };
+    let mantissa = (numerator + (denominator >> 1)) / denominator;
+    if mantissa > U512::from(u128::MAX) {
+        return Err(ClmmError::MathOverflow);
+    }
+    Ok(DecimalPrice::new(mantissa.as_u128(), scale))
+}
+
+/// Sqrt price for a decimal price, rounded down
+pub fn price_to_sqrt_price_x64(
+    price: DecimalPrice,
+    mint_decimals_0: u8,
+    mint_decimals_1: u8,
+    inverted: bool,
+) -> Result<u128, ClmmError> {
+    if price.mantissa == 0 {
+        return Err(ClmmError::InvalidSqrtPrice);
+    }
+    let q128 = U512::one() << 128;
+
+    // Raw price in base units as Q128.128
+    let price_x128 = if inverted {
+        checked_mul(q128, pow10(price.scale as u32 + mint_decimals_1 as u32)?)?
+            / checked_mul(U512::from(price.mantissa), pow10(mint_decimals_0 as u32)?)?
+    } else {
+        checked_mul(
+            checked_mul(U512::from(price.mantissa), q128)?,
+            pow10(mint_decimals_1 as u32)?,
+        )? / pow10(price.scale as u32 + mint_decimals_0 as u32)?
+    };
+
+    let sqrt_price_x64 = price_x128.integer_sqrt();
+    if sqrt_price_x64 < U512::from(MIN_SQRT_PRICE_X64)
+        || sqrt_price_x64 >= U512::from(MAX_SQRT_PRICE_X64)
+    {
+        return Err(ClmmError::InvalidSqrtPrice);
+    }
+    Ok(sqrt_price_x64.as_u128())
+}
+
+/// Tick whose price range contains the decimal price
+///
+/// The tick is taken on the pool's own price, so an inverted quote lands on the
+/// same tick as the equivalent non-inverted one.
+pub fn price_to_tick(
+    price: DecimalPrice,
+    mint_decimals_0: u8,
+    mint_decimals_1: u8,
+    inverted: bool,
+) -> Result<i32, ClmmError> {
+    get_tick_at_sqrt_price(price_to_sqrt_price_x64(
+        price,
+        mint_decimals_0,
+        mint_decimals_1,
+        inverted,
+    )?)
+}
+
+/// Decimal price at a tick, rounded to the nearest unit of `scale` decimals
+pub fn tick_to_price(
+    tick: i32,
+    mint_decimals_0: u8,
+    mint_decimals_1: u8,
+    inverted: bool,
+    scale: u8,
+) -> Result<DecimalPrice, ClmmError> {
+    sqrt_price_x64_to_price(
+        get_sqrt_price_at_tick(tick)?,
+        mint_decimals_0,
+        mint_decimals_1,
+        inverted,
+        scale,
+    )
+}
+
+/// Nearest multiple of `tick_spacing`, kept within [MIN_TICK, MAX_TICK]
+pub fn round_tick_to_spacing(tick: i32, tick_spacing: u16) -> Result<i32, ClmmError> {
+    if tick_spacing == 0 {
+        return Err(ClmmError::InvalidTickSpacing);
+    }
+    let spacing = tick_spacing as i32;
+    let lower = tick.div_euclid(spacing) * spacing;
+    let rounded = if (tick - lower) * 2 >= spacing {
+        lower + spacing
+    } else {
+        lower
+    };
+
+    let max_usable = MAX_TICK / spacing * spacing;
+    let min_usable = MIN_TICK / spacing * spacing;
+    Ok(rounded.clamp(min_usable, max_usable))
+}
+
+/// Nearest tick usable as a position bound for the decimal price
+pub fn price_to_usable_tick(
+    price: DecimalPrice,
+    mint_decimals_0: u8,
+    mint_decimals_1: u8,
+    inverted: bool,
+    tick_spacing: u16,
+) -> Result<i32, ClmmError> {
+    round_tick_to_spacing(
+        price_to_tick(price, mint_decimals_0, mint_decimals_1, inverted)?,
+        tick_spacing,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(DecimalPrice::new(15025, 2).to_string(), "150.25");
+        assert_eq!(DecimalPrice::new(5, 3).to_string(), "0.005");
+        assert_eq!(DecimalPrice::new(42, 0).to_string(), "42");
+    }
+
+    #[test]
+    fn test_tick_zero_accounts_for_decimals() {
+        // One base unit of each token is worth the same, so 1 token 0 (6 decimals) buys 0.001 token 1 (9 decimals)
+        assert_eq!(
+            tick_to_price(0, 6, 9, false, 6).unwrap(),
+            DecimalPrice::new(1_000, 6)
+        );
+        assert_eq!(
+            tick_to_price(0, 6, 9, true, 0).unwrap(),
+            DecimalPrice::new(1_000, 0)
+        );
+    }
+
+    #[test]
+    fn test_price_round_trip() {
+        // 150.25 token 1 (6 decimals) per token 0 (9 decimals)
+        let price = DecimalPrice::new(15025, 2);
+        let sqrt_price_x64 = price_to_sqrt_price_x64(price, 9, 6, false).unwrap();
+        let back = sqrt_price_x64_to_price(sqrt_price_x64, 9, 6, false, 6).unwrap();
+        assert!(back.mantissa.abs_diff(150_250_000) <= 1);
+
+        let tick = price_to_tick(price, 9, 6, false).unwrap();
+        let lower = tick_to_price(tick, 9, 6, false, 6).unwrap();
+        let upper = tick_to_price(tick + 1, 9, 6, false, 6).unwrap();
+        assert!(lower.mantissa <= 150_250_000 && 150_250_000 < upper.mantissa);
+    }
+
+    #[test]
+    fn test_inverted_matches_tick() {
+        // 1 / 150.25 = 0.006655574043...
+        let inverted = DecimalPrice::new(6_655_574_043, 12);
+        let tick = price_to_tick(DecimalPrice::new(15025, 2), 9, 6, false).unwrap();
+        assert_eq!(price_to_tick(inverted, 9, 6, true).unwrap(), tick);
+
+        let quoted = tick_to_price(tick, 9, 6, true, 6).unwrap();
+        assert!(quoted.mantissa.abs_diff(6_655) <= 1);
+    }
+
+    #[test]
+    fn test_round_tick_to_spacing() {
+        assert_eq!(round_tick_to_spacing(14, 10).unwrap(), 10);
+        assert_eq!(round_tick_to_spacing(15, 10).unwrap(), 20);
+        assert_eq!(round_tick_to_spacing(-14, 10).unwrap(), -10);
+        assert_eq!(round_tick_to_spacing(-16, 10).unwrap(), -20);
+        assert_eq!(round_tick_to_spacing(MAX_TICK, 60).unwrap(), 443_580);
+        assert_eq!(round_tick_to_spacing(MIN_TICK, 60).unwrap(), -443_580);
+        assert_eq!(
+            round_tick_to_spacing(0, 0),
+            Err(ClmmError::InvalidTickSpacing)
+        );
+    }
+
+    #[test]
+    fn test_usable_tick() {
+        let price = DecimalPrice::new(15025, 2);
+        let tick = price_to_usable_tick(price, 9, 6, false, 60).unwrap();
+        assert_eq!(tick % 60, 0);
+        assert!((tick - price_to_tick(price, 9, 6, false).unwrap()).abs() <= 30);
+    }
+
+    #[test]
+    fn test_rejects_out_of_range_prices() {
+        assert_eq!(
+            price_to_sqrt_price_x64(DecimalPrice::new(0, 0), 6, 6, false),
+            Err(ClmmError::InvalidSqrtPrice)
+        );
+        assert_eq!(
+            price_to_sqrt_price_x64(DecimalPrice::new(u128::MAX, 0), 6, 6, false),
+            Err(ClmmError::InvalidSqrtPrice)
+        );
+        assert_eq!(
+            sqrt_price_x64_to_price(1 << 64, 0, 255, false, 0),
+            Err(ClmmError::MathOverflow)
+        );
+    }
+}
@@ -1 +1,202 @@
 use crate::error::ClmmError;
+use crate::libraries::big_num::U512;
+
+/// Get the next sqrt price after a token 0 amount is added to or removed from the pool
+///
+/// Formula: √P' = L × √P / (L ± Δx × √P)
+///
+/// Always rounds up, so the price moves less than the exact amount would move it:
+/// down by less when adding token 0, up by more when removing it.
+pub fn get_next_sqrt_price_from_amount_0_rounding_up(
+    sqrt_price_x64: u128,
+    liquidity: u128,
+    amount: u64,
+    add: bool,
+) -> Result<u128, ClmmError> {
+    if amount == 0 {
+        return Ok(sqrt_price_x64);
+    }
+
+    let numerator = U512::from(liquidity) << 64;
+    let product = U512::from(amount) * U512::from(sqrt_price_x64);
+    let denominator = if add {
+        numerator + product
+    } else {
+        // Removing all of the token 0 reserve (or more) is impossible
+        if product >= numerator {
+            return Err(ClmmError::InsufficientLiquidity);
+        }
+        numerator - product
+    };
+    if denominator.is_zero() {
+        return Err(ClmmError::DivisionByZero);
+    }
+
+    let full = numerator * U512::from(sqrt_price_x64);
+    let mut result = full / denominator;
+    if !(full % denominator).is_zero() {
+        result += U512::one();
+    }
+
+    if result > U512::from(u128::MAX) {
+        return Err(ClmmError::MathOverflow);
+    }
+    Ok(result.as_u128())
+}
+
+/// Get the next sqrt price after a token 1 amount is added to or removed from the pool
+///
+/// Formula: √P' = √P ± Δy / L
+///
+/// Always rounds down, so the price moves less than the exact amount would move it:
+/// up by less when adding token 1, down by more when removing it.
+pub fn get_next_sqrt_price_from_amount_1_rounding_down(
+    sqrt_price_x64: u128,
+    liquidity: u128,
+    amount: u64,
+    add: bool,
+) -> Result<u128, ClmmError> {
+    if amount == 0 {
+        return Ok(sqrt_price_x64);
+    }
+    if liquidity == 0 {
+        return Err(ClmmError::DivisionByZero);
+    }
+
+    let numerator = (amount as u128) << 64;
+    if add {
+        let quotient = numerator / liquidity;
+        sqrt_price_x64
+            .checked_add(quotient)
+            .ok_or(ClmmError::MathOverflow)
+    } else {
+        let quotient = numerator.div_ceil(liquidity);
+        if sqrt_price_x64 <= quotient {
+            return Err(ClmmError::InsufficientLiquidity);
+        }
+        Ok(sqrt_price_x64 - quotient)
+    }
+}
+
+/// Get the next sqrt price after swapping `amount_in` into the pool
+///
+/// zero_for_one - token 0 in (price moves down), otherwise token 1 in (price moves up)
+pub fn get_next_sqrt_price_from_input(
+    sqrt_price_x64: u128,
+    liquidity: u128,
+    amount_in: u64,
+    zero_for_one: bool,
+) -> Result<u128, ClmmError> {
+    if sqrt_price_x64 == 0 {
+        return Err(ClmmError::InvalidSqrtPrice);
+    }
+    if liquidity == 0 {
+        return Err(ClmmError::InsufficientLiquidity);
+    }
+
+    if zero_for_one {
+        get_next_sqrt_price_from_amount_0_rounding_up(sqrt_price_x64, liquidity, amount_in, true)
+    } else {
+        get_next_sqrt_price_from_amount_1_rounding_down(sqrt_price_x64, liquidity, amount_in, true)
+    }
+}
+
+/// Get the next sqrt price after taking `amount_out` out of the pool
+///
+/// zero_for_one - token 1 out (price moves down), otherwise token 0 out (price moves up)
+pub fn get_next_sqrt_price_from_output(
+    sqrt_price_x64: u128,
+    liquidity: u128,
+    amount_out: u64,
+    zero_for_one: bool,
+) -> Result<u128, ClmmError> {
+    if sqrt_price_x64 == 0 {
+        return Err(ClmmError::InvalidSqrtPrice);
+    }
+    if liquidity == 0 {
+        return Err(ClmmError::InsufficientLiquidity);
+    }
+
+    if zero_for_one {
+        get_next_sqrt_price_from_amount_1_rounding_down(
+            sqrt_price_x64,
+            liquidity,
+            amount_out,
+            false,
+        )
+    } else {
+        get_next_sqrt_price_from_amount_0_rounding_up(sqrt_price_x64, liquidity, amount_out, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::libraries::liquidity_math::{
+        get_delta_amount_0_unsigned, get_delta_amount_1_unsigned,
+    };
+    use crate::libraries::tick_math::{get_sqrt_price_at_tick, MAX_SQRT_PRICE_X64};
+
+    const Q64: u128 = 1 << 64;
+
+    #[test]
+    fn test_zero_amount_keeps_price() {
+        assert_eq!(
+            get_next_sqrt_price_from_input(Q64, 1_000, 0, true).unwrap(),
+            Q64
+        );
+        assert_eq!(
+            get_next_sqrt_price_from_output(Q64, 1_000, 0, false).unwrap(),
+            Q64
+        );
+    }
+
+    #[test]
+    fn test_zero_liquidity_is_rejected() {
+        assert_eq!(
+            get_next_sqrt_price_from_input(Q64, 0, 1, true),
+            Err(ClmmError::InsufficientLiquidity)
+        );
+    }
+
+    #[test]
+    fn test_input_moves_price_in_swap_direction() {
+        let sqrt_price = get_sqrt_price_at_tick(0).unwrap();
+        let liquidity = 1_000_000_000u128;
+
+        let down = get_next_sqrt_price_from_input(sqrt_price, liquidity, 1_000, true).unwrap();
+        let up = get_next_sqrt_price_from_input(sqrt_price, liquidity, 1_000, false).unwrap();
+        assert!(down < sqrt_price);
+        assert!(up > sqrt_price);
+
+        // The pool never takes in less than the price move is worth
+        assert!(get_delta_amount_0_unsigned(down, sqrt_price, liquidity, true).unwrap() <= 1_000);
+        assert!(get_delta_amount_1_unsigned(sqrt_price, up, liquidity, true).unwrap() <= 1_000);
+    }
+
+    #[test]
+    fn test_output_cannot_drain_reserves() {
+        let sqrt_price = get_sqrt_price_at_tick(0).unwrap();
+        let liquidity = 1_000u128;
+
+        // Token 1 reserve is about L * √P = 1000
+        assert_eq!(
+            get_next_sqrt_price_from_output(sqrt_price, liquidity, 1_000, true),
+            Err(ClmmError::InsufficientLiquidity)
+        );
+        assert_eq!(
+            get_next_sqrt_price_from_output(sqrt_price, liquidity, 1_000, false),
+            Err(ClmmError::InsufficientLiquidity)
+        );
+    }
+
+    #[test]
+    fn test_large_liquidity_does_not_wrap() {
+        // L << 64 no longer fits in a u128 here
+        let liquidity = u128::MAX / 2;
+        let sqrt_price =
+            get_next_sqrt_price_from_input(MAX_SQRT_PRICE_X64, liquidity, u64::MAX, true).unwrap();
+        assert!(sqrt_price < MAX_SQRT_PRICE_X64);
+        assert!(sqrt_price > MAX_SQRT_PRICE_X64 / 2);
+    }
+}
@@ -0,0 +1,214 @@
+use crate::error::ClmmError;
+use crate::libraries::full_math::{mul_div, mul_div_round_up};
+use crate::libraries::liquidity_math::{get_delta_amount_0_unsigned, get_delta_amount_1_unsigned};
+use crate::libraries::sqrt_price_math::{
+    get_next_sqrt_price_from_input, get_next_sqrt_price_from_output,
+};
+use crate::state::FEE_RATE_DENOMINATOR;
+
+/// Result of swapping within a single price range of constant liquidity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SwapStep {
+    pub sqrt_price_next_x64: u128,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+}
+
+/// Amount needed to move the price between two points, `None` if it does not fit in a u64
+fn delta_amount(
+    sqrt_price_a_x64: u128,
+    sqrt_price_b_x64: u128,
+    liquidity: u128,
+    round_up: bool,
+    token_0: bool,
+) -> Result<Option<u64>, ClmmError> {
+    let result = if token_0 {
+        get_delta_amount_0_unsigned(sqrt_price_a_x64, sqrt_price_b_x64, liquidity, round_up)
+    } else {
+        get_delta_amount_1_unsigned(sqrt_price_a_x64, sqrt_price_b_x64, liquidity, round_up)
+    };
+    match result {
+        Ok(amount) => Ok(Some(amount)),
+        Err(ClmmError::MathOverflow) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Compute the swap within one step, moving from the current price towards the target price
+///
+/// is_base_input - `amount_remaining` is the input amount (fee included), otherwise the output amount
+///
+/// The fee is charged on the input, rounded up. When the target price is not reached
+/// the whole remaining input is kept, so the difference is paid as fee.
+pub fn compute_swap_step(
+    sqrt_price_current_x64: u128,
+    sqrt_price_target_x64: u128,
+    liquidity: u128,
+    amount_remaining: u64,
+    fee_rate: u32,
+    is_base_input: bool,
+    zero_for_one: bool,
+) -> Result<SwapStep, ClmmError> {
+    if fee_rate >= FEE_RATE_DENOMINATOR {
+        return Err(ClmmError::InvalidFeeRate);
+    }
+    let mut step = SwapStep::default();
+
+    if is_base_input {
+        let amount_remaining_less_fee = mul_div(
+            amount_remaining as u128,
+            (FEE_RATE_DENOMINATOR - fee_rate) as u128,
+            FEE_RATE_DENOMINATOR as u128,
+        )? as u64;
+
+        let amount_in = delta_amount(
+            sqrt_price_current_x64,
+            sqrt_price_target_x64,
+            liquidity,
+            true,
+            zero_for_one,
+        )?;
+        step.sqrt_price_next_x64 = match amount_in {
+            Some(amount_in) if amount_remaining_less_fee >= amount_in => sqrt_price_target_x64,
+            _ => get_next_sqrt_price_from_input(
+                sqrt_price_current_x64,
+                liquidity,
+                amount_remaining_less_fee,
+                zero_for_one,
+            )?,
+        };
+    } else {
+        let amount_out = delta_amount(
+            sqrt_price_current_x64,
+            sqrt_price_target_x64,
+            liquidity,
+            false,
+            !zero_for_one,
+        )?;
+        step.sqrt_price_next_x64 = match amount_out {
+            Some(amount_out) if amount_remaining >= amount_out => sqrt_price_target_x64,
+            _ => get_next_sqrt_price_from_output(
+                sqrt_price_current_x64,
+                liquidity,
+                amount_remaining,
+                zero_for_one,
+            )?,
+        };
+    }
+
+    let max = step.sqrt_price_next_x64 == sqrt_price_target_x64;
+
+    // Amounts for the actual price move, input rounded up and output rounded down
+    step.amount_in = delta_amount(
+        sqrt_price_current_x64,
+        step.sqrt_price_next_x64,
+        liquidity,
+        true,
+        zero_for_one,
+    )?
+    .ok_or(ClmmError::MathOverflow)?;
+    step.amount_out = delta_amount(
+        sqrt_price_current_x64,
+        step.sqrt_price_next_x64,
+        liquidity,
+        false,
+        !zero_for_one,
+    )?
+    .ok_or(ClmmError::MathOverflow)?;
+
+    // Exact output must never hand out more than requested
+    if !is_base_input && step.amount_out > amount_remaining {
+        step.amount_out = amount_remaining;
+    }
+
+    step.fee_amount = if is_base_input && !max {
+        amount_remaining - step.amount_in
+    } else {
+        mul_div_round_up(
+            step.amount_in as u128,
+            fee_rate as u128,
+            (FEE_RATE_DENOMINATOR - fee_rate) as u128,
+        )?
+        .try_into()
+        .map_err(|_| ClmmError::MathOverflow)?
+    };
+
+    Ok(step)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::libraries::tick_math::get_sqrt_price_at_tick;
+
+    #[test]
+    fn test_exact_input_capped_at_target() {
+        let current = get_sqrt_price_at_tick(0).unwrap();
+        let target = get_sqrt_price_at_tick(-10).unwrap();
+        let step = compute_swap_step(
+            current,
+            target,
+            1_000_000_000,
+            u64::MAX / 2,
+            3000,
+            true,
+            true,
+        )
+        .unwrap();
+
+        assert_eq!(step.sqrt_price_next_x64, target);
+        assert!(step.amount_in > 0);
+        assert!(step.amount_out < step.amount_in);
+        // Fee is 0.3% of the gross input, rounded up
+        let gross = step.amount_in + step.fee_amount;
+        assert!(step.fee_amount >= gross * 3000 / 1_000_000);
+    }
+
+    #[test]
+    fn test_exact_input_consumes_remaining() {
+        let current = get_sqrt_price_at_tick(0).unwrap();
+        let target = get_sqrt_price_at_tick(1000).unwrap();
+        let step =
+            compute_swap_step(current, target, 1_000_000_000, 10_000, 3000, true, false).unwrap();
+
+        assert!(step.sqrt_price_next_x64 > current);
+        assert!(step.sqrt_price_next_x64 < target);
+        assert_eq!(step.amount_in + step.fee_amount, 10_000);
+        assert!(step.fee_amount >= 30);
+    }
+
+    #[test]
+    fn test_exact_output_never_exceeds_request() {
+        let current = get_sqrt_price_at_tick(0).unwrap();
+        let target = get_sqrt_price_at_tick(-1000).unwrap();
+        let step =
+            compute_swap_step(current, target, 1_000_000_000, 10_000, 3000, false, true).unwrap();
+
+        assert!(step.sqrt_price_next_x64 < current);
+        assert!(step.sqrt_price_next_x64 > target);
+        assert!(step.amount_out <= 10_000);
+        assert!(step.amount_in > step.amount_out);
+    }
+
+    #[test]
+    fn test_zero_liquidity_jumps_to_target() {
+        let current = get_sqrt_price_at_tick(0).unwrap();
+        let target = get_sqrt_price_at_tick(600).unwrap();
+        let step = compute_swap_step(current, target, 0, 1_000, 3000, true, false).unwrap();
+
+        assert_eq!(step.sqrt_price_next_x64, target);
+        assert_eq!(step.amount_in, 0);
+        assert_eq!(step.amount_out, 0);
+        assert_eq!(step.fee_amount, 0);
+    }
+
+    #[test]
+    fn test_rejects_full_fee() {
+        let current = get_sqrt_price_at_tick(0).unwrap();
+        assert_eq!(
+            compute_swap_step(current, current, 1, 1, FEE_RATE_DENOMINATOR, true, true),
+            Err(ClmmError::InvalidFeeRate)
+        );
+    }
+}
@@ -22,7 +22,7 @@ pub const MAX_SQRT_PRICE_X64: u128 = 79226673521066979257578248091;
 ///
 /// Magic numbers are `2^64 / sqrt(1.0001^(2^i))` for i in [0, 18).
 pub fn get_sqrt_price_at_tick(tick: i32) -> Result<u128, ClmmError> {
-    if tick < MIN_TICK || tick > MAX_TICK {
+    if !(MIN_TICK..=MAX_TICK).contains(&tick) {
         return Err(ClmmError::TickOutOfBounds);
     }
 
@@ -125,96 +125,74 @@ pub fn get_sqrt_price_at_tick(tick: i32) -> Result<u128, ClmmError> {
     Ok(ratio.as_u128())
 }
 
+/// Number of fractional bits computed for log2(sqrt_price)
+const BIT_PRECISION: u32 = 16;
+
 /// Get the tick at a given sqrt price
 ///
 /// This is the inverse of get_sqrt_price_at_tick.
 /// Returns the largest tick such that get_sqrt_price_at_tick(tick) <= sqrt_price_x64
 ///
-/// Uses binary search for correctness and simplicity.
+/// Computes log2(sqrt_price) in Q32.32, converts it to log_sqrt(1.0001) and
+/// narrows the result down to two candidate ticks. A single call to
+/// get_sqrt_price_at_tick picks the right one.
 pub fn get_tick_at_sqrt_price(sqrt_price_x64: u128) -> Result<i32, ClmmError> {
-    if sqrt_price_x64 < MIN_SQRT_PRICE_X64 || sqrt_price_x64 > MAX_SQRT_PRICE_X64 {
+    if !(MIN_SQRT_PRICE_X64..=MAX_SQRT_PRICE_X64).contains(&sqrt_price_x64) {
         return Err(ClmmError::InvalidSqrtPrice);
     }
-    // This is simpler and more reliable than the logarithm approach
-    let mut low = MIN_TICK;
-    let mut high = MAX_TICK;
 
-    while low < high {
-        let mid = low + (high - low + 1) / 2;
-        let sqrt_price_at_mid = get_sqrt_price_at_tick(mid)?;
+    // Integer part of log2, in Q32.32
+    let msb = 127 - sqrt_price_x64.leading_zeros();
+    let log2p_integer_x32 = (msb as i128 - 64) << 32;
 
-        if sqrt_price_at_mid <= sqrt_price_x64 {
-            low = mid;
-        } else {
-            high = mid - 1;
-        }
-    }
+    // Normalize the mantissa to [1, 2) in Q1.63
+    let mut r = if msb >= 64 {
+        sqrt_price_x64 >> (msb - 63)
+    } else {
+        sqrt_price_x64 << (63 - msb)
+    };
 
-    Ok(low)
+    // Fractional part of log2 by repeated squaring: each square doubles the
+    // log, and a result >= 2 means the next fractional bit is set.
+    let mut log2p_fraction_x64 = 0i128;
+    let mut bit = 1i128 << 63;
+    for _ in 0..BIT_PRECISION {
+        r *= r;
+        let is_r_more_than_two = (r >> 127) as u32;
+        r >>= 63 + is_r_more_than_two;
+        log2p_fraction_x64 += bit * is_r_more_than_two as i128;
+        bit >>= 1;
+    }
+    let log2p_x32 = log2p_integer_x32 + (log2p_fraction_x64 >> 32);
+
+    // 2^64 / log2(sqrt(1.0001)) in Q32.32, so the product is log_sqrt(1.0001) in Q64.64
+    let log_sqrt_10001_x64 = log2p_x32 * 59543866431248i128;
+
+    // Error bounds of the truncated log2, 0.01 below and ~0.856 above
+    let tick_low = ((log_sqrt_10001_x64 - 184467440737095516i128) >> 64) as i32;
+    let tick_high = ((log_sqrt_10001_x64 + 15793534762490258745i128) >> 64) as i32;
+
+    if tick_low == tick_high {
+        Ok(tick_low)
+    } else if tick_high <= MAX_TICK && get_sqrt_price_at_tick(tick_high)? <= sqrt_price_x64 {
+        Ok(tick_high)
+    } else {
+        Ok(tick_low)
+    }
 }
 
 pub fn check_tick_in_bounds(tick: i32) -> bool {
-    tick >= MIN_TICK && tick <= MAX_TICK
+    (MIN_TICK..=MAX_TICK).contains(&tick)
 }
 
 pub fn check_tick_aligned(tick: i32, tick_spacing: i32) -> bool {
     tick % tick_spacing == 0
 }
 
-pub fn get_next_sqrt_price_from_input(
-    sqrt_price_x64: u128,
-    liquidity: u128,
-    amount: u64,
-    zero_for_one: bool,
-) -> Result<u128, ClmmError> {
-    //TODO
-    Ok(0)
-}
-
-pub fn get_next_sqrt_price_from_amount_0_rounding_up(
-    sqrt_price_x64: u128,
-    liquidity: u128,
-    amount: u64,
-) -> Result<u128, ClmmError> {
-    if amount == 0 {
-        return Ok(sqrt_price_x64);
-    }
-
-    let numerator = (liquidity as u128) << 64;
-    let product = (amount as u128)
-        .checked_mul(sqrt_price_x64)
-        .ok_or(ClmmError::MathOverflow)?;
-    let denominator = numerator
-        .checked_add(product)
-        .ok_or(ClmmError::MathOverflow)?;
-
-    let result =
-        crate::libraries::full_math::mul_div_round_up(numerator, sqrt_price_x64, denominator)?;
-
-    Ok(result)
-}
-
-pub fn get_next_sqrt_price_from_amount_1_rounding_down(
-    sqrt_price_x64: u128,
-    liquidity: u128,
-    amount: u64,
-) -> Result<u128, ClmmError> {
-    if amount == 0 {
-        return Ok(sqrt_price_x64);
-    }
-
-    let delta = ((amount as u128) << 64)
-        .checked_div(liquidity)
-        .ok_or(ClmmError::DivisionByZero)?;
-
-    sqrt_price_x64
-        .checked_add(delta)
-        .ok_or(ClmmError::MathOverflow)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_get_sqrt_price_at_tick_min() {
@@ -234,12 +212,8 @@ mod tests {
         // In Q64.64: 2^64 = 18446744073709551616
         let sqrt_price = get_sqrt_price_at_tick(0).unwrap();
         let expected = 1u128 << 64; // 2^64
-                                    // Allow small rounding error
-        let diff = if sqrt_price > expected {
-            sqrt_price - expected
-        } else {
-            expected - sqrt_price
-        };
+        // Allow small rounding error
+        let diff = sqrt_price.abs_diff(expected);
         assert!(diff < 1000, "sqrt_price at tick 0 should be ~2^64");
     }
 
@@ -306,6 +280,63 @@ mod tests {
         assert!(!check_tick_aligned(-5, 10));
     }
 
+    /// The binary search get_tick_at_sqrt_price used to run, kept as the reference.
+    fn get_tick_at_sqrt_price_reference(sqrt_price_x64: u128) -> i32 {
+        let mut low = MIN_TICK;
+        let mut high = MAX_TICK;
+        while low < high {
+            let mid = low + (high - low + 1) / 2;
+            if get_sqrt_price_at_tick(mid).unwrap() <= sqrt_price_x64 {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        low
+    }
+
+    fn assert_matches_reference_around_tick(tick: i32) {
+        let sqrt_price = get_sqrt_price_at_tick(tick).unwrap();
+        for price in [sqrt_price - 1, sqrt_price, sqrt_price + 1] {
+            if !(MIN_SQRT_PRICE_X64..=MAX_SQRT_PRICE_X64).contains(&price) {
+                continue;
+            }
+            assert_eq!(
+                get_tick_at_sqrt_price(price).unwrap(),
+                get_tick_at_sqrt_price_reference(price),
+                "Mismatch at sqrt price {} near tick {}",
+                price,
+                tick
+            );
+        }
+    }
+
+    #[test]
+    fn test_get_tick_at_sqrt_price_matches_binary_search() {
+        for tick in (MIN_TICK..=MAX_TICK).step_by(997) {
+            assert_matches_reference_around_tick(tick);
+        }
+        for tick in [MIN_TICK, MIN_TICK + 1, -1, 0, 1, MAX_TICK - 1, MAX_TICK] {
+            assert_matches_reference_around_tick(tick);
+        }
+    }
+
+    #[test]
+    #[ignore = "walks the whole tick range, run with --release -- --ignored"]
+    fn test_get_tick_at_sqrt_price_matches_binary_search_exhaustive() {
+        for tick in MIN_TICK..=MAX_TICK {
+            assert_matches_reference_around_tick(tick);
+        }
+    }
+
+    #[test]
+    fn test_get_tick_at_sqrt_price_bounds() {
+        assert_eq!(get_tick_at_sqrt_price(MIN_SQRT_PRICE_X64).unwrap(), MIN_TICK);
+        assert_eq!(get_tick_at_sqrt_price(MAX_SQRT_PRICE_X64).unwrap(), MAX_TICK);
+        assert!(get_tick_at_sqrt_price(MIN_SQRT_PRICE_X64 - 1).is_err());
+        assert!(get_tick_at_sqrt_price(MAX_SQRT_PRICE_X64 + 1).is_err());
+    }
+
     #[test]
     fn test_roundtrip_various_ticks() {
         // Test multiple ticks across the range
@@ -317,4 +348,27 @@ mod tests {
             assert_eq!(tick, recovered_tick, "Roundtrip failed for tick {}", tick);
         }
     }
+
+    proptest! {
+        #[test]
+        fn prop_tick_roundtrip(tick in MIN_TICK..=MAX_TICK) {
+            let sqrt_price = get_sqrt_price_at_tick(tick).unwrap();
+            prop_assert_eq!(get_tick_at_sqrt_price(sqrt_price).unwrap(), tick);
+        }
+
+        #[test]
+        fn prop_sqrt_price_strictly_increasing(tick in MIN_TICK..MAX_TICK, step in 1i32..=1000) {
+            let next = (tick + step).min(MAX_TICK);
+            prop_assert!(get_sqrt_price_at_tick(tick).unwrap() < get_sqrt_price_at_tick(next).unwrap());
+        }
+
+        #[test]
+        fn prop_tick_brackets_sqrt_price(sqrt_price in MIN_SQRT_PRICE_X64..=MAX_SQRT_PRICE_X64) {
+            let tick = get_tick_at_sqrt_price(sqrt_price).unwrap();
+            prop_assert!(get_sqrt_price_at_tick(tick).unwrap() <= sqrt_price);
+            if tick < MAX_TICK {
+                prop_assert!(get_sqrt_price_at_tick(tick + 1).unwrap() > sqrt_price);
+            }
+        }
+    }
 }
@@ -0,0 +1,86 @@
+//! Address derivation for every program-derived account.
+
+use solana_pubkey::Pubkey;
+
+use crate::state::{
+    ALLOWLIST_ENTRY_SEED, BUNDLED_POSITION_SEED, CONFIG_SEED, FEE_TIER_REGISTRY_SEED, POOL_SEED,
+    POOL_VAULT_SEED, POSITION_BUNDLE_SEED, POSITION_SEED, TICK_ARRAY_SEED,
+};
+
+pub fn find_fee_tier_registry_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FEE_TIER_REGISTRY_SEED], program_id)
+}
+
+pub fn find_amm_config_address(program_id: &Pubkey, index: u16) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG_SEED, &index.to_be_bytes()], program_id)
+}
+
+pub fn find_pool_address(
+    program_id: &Pubkey,
+    amm_config: &Pubkey,
+    token_mint_0: &Pubkey,
+    token_mint_1: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            POOL_SEED,
+            amm_config.as_ref(),
+            token_mint_0.as_ref(),
+            token_mint_1.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+pub fn find_pool_vault_address(program_id: &Pubkey, pool: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POOL_VAULT_SEED, pool.as_ref(), mint.as_ref()], program_id)
+}
+
+pub fn find_allowlist_entry_address(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    wallet: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ALLOWLIST_ENTRY_SEED, pool.as_ref(), wallet.as_ref()],
+        program_id,
+    )
+}
+
+pub fn find_position_address(program_id: &Pubkey, nft_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POSITION_SEED, nft_mint.as_ref()], program_id)
+}
+
+pub fn find_position_bundle_address(program_id: &Pubkey, bundle_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POSITION_BUNDLE_SEED, bundle_mint.as_ref()], program_id)
+}
+
+pub fn find_bundled_position_address(
+    program_id: &Pubkey,
+    bundle_mint: &Pubkey,
+    bundle_index: u16,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            BUNDLED_POSITION_SEED,
+            bundle_mint.as_ref(),
+            &bundle_index.to_be_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn find_tick_array_address(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    start_tick_index: i32,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            TICK_ARRAY_SEED,
+            pool.as_ref(),
+            &start_tick_index.to_be_bytes(),
+        ],
+        program_id,
+    )
+}
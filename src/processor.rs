@@ -21,15 +21,42 @@ pub fn process_instruction(
             trade_fee_rate,
             protocol_fee_rate,
             tick_spacing,
+            fund_fee_rate,
+            fund_owner,
+            max_referral_fee_rate,
         } => {
             msg!("Instruction: InitializeConfig");
-
-            Ok(())
+            process_initialize_config(
+                program_id,
+                accounts,
+                index,
+                trade_fee_rate,
+                protocol_fee_rate,
+                fund_fee_rate,
+                fund_owner,
+                max_referral_fee_rate,
+                tick_spacing,
+            )
         }
-        ClmmInstruction::CreatePool { sqrt_price_x64 } => {
+        ClmmInstruction::CreatePool {
+            sqrt_price_x64,
+            permission_authority,
+            open_time,
+            launch_duration,
+            launch_max_amount_0,
+            launch_max_amount_1,
+        } => {
             msg!("Instruction: CreatePool");
-            // TODO: Implement
-            Ok(())
+            process_create_pool(
+                program_id,
+                accounts,
+                sqrt_price_x64,
+                permission_authority,
+                open_time,
+                launch_duration,
+                launch_max_amount_0,
+                launch_max_amount_1,
+            )
         }
         ClmmInstruction::OpenPosition {
             tick_lower,
@@ -37,39 +64,214 @@ pub fn process_instruction(
             liquidity,
             amount_0_max,
             amount_1_max,
+            deadline,
+            sqrt_price_band,
         } => {
             msg!("Instruction: OpenPosition");
-            Ok(())
+            process_open_position(
+                program_id,
+                accounts,
+                tick_lower,
+                tick_upper,
+                liquidity,
+                amount_0_max,
+                amount_1_max,
+                deadline,
+                sqrt_price_band,
+            )
         }
         ClmmInstruction::IncreaseLiquidity {
             liquidity,
             amount_0_max,
             amount_1_max,
+            deadline,
+            sqrt_price_band,
         } => {
             msg!("Instruction: IncreaseLiquidity");
-            Ok(())
+            process_increase_liquidity(
+                program_id,
+                accounts,
+                liquidity,
+                amount_0_max,
+                amount_1_max,
+                deadline,
+                sqrt_price_band,
+            )
         }
         ClmmInstruction::DecreaseLiquidity {
             liquidity,
             amount_0_min,
             amount_1_min,
+            deadline,
+            sqrt_price_band,
         } => {
             msg!("Instruction: DecreaseLiquidity");
-
-            Ok(())
+            process_decrease_liquidity(
+                program_id,
+                accounts,
+                liquidity,
+                amount_0_min,
+                amount_1_min,
+                deadline,
+                sqrt_price_band,
+            )
         }
         ClmmInstruction::Swap {
             amount_in,
             minimum_amount_out,
             sqrt_price_limit_x64,
             is_base_input,
+            deadline,
+            referral_fee_rate,
         } => {
             msg!("Instruction: Swap");
-            Ok(())
+            process_swap(
+                program_id,
+                accounts,
+                amount_in,
+                minimum_amount_out,
+                sqrt_price_limit_x64,
+                is_base_input,
+                deadline,
+                referral_fee_rate,
+            )
         }
         ClmmInstruction::CollectFees => {
             msg!("Instruction: CollectFees");
-            Ok(())
+            process_collect_fees(program_id, accounts)
+        }
+        ClmmInstruction::MigrateAccount => {
+            msg!("Instruction: MigrateAccount");
+            process_migrate_account(program_id, accounts)
+        }
+        ClmmInstruction::InitializeFeeTierRegistry => {
+            msg!("Instruction: InitializeFeeTierRegistry");
+            process_initialize_fee_tier_registry(program_id, accounts)
+        }
+        ClmmInstruction::SetFeeTierEnabled { enabled } => {
+            msg!("Instruction: SetFeeTierEnabled");
+            process_set_fee_tier_enabled(program_id, accounts, enabled)
+        }
+        ClmmInstruction::ClosePosition => {
+            msg!("Instruction: ClosePosition");
+            process_close_position(program_id, accounts)
+        }
+        ClmmInstruction::CloseTickArray => {
+            msg!("Instruction: CloseTickArray");
+            process_close_tick_array(program_id, accounts)
+        }
+        ClmmInstruction::InitializePositionBundle => {
+            msg!("Instruction: InitializePositionBundle");
+            process_initialize_position_bundle(program_id, accounts)
+        }
+        ClmmInstruction::OpenBundledPosition {
+            bundle_index,
+            tick_lower,
+            tick_upper,
+            liquidity,
+            amount_0_max,
+            amount_1_max,
+            deadline,
+            sqrt_price_band,
+        } => {
+            msg!("Instruction: OpenBundledPosition");
+            process_open_bundled_position(
+                program_id,
+                accounts,
+                bundle_index,
+                tick_lower,
+                tick_upper,
+                liquidity,
+                amount_0_max,
+                amount_1_max,
+                deadline,
+                sqrt_price_band,
+            )
+        }
+        ClmmInstruction::CloseBundledPosition { bundle_index } => {
+            msg!("Instruction: CloseBundledPosition");
+            process_close_bundled_position(program_id, accounts, bundle_index)
+        }
+        ClmmInstruction::ClosePositionBundle => {
+            msg!("Instruction: ClosePositionBundle");
+            process_close_position_bundle(program_id, accounts)
+        }
+        ClmmInstruction::CompoundFees {
+            deadline,
+            sqrt_price_band,
+        } => {
+            msg!("Instruction: CompoundFees");
+            process_compound_fees(program_id, accounts, deadline, sqrt_price_band)
+        }
+        ClmmInstruction::Reposition {
+            tick_lower,
+            tick_upper,
+            liquidity_min,
+            amount_0_leftover_max,
+            amount_1_leftover_max,
+            deadline,
+            sqrt_price_band,
+        } => {
+            msg!("Instruction: Reposition");
+            process_reposition(
+                program_id,
+                accounts,
+                tick_lower,
+                tick_upper,
+                liquidity_min,
+                amount_0_leftover_max,
+                amount_1_leftover_max,
+                deadline,
+                sqrt_price_band,
+            )
+        }
+        ClmmInstruction::CollectFundFees {
+            amount_0_requested,
+            amount_1_requested,
+        } => {
+            msg!("Instruction: CollectFundFees");
+            process_collect_fund_fees(program_id, accounts, amount_0_requested, amount_1_requested)
+        }
+        ClmmInstruction::SetMaxReferralFeeRate {
+            max_referral_fee_rate,
+        } => {
+            msg!("Instruction: SetMaxReferralFeeRate");
+            process_set_max_referral_fee_rate(program_id, accounts, max_referral_fee_rate)
+        }
+        ClmmInstruction::AddToAllowlist {
+            wallet,
+            permissions,
+        } => {
+            msg!("Instruction: AddToAllowlist");
+            process_add_to_allowlist(program_id, accounts, wallet, permissions)
+        }
+        ClmmInstruction::RemoveFromAllowlist => {
+            msg!("Instruction: RemoveFromAllowlist");
+            process_remove_from_allowlist(program_id, accounts)
+        }
+        ClmmInstruction::SetPoolFeeRate { trade_fee_rate } => {
+            msg!("Instruction: SetPoolFeeRate");
+            process_set_pool_fee_rate(program_id, accounts, trade_fee_rate)
+        }
+        ClmmInstruction::InitializeTickArray { start_tick_index } => {
+            msg!("Instruction: InitializeTickArray");
+            process_initialize_tick_array(program_id, accounts, start_tick_index)
+        }
+        ClmmInstruction::QuoteSwap {
+            amount,
+            sqrt_price_limit_x64,
+            zero_for_one,
+            is_base_input,
+        } => {
+            msg!("Instruction: QuoteSwap");
+            process_quote_swap(
+                program_id,
+                accounts,
+                amount,
+                sqrt_price_limit_x64,
+                zero_for_one,
+                is_base_input,
+            )
         }
     }
 }
@@ -0,0 +1,101 @@
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_account_info::AccountInfo;
+use solana_program_error::ProgramResult;
+use solana_pubkey::Pubkey;
+
+use super::{AccountRef, AccountState, PoolState};
+use crate::error::ClmmError;
+
+pub const ALLOWLIST_ENTRY_SEED: &[u8] = b"allowlist_entry";
+
+/// Lets a wallet use a permissioned pool, with what it may do kept in `permissions`.
+///
+/// Entries live at the PDA of the pool and wallet, and are closed once the wallet is removed.
+#[derive(BorshDeserialize, BorshSchema, BorshSerialize, Debug, Clone, Default)]
+#[repr(C)]
+pub struct AllowlistEntry {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub bump: u8,
+    pub permissions: u8,
+    pub padding: [u8; 5],
+    pub pool: Pubkey,
+    pub wallet: Pubkey,
+    pub padding_1: [u64; 4],
+}
+
+impl AllowlistEntry {
+    pub const LEN: usize = 8 + 1 + 1 + 1 + 5 + 32 + 32 + 32;
+    pub const VERSION: u8 = 1;
+    pub const DISCRIMINATOR: [u8; 8] = [0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+
+    /// May swap against the pool
+    pub const PERMISSION_SWAP: u8 = 1 << 0;
+    /// May open positions and add liquidity
+    pub const PERMISSION_LIQUIDITY: u8 = 1 << 1;
+    pub const PERMISSION_ALL: u8 = Self::PERMISSION_SWAP | Self::PERMISSION_LIQUIDITY;
+
+    pub fn has_permission(&self, permission: u8) -> bool {
+        self.permissions & permission == permission
+    }
+}
+
+impl AccountState for AllowlistEntry {
+    const DISCRIMINATOR: [u8; 8] = AllowlistEntry::DISCRIMINATOR;
+    const LEN: usize = AllowlistEntry::LEN;
+    const VERSION: u8 = AllowlistEntry::VERSION;
+
+    fn bump(&self) -> Option<u8> {
+        Some(self.bump)
+    }
+}
+
+/// Checks that `wallet` holds `permission` on a permissioned pool.
+///
+/// Permissionless pools accept anyone, so `entry` is only read for permissioned ones.
+pub fn check_allowlisted(
+    program_id: &Pubkey,
+    pool_key: &Pubkey,
+    pool: &PoolState,
+    wallet: &Pubkey,
+    entry: Option<&AccountInfo>,
+    permission: u8,
+) -> ProgramResult {
+    if !pool.is_permissioned() {
+        return Ok(());
+    }
+    let entry_info = entry.ok_or(ClmmError::NotAllowlisted)?;
+    if entry_info.owner != program_id {
+        return Err(ClmmError::NotAllowlisted.into());
+    }
+    let entry = AccountRef::<AllowlistEntry>::load(entry_info, program_id)?.verify_pda(
+        program_id,
+        &[ALLOWLIST_ENTRY_SEED, pool_key.as_ref(), wallet.as_ref()],
+    )?;
+    if !entry.has_permission(permission) {
+        return Err(ClmmError::NotAllowlisted.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_len_matches_serialized_size() {
+        let entry = AllowlistEntry::default();
+        assert_eq!(borsh::to_vec(&entry).unwrap().len(), AllowlistEntry::LEN);
+    }
+
+    #[test]
+    fn test_has_permission() {
+        let entry = AllowlistEntry {
+            permissions: AllowlistEntry::PERMISSION_SWAP,
+            ..Default::default()
+        };
+        assert!(entry.has_permission(AllowlistEntry::PERMISSION_SWAP));
+        assert!(!entry.has_permission(AllowlistEntry::PERMISSION_LIQUIDITY));
+        assert!(!entry.has_permission(AllowlistEntry::PERMISSION_ALL));
+    }
+}
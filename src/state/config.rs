@@ -1,21 +1,62 @@
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use solana_pubkey::Pubkey;
 
+use super::AccountState;
+
 pub const CONFIG_SEED: &[u8] = b"clmm_config";
 
-#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Default)]
+/// Fee rates are expressed in hundredths of a basis point
+pub const FEE_RATE_DENOMINATOR: u32 = 1_000_000;
+
+#[derive(BorshDeserialize, BorshSchema, BorshSerialize, Debug, Clone, Default)]
 #[repr(C)]
 pub struct AmmConfig {
     pub discriminator: [u8; 8],
+    pub version: u8,
     pub bump: u8,
     pub index: u16,
     pub admin: Pubkey,
     pub trade_fee_rate: u32,
+    /// Share of the trade fee kept for the protocol
     pub protocol_fee_rate: u32,
     pub tick_spacing: u16,
+    pub status: u8,
+    pub padding: [u8; 7],
+    /// Share of the trade fee kept for the fund, collected by `fund_owner`
+    pub fund_fee_rate: u32,
+    pub fund_owner: Pubkey,
+    /// Largest share of the trade fee a swap may pay its referrer
+    pub max_referral_fee_rate: u32,
+    pub padding_1: [u8; 16],
 }
 
 impl AmmConfig {
-    pub const LEN: usize = 8 + 1 + 2 + 32 + 4 + 4 + 2;
+    pub const LEN: usize = 8 + 1 + 1 + 2 + 32 + 4 + 4 + 2 + 1 + 7 + 4 + 32 + 4 + 16;
+    pub const VERSION: u8 = 1;
     pub const DISCRIMINATOR: [u8; 8] = [0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+
+    /// Status bit set while the tier does not accept new pools
+    pub const STATUS_DISABLED: u8 = 1 << 0;
+
+    pub fn is_disabled(&self) -> bool {
+        self.status & Self::STATUS_DISABLED != 0
+    }
+
+    pub fn set_disabled(&mut self, disabled: bool) {
+        if disabled {
+            self.status |= Self::STATUS_DISABLED;
+        } else {
+            self.status &= !Self::STATUS_DISABLED;
+        }
+    }
+}
+
+impl AccountState for AmmConfig {
+    const DISCRIMINATOR: [u8; 8] = AmmConfig::DISCRIMINATOR;
+    const LEN: usize = AmmConfig::LEN;
+    const VERSION: u8 = AmmConfig::VERSION;
+
+    fn bump(&self) -> Option<u8> {
+        Some(self.bump)
+    }
 }
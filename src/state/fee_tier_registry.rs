@@ -0,0 +1,148 @@
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_pubkey::Pubkey;
+
+use super::AccountState;
+use crate::error::ClmmError;
+
+pub const FEE_TIER_REGISTRY_SEED: &[u8] = b"fee_tier_registry";
+
+/// Number of fee tiers the registry can hold
+pub const MAX_FEE_TIERS: usize = 32;
+
+/// Registry entry mirroring one amm config
+#[derive(
+    BorshDeserialize, BorshSchema, BorshSerialize, Debug, Clone, Copy, Default, PartialEq, Eq,
+)]
+#[repr(C)]
+pub struct FeeTier {
+    pub amm_config: Pubkey,
+    pub index: u16,
+    pub tick_spacing: u16,
+    pub trade_fee_rate: u32,
+    /// Same bits as `AmmConfig::status`
+    pub status: u8,
+    pub padding: [u8; 7],
+}
+
+impl FeeTier {
+    pub const LEN: usize = 32 + 2 + 2 + 4 + 1 + 7;
+}
+
+/// Singleton listing every amm config, so clients can enumerate fee tiers
+/// without probing config indices
+#[derive(BorshDeserialize, BorshSchema, BorshSerialize, Debug, Clone, Default)]
+#[repr(C)]
+pub struct FeeTierRegistry {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub bump: u8,
+    pub tier_count: u16,
+    pub padding: [u8; 4],
+    /// Only admin may add fee tiers or change their status
+    pub admin: Pubkey,
+    pub tiers: [FeeTier; MAX_FEE_TIERS],
+}
+
+impl FeeTierRegistry {
+    pub const LEN: usize = 8 + 1 + 1 + 2 + 4 + 32 + FeeTier::LEN * MAX_FEE_TIERS;
+    pub const VERSION: u8 = 1;
+    pub const DISCRIMINATOR: [u8; 8] = [0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+
+    /// Registered fee tiers, in creation order
+    pub fn tiers(&self) -> &[FeeTier] {
+        &self.tiers[..self.tier_count as usize]
+    }
+
+    /// Registers a fee tier, tick spacings must be unique across tiers
+    pub fn add_tier(&mut self, tier: FeeTier) -> Result<(), ClmmError> {
+        if self
+            .tiers()
+            .iter()
+            .any(|existing| existing.tick_spacing == tier.tick_spacing)
+        {
+            return Err(ClmmError::DuplicateTickSpacing);
+        }
+        let slot = self
+            .tiers
+            .get_mut(self.tier_count as usize)
+            .ok_or(ClmmError::FeeTierRegistryFull)?;
+        *slot = tier;
+        self.tier_count += 1;
+        Ok(())
+    }
+
+    pub fn get_tier_mut(&mut self, amm_config: &Pubkey) -> Option<&mut FeeTier> {
+        let count = self.tier_count as usize;
+        self.tiers[..count]
+            .iter_mut()
+            .find(|tier| tier.amm_config == *amm_config)
+    }
+}
+
+impl AccountState for FeeTierRegistry {
+    const DISCRIMINATOR: [u8; 8] = FeeTierRegistry::DISCRIMINATOR;
+    const LEN: usize = FeeTierRegistry::LEN;
+    const VERSION: u8 = FeeTierRegistry::VERSION;
+
+    fn bump(&self) -> Option<u8> {
+        Some(self.bump)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tier(index: u16, tick_spacing: u16) -> FeeTier {
+        FeeTier {
+            amm_config: Pubkey::new_unique(),
+            index,
+            tick_spacing,
+            trade_fee_rate: 3_000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_len_matches_serialized_size() {
+        let registry = FeeTierRegistry::default();
+        assert_eq!(
+            borsh::to_vec(&registry).unwrap().len(),
+            FeeTierRegistry::LEN
+        );
+    }
+
+    #[test]
+    fn test_add_tier_rejects_duplicate_tick_spacing() {
+        let mut registry = FeeTierRegistry::default();
+        registry.add_tier(tier(0, 10)).unwrap();
+        registry.add_tier(tier(1, 60)).unwrap();
+        assert_eq!(
+            registry.add_tier(tier(2, 10)),
+            Err(ClmmError::DuplicateTickSpacing)
+        );
+        assert_eq!(registry.tiers().len(), 2);
+    }
+
+    #[test]
+    fn test_add_tier_when_full() {
+        let mut registry = FeeTierRegistry::default();
+        for i in 0..MAX_FEE_TIERS as u16 {
+            registry.add_tier(tier(i, i + 1)).unwrap();
+        }
+        assert_eq!(
+            registry.add_tier(tier(100, 1_000)),
+            Err(ClmmError::FeeTierRegistryFull)
+        );
+    }
+
+    #[test]
+    fn test_get_tier_mut() {
+        let mut registry = FeeTierRegistry::default();
+        let first = tier(0, 10);
+        registry.add_tier(first).unwrap();
+        registry.get_tier_mut(&first.amm_config).unwrap().status = 1;
+        assert_eq!(registry.tiers()[0].status, 1);
+        assert!(registry.get_tier_mut(&Pubkey::new_unique()).is_none());
+    }
+}
@@ -0,0 +1,498 @@
+use std::cell::Ref;
+use std::cmp::Ordering;
+use std::ops::{Deref, DerefMut};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_account_info::AccountInfo;
+use solana_program_error::{ProgramError, ProgramResult};
+use solana_pubkey::Pubkey;
+
+use crate::error::ClmmError;
+
+/// A program-owned account layout that can be loaded through [`AccountRef`] or [`AccountMut`].
+///
+/// Every layout starts with an 8-byte discriminator followed by a one-byte layout version.
+pub trait AccountState: BorshSerialize + BorshDeserialize {
+    const DISCRIMINATOR: [u8; 8];
+    const LEN: usize;
+    const VERSION: u8;
+
+    /// Canonical bump stored in the account, if the layout keeps one.
+    fn bump(&self) -> Option<u8> {
+        None
+    }
+
+    /// Rewrites account data laid out at `from_version` into the `from_version + 1` layout.
+    fn migrate(_from_version: u8, _data: &[u8]) -> Result<Vec<u8>, ProgramError> {
+        Err(ClmmError::UnsupportedAccountVersion.into())
+    }
+}
+
+/// Offset of the layout version byte, right after the discriminator.
+pub const VERSION_OFFSET: usize = 8;
+
+/// Checks owner, discriminator, layout version and length, then deserializes the account.
+fn load_state<T: AccountState>(
+    info: &AccountInfo,
+    program_id: &Pubkey,
+) -> Result<Box<T>, ProgramError> {
+    if info.owner != program_id {
+        return Err(ClmmError::InvalidAccountOwner.into());
+    }
+
+    let data = info.try_borrow_data()?;
+    if data.len() <= VERSION_OFFSET || data[..8] != T::DISCRIMINATOR {
+        return Err(ClmmError::InvalidAccountData.into());
+    }
+    match data[VERSION_OFFSET].cmp(&T::VERSION) {
+        Ordering::Less => return Err(ClmmError::AccountMigrationRequired.into()),
+        Ordering::Greater => return Err(ClmmError::UnsupportedAccountVersion.into()),
+        Ordering::Equal => {}
+    }
+    if data.len() < T::LEN {
+        return Err(ClmmError::InvalidAccountData.into());
+    }
+
+    let state = T::deserialize(&mut &data[..]).map_err(|_| ClmmError::InvalidAccountData)?;
+    Ok(Box::new(state))
+}
+
+/// Re-derives the PDA from `seeds` and the account's bump and compares it to `key`.
+///
+/// Layouts without a stored bump fall back to the canonical bump search.
+fn verify_pda<T: AccountState>(
+    key: &Pubkey,
+    state: &T,
+    program_id: &Pubkey,
+    seeds: &[&[u8]],
+) -> ProgramResult {
+    let expected = match state.bump() {
+        Some(bump) => {
+            let bump = [bump];
+            let mut seeds_with_bump = seeds.to_vec();
+            seeds_with_bump.push(&bump);
+            Pubkey::create_program_address(&seeds_with_bump, program_id)
+                .map_err(|_| ClmmError::InvalidPda)?
+        }
+        None => Pubkey::find_program_address(seeds, program_id).0,
+    };
+
+    if expected != *key {
+        return Err(ClmmError::InvalidPda.into());
+    }
+    Ok(())
+}
+
+/// Read-only typed view over a verified program account.
+pub struct AccountRef<'a, 'info, T: AccountState> {
+    info: &'a AccountInfo<'info>,
+    state: Box<T>,
+}
+
+impl<'a, 'info, T: AccountState> AccountRef<'a, 'info, T> {
+    pub fn load(info: &'a AccountInfo<'info>, program_id: &Pubkey) -> Result<Self, ProgramError> {
+        let state = load_state(info, program_id)?;
+        Ok(Self { info, state })
+    }
+
+    /// Checks that the account lives at the PDA derived from `seeds`.
+    pub fn verify_pda(self, program_id: &Pubkey, seeds: &[&[u8]]) -> Result<Self, ProgramError> {
+        verify_pda(self.info.key, self.state.as_ref(), program_id, seeds)?;
+        Ok(self)
+    }
+
+    pub fn key(&self) -> &'a Pubkey {
+        self.info.key
+    }
+
+    pub fn info(&self) -> &'a AccountInfo<'info> {
+        self.info
+    }
+
+    /// Detaches the state from the account, for scratch changes that are never written back.
+    pub fn into_inner(self) -> Box<T> {
+        self.state
+    }
+}
+
+impl<T: AccountState> Deref for AccountRef<'_, '_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.state
+    }
+}
+
+/// Mutable typed view over a verified program account.
+///
+/// The state is serialized back into the account data when the view is dropped.
+/// Use [`AccountMut::exit`] to surface serialization errors instead of aborting.
+///
+/// The view keeps the account data borrowed while it lives, so a second mutable view
+/// of the same account fails with `AccountBorrowFailed` instead of overwriting the
+/// first one's writes with a stale copy. The borrow is shared, so the account can
+/// still be passed read-only to CPIs.
+pub struct AccountMut<'a, 'info, T: AccountState> {
+    info: &'a AccountInfo<'info>,
+    state: Box<T>,
+    borrow: Option<Ref<'a, &'info mut [u8]>>,
+}
+
+/// Borrows the account data for a mutable view, failing if another view holds it.
+fn borrow_for_view<'a, 'info>(
+    info: &'a AccountInfo<'info>,
+) -> Result<Ref<'a, &'info mut [u8]>, ProgramError> {
+    drop(info.try_borrow_mut_data()?);
+    info.data
+        .try_borrow()
+        .map_err(|_| ProgramError::AccountBorrowFailed)
+}
+
+impl<'a, 'info, T: AccountState> AccountMut<'a, 'info, T> {
+    pub fn load(info: &'a AccountInfo<'info>, program_id: &Pubkey) -> Result<Self, ProgramError> {
+        if !info.is_writable {
+            return Err(ClmmError::AccountNotWritable.into());
+        }
+        let borrow = borrow_for_view(info)?;
+        let state = load_state(info, program_id)?;
+        Ok(Self {
+            info,
+            state,
+            borrow: Some(borrow),
+        })
+    }
+
+    /// Checks that the account lives at the PDA derived from `seeds`.
+    pub fn verify_pda(self, program_id: &Pubkey, seeds: &[&[u8]]) -> Result<Self, ProgramError> {
+        verify_pda(self.info.key, self.state.as_ref(), program_id, seeds)?;
+        Ok(self)
+    }
+
+    pub fn key(&self) -> &'a Pubkey {
+        self.info.key
+    }
+
+    pub fn info(&self) -> &'a AccountInfo<'info> {
+        self.info
+    }
+
+    /// Writes `state` into a freshly allocated program account.
+    ///
+    /// The account must already be owned by the program, sized for `T` and still zeroed.
+    pub fn init(
+        info: &'a AccountInfo<'info>,
+        program_id: &Pubkey,
+        state: T,
+    ) -> Result<Self, ProgramError> {
+        if info.owner != program_id {
+            return Err(ClmmError::InvalidAccountOwner.into());
+        }
+        if !info.is_writable {
+            return Err(ClmmError::AccountNotWritable.into());
+        }
+        let borrow = borrow_for_view(info)?;
+        if borrow.len() < T::LEN {
+            return Err(ClmmError::InvalidAccountData.into());
+        }
+        if borrow[..8] != [0u8; 8] {
+            return Err(ClmmError::AccountAlreadyInitialized.into());
+        }
+        Ok(Self {
+            info,
+            state: Box::new(state),
+            borrow: Some(borrow),
+        })
+    }
+
+    /// Writes the state back to the account and releases the view.
+    pub fn exit(mut self) -> ProgramResult {
+        let result = self.persist();
+        std::mem::forget(self);
+        result
+    }
+
+    /// Closes the account and sends its lamports to `destination`.
+    ///
+    /// The data is dropped and the account handed back to the system program,
+    /// so the address can be created again later.
+    pub fn close(mut self, destination: &AccountInfo<'info>) -> ProgramResult {
+        let info = self.info;
+        self.borrow = None;
+        std::mem::forget(self);
+        if info.key == destination.key {
+            return Err(ClmmError::InvalidAccountData.into());
+        }
+
+        let lamports = destination
+            .lamports()
+            .checked_add(info.lamports())
+            .ok_or(ClmmError::MathOverflow)?;
+        **destination.try_borrow_mut_lamports()? = lamports;
+        **info.try_borrow_mut_lamports()? = 0;
+        info.resize(0)?;
+        info.assign(&solana_system_interface::program::ID);
+        Ok(())
+    }
+
+    /// Releases the view's borrow and serializes the state into the account data.
+    fn persist(&mut self) -> ProgramResult {
+        self.borrow = None;
+        let mut data = self.info.try_borrow_mut_data()?;
+        self.state
+            .serialize(&mut &mut data[..])
+            .map_err(|_| ClmmError::InvalidAccountData)?;
+        Ok(())
+    }
+}
+
+impl<T: AccountState> Deref for AccountMut<'_, '_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.state
+    }
+}
+
+impl<T: AccountState> DerefMut for AccountMut<'_, '_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.state
+    }
+}
+
+impl<T: AccountState> Drop for AccountMut<'_, '_, T> {
+    fn drop(&mut self) {
+        // Losing a write silently would be worse than aborting the transaction.
+        if let Err(err) = self.persist() {
+            panic!("failed to write back account {}: {:?}", self.info.key, err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{AmmConfig, CONFIG_SEED};
+
+    /// Backing storage for an `AccountInfo` handed to the loader.
+    struct TestAccount {
+        key: Pubkey,
+        owner: Pubkey,
+        lamports: u64,
+        data: Vec<u8>,
+    }
+
+    impl TestAccount {
+        fn new(owner: Pubkey, data: Vec<u8>) -> Self {
+            Self {
+                key: Pubkey::new_unique(),
+                owner,
+                lamports: 0,
+                data,
+            }
+        }
+
+        fn info(&mut self, is_writable: bool) -> AccountInfo<'_> {
+            AccountInfo::new(
+                &self.key,
+                false,
+                is_writable,
+                &mut self.lamports,
+                &mut self.data,
+                &self.owner,
+                false,
+            )
+        }
+    }
+
+    fn valid_config() -> AmmConfig {
+        AmmConfig {
+            discriminator: AmmConfig::DISCRIMINATOR,
+            version: AmmConfig::VERSION,
+            ..Default::default()
+        }
+    }
+
+    fn config_data(config: &AmmConfig) -> Vec<u8> {
+        let mut data = vec![0u8; AmmConfig::LEN];
+        config.serialize(&mut &mut data[..]).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_load_rejects_wrong_owner() {
+        let program_id = Pubkey::new_unique();
+        let mut account = TestAccount::new(Pubkey::new_unique(), config_data(&valid_config()));
+
+        let err = AccountRef::<AmmConfig>::load(&account.info(false), &program_id)
+            .err()
+            .unwrap();
+        assert_eq!(err, ClmmError::InvalidAccountOwner.into());
+    }
+
+    #[test]
+    fn test_load_rejects_wrong_discriminator() {
+        let program_id = Pubkey::new_unique();
+        let config = AmmConfig {
+            discriminator: crate::state::PoolState::DISCRIMINATOR,
+            ..valid_config()
+        };
+        let mut account = TestAccount::new(program_id, config_data(&config));
+
+        let err = AccountRef::<AmmConfig>::load(&account.info(false), &program_id)
+            .err()
+            .unwrap();
+        assert_eq!(err, ClmmError::InvalidAccountData.into());
+    }
+
+    #[test]
+    fn test_load_rejects_short_account() {
+        let program_id = Pubkey::new_unique();
+        let mut data = AmmConfig::DISCRIMINATOR.to_vec();
+        data.push(AmmConfig::VERSION);
+        let mut account = TestAccount::new(program_id, data);
+
+        let err = AccountRef::<AmmConfig>::load(&account.info(false), &program_id)
+            .err()
+            .unwrap();
+        assert_eq!(err, ClmmError::InvalidAccountData.into());
+    }
+
+    #[test]
+    fn test_load_rejects_stale_version() {
+        let program_id = Pubkey::new_unique();
+        let config = AmmConfig {
+            version: AmmConfig::VERSION - 1,
+            ..valid_config()
+        };
+        let mut account = TestAccount::new(program_id, config_data(&config));
+
+        let err = AccountRef::<AmmConfig>::load(&account.info(false), &program_id)
+            .err()
+            .unwrap();
+        assert_eq!(err, ClmmError::AccountMigrationRequired.into());
+    }
+
+    #[test]
+    fn test_load_rejects_newer_version() {
+        let program_id = Pubkey::new_unique();
+        let config = AmmConfig {
+            version: AmmConfig::VERSION + 1,
+            ..valid_config()
+        };
+        let mut account = TestAccount::new(program_id, config_data(&config));
+
+        let err = AccountRef::<AmmConfig>::load(&account.info(false), &program_id)
+            .err()
+            .unwrap();
+        assert_eq!(err, ClmmError::UnsupportedAccountVersion.into());
+    }
+
+    #[test]
+    fn test_load_mut_requires_writable() {
+        let program_id = Pubkey::new_unique();
+        let mut account = TestAccount::new(program_id, config_data(&valid_config()));
+
+        let err = AccountMut::<AmmConfig>::load(&account.info(false), &program_id)
+            .err()
+            .unwrap();
+        assert_eq!(err, ClmmError::AccountNotWritable.into());
+    }
+
+    #[test]
+    fn test_verify_pda_accepts_matching_seeds() {
+        let program_id = Pubkey::new_unique();
+        let index = 7u16.to_be_bytes();
+        let seeds: &[&[u8]] = &[CONFIG_SEED, &index];
+        let (key, bump) = Pubkey::find_program_address(seeds, &program_id);
+        let config = AmmConfig {
+            bump,
+            index: 7,
+            ..valid_config()
+        };
+        let mut account = TestAccount {
+            key,
+            ..TestAccount::new(program_id, config_data(&config))
+        };
+
+        let info = account.info(false);
+        let config = AccountRef::<AmmConfig>::load(&info, &program_id).unwrap();
+        assert!(config.verify_pda(&program_id, seeds).is_ok());
+    }
+
+    #[test]
+    fn test_verify_pda_rejects_other_seeds() {
+        let program_id = Pubkey::new_unique();
+        let index = 7u16.to_be_bytes();
+        let (key, bump) = Pubkey::find_program_address(&[CONFIG_SEED, &index], &program_id);
+        let config = AmmConfig {
+            bump,
+            index: 7,
+            ..valid_config()
+        };
+        let mut account = TestAccount {
+            key,
+            ..TestAccount::new(program_id, config_data(&config))
+        };
+
+        let info = account.info(false);
+        let config = AccountRef::<AmmConfig>::load(&info, &program_id).unwrap();
+        let other_index = 8u16.to_be_bytes();
+        let err = config
+            .verify_pda(&program_id, &[CONFIG_SEED, &other_index])
+            .err()
+            .unwrap();
+        assert_eq!(err, ClmmError::InvalidPda.into());
+    }
+
+    #[test]
+    fn test_account_mut_writes_back_on_drop() {
+        let program_id = Pubkey::new_unique();
+        let mut account = TestAccount::new(program_id, config_data(&valid_config()));
+        let info = account.info(true);
+
+        {
+            let mut config = AccountMut::<AmmConfig>::load(&info, &program_id).unwrap();
+            config.trade_fee_rate = 2500;
+        }
+
+        let config = AccountRef::<AmmConfig>::load(&info, &program_id).unwrap();
+        assert_eq!(config.trade_fee_rate, 2500);
+    }
+
+    #[test]
+    fn test_account_mut_rejects_second_mut_view() {
+        let program_id = Pubkey::new_unique();
+        let mut account = TestAccount::new(program_id, config_data(&valid_config()));
+        let info = account.info(true);
+
+        let _first = AccountMut::<AmmConfig>::load(&info, &program_id).unwrap();
+        let err = AccountMut::<AmmConfig>::load(&info, &program_id)
+            .err()
+            .unwrap();
+        assert_eq!(err, ProgramError::AccountBorrowFailed);
+    }
+
+    #[test]
+    fn test_account_mut_allows_reads_while_held() {
+        let program_id = Pubkey::new_unique();
+        let mut account = TestAccount::new(program_id, config_data(&valid_config()));
+        let info = account.info(true);
+
+        let _view = AccountMut::<AmmConfig>::load(&info, &program_id).unwrap();
+        assert!(AccountRef::<AmmConfig>::load(&info, &program_id).is_ok());
+    }
+
+    #[test]
+    fn test_account_mut_exit_releases_borrow() {
+        let program_id = Pubkey::new_unique();
+        let mut account = TestAccount::new(program_id, config_data(&valid_config()));
+        let info = account.info(true);
+
+        let mut first = AccountMut::<AmmConfig>::load(&info, &program_id).unwrap();
+        first.trade_fee_rate = 2500;
+        first.exit().unwrap();
+
+        let second = AccountMut::<AmmConfig>::load(&info, &program_id).unwrap();
+        assert_eq!(second.trade_fee_rate, 2500);
+    }
+}
@@ -1,11 +1,19 @@
+pub mod allowlist;
 pub mod config;
+pub mod fee_tier_registry;
+pub mod loader;
 pub mod pool;
 pub mod position;
+pub mod position_bundle;
 pub mod tick;
 pub mod tick_array;
 
+pub use allowlist::*;
 pub use config::*;
+pub use fee_tier_registry::*;
+pub use loader::*;
 pub use pool::*;
 pub use position::*;
+pub use position_bundle::*;
 pub use tick::*;
 pub use tick_array::*;
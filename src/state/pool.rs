@@ -1,13 +1,17 @@
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use solana_pubkey::Pubkey;
 
+use super::{AccountState, AmmConfig, TICK_ARRAY_SIZE};
+use crate::error::ClmmError;
+
 pub const POOL_SEED: &[u8] = b"pool";
 pub const POOL_VAULT_SEED: &[u8] = b"pool_vault";
 
-#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+#[derive(BorshDeserialize, BorshSchema, BorshSerialize, Debug, Clone)]
 #[repr(C)]
 pub struct PoolState {
     pub discriminator: [u8; 8],
+    pub version: u8,
     pub bump: u8,
     pub amm_config: Pubkey,
     pub creator: Pubkey,
@@ -28,10 +32,26 @@ pub struct PoolState {
     pub status: u8,
     pub padding: [u8; 7],
     pub tick_array_bitmap: [u64; 16],
+    pub fund_fees_token_0: u64,
+    pub fund_fees_token_1: u64,
+    /// Manages the allowlist of a permissioned pool
+    pub permission_authority: Pubkey,
+    /// Unix time from which swaps are accepted, liquidity can be added before
+    pub open_time: u64,
+    /// Seconds after `open_time` during which swaps are capped
+    pub launch_duration: u64,
+    /// Most of each token a single swap may move during the launch window, zero for no cap
+    pub launch_max_amount_0: u64,
+    pub launch_max_amount_1: u64,
+    /// Replaces the config's trade fee rate while `STATUS_FEE_OVERRIDE` is set
+    pub trade_fee_rate_override: u32,
+    pub padding_2: [u8; 4],
+    pub padding_1: [u64; 21],
 }
 
 impl PoolState {
     pub const LEN: usize = 8
+        + 1
         + 1
         + 32
         + 32
@@ -51,15 +71,134 @@ impl PoolState {
         + 8
         + 1
         + 7
-        + 128;
+        + 128
+        + 8
+        + 8
+        + 32
+        + 8
+        + 8
+        + 8
+        + 8
+        + 4
+        + 4
+        + 168;
+
+    pub const VERSION: u8 = 1;
 
     pub const DISCRIMINATOR: [u8; 8] = [0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
 }
 
+impl PoolState {
+    /// Status bit set when only allowlisted wallets may swap or add liquidity
+    pub const STATUS_PERMISSIONED: u8 = 1 << 0;
+    /// Status bit set while the pool trades at `trade_fee_rate_override`
+    pub const STATUS_FEE_OVERRIDE: u8 = 1 << 1;
+
+    pub fn is_permissioned(&self) -> bool {
+        self.status & Self::STATUS_PERMISSIONED != 0
+    }
+
+    /// Fee rate swaps pay, the pool override if set, otherwise the config's
+    pub fn trade_fee_rate(&self, amm_config: &AmmConfig) -> u32 {
+        if self.status & Self::STATUS_FEE_OVERRIDE != 0 {
+            self.trade_fee_rate_override
+        } else {
+            amm_config.trade_fee_rate
+        }
+    }
+
+    pub fn set_trade_fee_rate_override(&mut self, trade_fee_rate: Option<u32>) {
+        match trade_fee_rate {
+            Some(rate) => {
+                self.status |= Self::STATUS_FEE_OVERRIDE;
+                self.trade_fee_rate_override = rate;
+            }
+            None => {
+                self.status &= !Self::STATUS_FEE_OVERRIDE;
+                self.trade_fee_rate_override = 0;
+            }
+        }
+    }
+
+    /// Checks swaps are accepted at unix time `now`
+    pub fn check_open(&self, now: i64) -> Result<(), ClmmError> {
+        if now < 0 || (now as u64) < self.open_time {
+            return Err(ClmmError::PoolNotOpen);
+        }
+        Ok(())
+    }
+
+    /// Checks a swap moving `amount_0` and `amount_1` fits the launch window caps at `now`
+    pub fn check_launch_limits(
+        &self,
+        now: i64,
+        amount_0: u64,
+        amount_1: u64,
+    ) -> Result<(), ClmmError> {
+        let in_launch_window =
+            (now.max(0) as u64) < self.open_time.saturating_add(self.launch_duration);
+        let exceeds = |amount: u64, max: u64| max != 0 && amount > max;
+        if in_launch_window
+            && (exceeds(amount_0, self.launch_max_amount_0)
+                || exceeds(amount_1, self.launch_max_amount_1))
+        {
+            return Err(ClmmError::LaunchSwapTooLarge);
+        }
+        Ok(())
+    }
+
+    /// Signer seeds of the pool PDA, which owns both vaults
+    pub fn seeds(&self) -> [&[u8]; 5] {
+        [
+            POOL_SEED,
+            self.amm_config.as_ref(),
+            self.token_mint_0.as_ref(),
+            self.token_mint_1.as_ref(),
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+
+    /// Bit of tick_array_bitmap tracking the array at `start_tick_index`
+    ///
+    /// The bitmap covers 512 arrays on each side of tick 0, arrays further out are not tracked.
+    fn tick_array_bit(&self, start_tick_index: i32) -> Option<usize> {
+        let ticks_in_array = TICK_ARRAY_SIZE * self.tick_spacing as i32;
+        let bit = start_tick_index / ticks_in_array + 512;
+        (0..1024).contains(&bit).then_some(bit as usize)
+    }
+
+    /// Mark whether the tick array at `start_tick_index` holds any initialized tick
+    pub fn set_tick_array_initialized(&mut self, start_tick_index: i32, initialized: bool) {
+        if let Some(bit) = self.tick_array_bit(start_tick_index) {
+            if initialized {
+                self.tick_array_bitmap[bit / 64] |= 1 << (bit % 64);
+            } else {
+                self.tick_array_bitmap[bit / 64] &= !(1 << (bit % 64));
+            }
+        }
+    }
+
+    pub fn is_tick_array_initialized(&self, start_tick_index: i32) -> bool {
+        self.tick_array_bit(start_tick_index)
+            .is_some_and(|bit| self.tick_array_bitmap[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+}
+
+impl AccountState for PoolState {
+    const DISCRIMINATOR: [u8; 8] = PoolState::DISCRIMINATOR;
+    const LEN: usize = PoolState::LEN;
+    const VERSION: u8 = PoolState::VERSION;
+
+    fn bump(&self) -> Option<u8> {
+        Some(self.bump)
+    }
+}
+
 impl Default for PoolState {
     fn default() -> Self {
         Self {
             discriminator: PoolState::DISCRIMINATOR,
+            version: PoolState::VERSION,
             bump: 0,
             amm_config: Pubkey::default(),
             creator: Pubkey::default(),
@@ -80,6 +219,66 @@ impl Default for PoolState {
             status: 0,
             padding: [0; 7],
             tick_array_bitmap: [0; 16],
+            fund_fees_token_0: 0,
+            fund_fees_token_1: 0,
+            permission_authority: Pubkey::default(),
+            open_time: 0,
+            launch_duration: 0,
+            launch_max_amount_0: 0,
+            launch_max_amount_1: 0,
+            trade_fee_rate_override: 0,
+            padding_2: [0; 4],
+            padding_1: [0; 21],
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_len_matches_serialized_size() {
+        let pool = PoolState::default();
+        assert_eq!(borsh::to_vec(&pool).unwrap().len(), PoolState::LEN);
+    }
+
+    #[test]
+    fn test_launch_window() {
+        let pool = PoolState {
+            open_time: 1_000,
+            launch_duration: 60,
+            launch_max_amount_0: 500,
+            ..Default::default()
+        };
+        assert_eq!(pool.check_open(999), Err(ClmmError::PoolNotOpen));
+        assert_eq!(pool.check_open(1_000), Ok(()));
+
+        assert_eq!(pool.check_launch_limits(1_000, 500, u64::MAX), Ok(()));
+        assert_eq!(
+            pool.check_launch_limits(1_059, 501, 0),
+            Err(ClmmError::LaunchSwapTooLarge)
+        );
+        assert_eq!(pool.check_launch_limits(1_060, 501, 0), Ok(()));
+    }
+
+    #[test]
+    fn test_trade_fee_rate_override() {
+        let amm_config = AmmConfig {
+            trade_fee_rate: 3_000,
+            ..Default::default()
+        };
+        let mut pool = PoolState {
+            status: PoolState::STATUS_PERMISSIONED,
+            ..Default::default()
+        };
+        assert_eq!(pool.trade_fee_rate(&amm_config), 3_000);
+
+        // A zero override still takes precedence
+        pool.set_trade_fee_rate_override(Some(0));
+        assert_eq!(pool.trade_fee_rate(&amm_config), 0);
+        pool.set_trade_fee_rate_override(None);
+        assert_eq!(pool.trade_fee_rate(&amm_config), 3_000);
+        assert!(pool.is_permissioned());
+    }
+}
@@ -1,12 +1,15 @@
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use solana_pubkey::Pubkey;
 
+use super::AccountState;
+
 pub const POSITION_SEED: &[u8] = b"position";
 
-#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Default)]
+#[derive(BorshDeserialize, BorshSchema, BorshSerialize, Debug, Clone, Default)]
 #[repr(C)]
 pub struct PositionState {
     pub discriminator: [u8; 8],
+    pub version: u8,
     pub bump: u8,
     pub nft_mint: Pubkey,
     pub pool_id: Pubkey,
@@ -17,10 +20,22 @@ pub struct PositionState {
     pub fee_growth_inside_1_last_x64: u128,
     pub token_fees_owed_0: u64,
     pub token_fees_owed_1: u64,
+    pub padding: [u64; 8],
 }
 
 impl PositionState {
-    pub const LEN: usize = 8 + 1 + 32 + 32 + 4 + 4 + 16 + 16 + 16 + 8 + 8;
+    pub const LEN: usize = 8 + 1 + 1 + 32 + 32 + 4 + 4 + 16 + 16 + 16 + 8 + 8 + 64;
+    pub const VERSION: u8 = 1;
 
     pub const DISCRIMINATOR: [u8; 8] = [0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
 }
+
+impl AccountState for PositionState {
+    const DISCRIMINATOR: [u8; 8] = PositionState::DISCRIMINATOR;
+    const LEN: usize = PositionState::LEN;
+    const VERSION: u8 = PositionState::VERSION;
+
+    fn bump(&self) -> Option<u8> {
+        Some(self.bump)
+    }
+}
@@ -0,0 +1,108 @@
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_pubkey::Pubkey;
+
+use super::AccountState;
+use crate::error::ClmmError;
+
+pub const POSITION_BUNDLE_SEED: &[u8] = b"position_bundle";
+pub const BUNDLED_POSITION_SEED: &[u8] = b"bundled_position";
+
+/// Number of position slots in a bundle
+pub const POSITION_BUNDLE_SIZE: u16 = 256;
+
+/// Groups up to [`POSITION_BUNDLE_SIZE`] positions under a single NFT.
+///
+/// Each occupied slot is a regular [`super::PositionState`] at the bundled position PDA
+/// for the slot index, with `nft_mint` set to the bundle mint.
+#[derive(BorshDeserialize, BorshSchema, BorshSerialize, Debug, Clone, Default)]
+#[repr(C)]
+pub struct PositionBundleState {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub bump: u8,
+    pub bundle_mint: Pubkey,
+    /// One bit per slot, set while the slot holds a position
+    pub position_bitmap: [u8; 32],
+    pub padding: [u64; 8],
+}
+
+impl PositionBundleState {
+    pub const LEN: usize = 8 + 1 + 1 + 32 + 32 + 64;
+    pub const VERSION: u8 = 1;
+    pub const DISCRIMINATOR: [u8; 8] = [0x06, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+
+    fn slot(index: u16) -> Result<(usize, u8), ClmmError> {
+        if index >= POSITION_BUNDLE_SIZE {
+            return Err(ClmmError::InvalidBundleIndex);
+        }
+        Ok((index as usize / 8, 1 << (index % 8)))
+    }
+
+    pub fn is_occupied(&self, index: u16) -> Result<bool, ClmmError> {
+        let (byte, mask) = Self::slot(index)?;
+        Ok(self.position_bitmap[byte] & mask != 0)
+    }
+
+    pub fn open_slot(&mut self, index: u16) -> Result<(), ClmmError> {
+        if self.is_occupied(index)? {
+            return Err(ClmmError::BundleSlotInUse);
+        }
+        let (byte, mask) = Self::slot(index)?;
+        self.position_bitmap[byte] |= mask;
+        Ok(())
+    }
+
+    pub fn close_slot(&mut self, index: u16) -> Result<(), ClmmError> {
+        if !self.is_occupied(index)? {
+            return Err(ClmmError::PositionNotFound);
+        }
+        let (byte, mask) = Self::slot(index)?;
+        self.position_bitmap[byte] &= !mask;
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.position_bitmap.iter().all(|byte| *byte == 0)
+    }
+}
+
+impl AccountState for PositionBundleState {
+    const DISCRIMINATOR: [u8; 8] = PositionBundleState::DISCRIMINATOR;
+    const LEN: usize = PositionBundleState::LEN;
+    const VERSION: u8 = PositionBundleState::VERSION;
+
+    fn bump(&self) -> Option<u8> {
+        Some(self.bump)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slots() {
+        let mut bundle = PositionBundleState::default();
+        assert!(bundle.is_empty());
+
+        bundle.open_slot(0).unwrap();
+        bundle.open_slot(255).unwrap();
+        assert!(bundle.is_occupied(255).unwrap());
+        assert!(!bundle.is_occupied(1).unwrap());
+        assert_eq!(bundle.open_slot(0), Err(ClmmError::BundleSlotInUse));
+
+        bundle.close_slot(0).unwrap();
+        assert_eq!(bundle.close_slot(0), Err(ClmmError::PositionNotFound));
+        bundle.close_slot(255).unwrap();
+        assert!(bundle.is_empty());
+    }
+
+    #[test]
+    fn test_index_out_of_range() {
+        let mut bundle = PositionBundleState::default();
+        assert_eq!(
+            bundle.open_slot(POSITION_BUNDLE_SIZE),
+            Err(ClmmError::InvalidBundleIndex)
+        );
+    }
+}
@@ -1,14 +1,17 @@
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+use crate::error::ClmmError;
+use crate::libraries::liquidity_math;
 
 pub const REWARD_NUM: usize = 3;
 
-#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, Default)]
+#[derive(BorshDeserialize, BorshSchema, BorshSerialize, Debug, Clone, Copy, Default)]
 #[repr(C)]
 pub struct TickState {
     pub tick: i32,
     pub liquidity_net: i128,
     pub liquidity_gross: u128,
-    pub feee_growth_outside_0_x64: u128,
+    pub fee_growth_outside_0_x64: u128,
     pub fee_growth_outside_1_x64: u128,
     pub reward_growths_outside_x64: [u128; REWARD_NUM],
     pub padding: [u32; 13],
@@ -20,4 +23,167 @@ impl TickState {
     pub fn is_initialized(&self) -> bool {
         self.liquidity_gross > 0
     }
+
+    /// Apply a position's liquidity change to this tick
+    ///
+    /// By convention all fee growth before a tick is initialized happened below it,
+    /// so a newly initialized tick at or below the current tick starts with the global growth.
+    ///
+    /// Returns true if the tick flipped between initialized and uninitialized.
+    pub fn update(
+        &mut self,
+        tick_current: i32,
+        liquidity_delta: i128,
+        fee_growth_global_0_x64: u128,
+        fee_growth_global_1_x64: u128,
+        upper: bool,
+    ) -> Result<bool, ClmmError> {
+        let liquidity_gross_before = self.liquidity_gross;
+        let liquidity_gross_after =
+            liquidity_math::add_delta(liquidity_gross_before, liquidity_delta)?;
+
+        let flipped = (liquidity_gross_after == 0) != (liquidity_gross_before == 0);
+
+        if liquidity_gross_before == 0 && self.tick <= tick_current {
+            self.fee_growth_outside_0_x64 = fee_growth_global_0_x64;
+            self.fee_growth_outside_1_x64 = fee_growth_global_1_x64;
+        }
+
+        self.liquidity_gross = liquidity_gross_after;
+
+        // Crossing the lower tick left to right adds liquidity, crossing the upper tick removes it
+        self.liquidity_net = if upper {
+            self.liquidity_net.checked_sub(liquidity_delta)
+        } else {
+            self.liquidity_net.checked_add(liquidity_delta)
+        }
+        .ok_or(ClmmError::MathOverflow)?;
+
+        Ok(flipped)
+    }
+
+    /// Transition to the other side of this tick during a swap
+    ///
+    /// Returns the liquidity_net to apply when crossing left to right.
+    pub fn cross(&mut self, fee_growth_global_0_x64: u128, fee_growth_global_1_x64: u128) -> i128 {
+        self.fee_growth_outside_0_x64 =
+            fee_growth_global_0_x64.wrapping_sub(self.fee_growth_outside_0_x64);
+        self.fee_growth_outside_1_x64 =
+            fee_growth_global_1_x64.wrapping_sub(self.fee_growth_outside_1_x64);
+        self.liquidity_net
+    }
+
+    /// Reset everything but the tick index once no position references this tick
+    pub fn clear(&mut self) {
+        *self = TickState {
+            tick: self.tick,
+            ..Default::default()
+        };
+    }
+}
+
+/// Fee growth per unit of liquidity inside [tick_lower, tick_upper)
+///
+/// Growth values wrap, only differences between them are meaningful.
+pub fn get_fee_growth_inside(
+    tick_lower: &TickState,
+    tick_upper: &TickState,
+    tick_current: i32,
+    fee_growth_global_0_x64: u128,
+    fee_growth_global_1_x64: u128,
+) -> (u128, u128) {
+    let (fee_growth_below_0_x64, fee_growth_below_1_x64) = if tick_current >= tick_lower.tick {
+        (
+            tick_lower.fee_growth_outside_0_x64,
+            tick_lower.fee_growth_outside_1_x64,
+        )
+    } else {
+        (
+            fee_growth_global_0_x64.wrapping_sub(tick_lower.fee_growth_outside_0_x64),
+            fee_growth_global_1_x64.wrapping_sub(tick_lower.fee_growth_outside_1_x64),
+        )
+    };
+
+    let (fee_growth_above_0_x64, fee_growth_above_1_x64) = if tick_current < tick_upper.tick {
+        (
+            tick_upper.fee_growth_outside_0_x64,
+            tick_upper.fee_growth_outside_1_x64,
+        )
+    } else {
+        (
+            fee_growth_global_0_x64.wrapping_sub(tick_upper.fee_growth_outside_0_x64),
+            fee_growth_global_1_x64.wrapping_sub(tick_upper.fee_growth_outside_1_x64),
+        )
+    };
+
+    (
+        fee_growth_global_0_x64
+            .wrapping_sub(fee_growth_below_0_x64)
+            .wrapping_sub(fee_growth_above_0_x64),
+        fee_growth_global_1_x64
+            .wrapping_sub(fee_growth_below_1_x64)
+            .wrapping_sub(fee_growth_above_1_x64),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tick(index: i32) -> TickState {
+        TickState {
+            tick: index,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_update_flips_on_first_and_last_liquidity() {
+        let mut lower = tick(-10);
+        assert!(lower.update(0, 100, 0, 0, false).unwrap());
+        assert!(!lower.update(0, 50, 0, 0, false).unwrap());
+        assert_eq!(lower.liquidity_gross, 150);
+        assert_eq!(lower.liquidity_net, 150);
+        assert!(lower.update(0, -150, 0, 0, false).unwrap());
+        assert!(!lower.is_initialized());
+    }
+
+    #[test]
+    fn test_update_upper_subtracts_net() {
+        let mut upper = tick(10);
+        upper.update(0, 100, 0, 0, true).unwrap();
+        assert_eq!(upper.liquidity_net, -100);
+        assert_eq!(upper.liquidity_gross, 100);
+    }
+
+    #[test]
+    fn test_update_initializes_fee_growth_below_current() {
+        let mut below = tick(-10);
+        below.update(0, 1, 7, 9, false).unwrap();
+        assert_eq!(below.fee_growth_outside_0_x64, 7);
+        assert_eq!(below.fee_growth_outside_1_x64, 9);
+
+        let mut above = tick(10);
+        above.update(0, 1, 7, 9, true).unwrap();
+        assert_eq!(above.fee_growth_outside_0_x64, 0);
+        assert_eq!(above.fee_growth_outside_1_x64, 0);
+    }
+
+    #[test]
+    fn test_fee_growth_inside() {
+        let mut lower = tick(-10);
+        let mut upper = tick(10);
+        lower.update(0, 1, 100, 200, false).unwrap();
+        upper.update(0, 1, 100, 200, true).unwrap();
+
+        // All growth since initialization happened inside the range
+        assert_eq!(get_fee_growth_inside(&lower, &upper, 0, 150, 260), (50, 60));
+
+        // Price moves above the range: growth while above is outside
+        upper.cross(150, 260);
+        assert_eq!(
+            get_fee_growth_inside(&lower, &upper, 20, 180, 300),
+            (50, 60)
+        );
+    }
 }
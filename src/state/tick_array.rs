@@ -1,30 +1,49 @@
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use solana_pubkey::Pubkey;
 
-use super::TickState;
+use super::{AccountState, TickState};
+use crate::error::ClmmError;
+use crate::libraries::tick_math::{MAX_TICK, MIN_TICK};
 
 pub const TICK_ARRAY_SEED: &[u8] = b"tick_array";
 
 pub const TICK_ARRAY_SIZE: i32 = 60;
 pub const TICK_ARRAY_SIZE_USIZE: usize = 60;
 
-#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+#[derive(BorshDeserialize, BorshSchema, BorshSerialize, Debug, Clone)]
 #[repr(C)]
 pub struct TickArrayState {
     pub discriminator: [u8; 8],
+    pub version: u8,
     pub pool_id: Pubkey,
     pub start_tick_index: i32,
     pub ticks: [TickState; TICK_ARRAY_SIZE_USIZE],
     pub initialized_tick_count: u8,
-    pub padding: [u8; 115],
+    /// Paid the rent, refunded when the array is closed
+    pub payer: Pubkey,
+    pub padding: [u8; 82],
 }
 
 impl TickArrayState {
-    pub const LEN: usize = 8 + 32 + 4 + (TickState::LEN * TICK_ARRAY_SIZE_USIZE) + 1 + 115;
+    pub const LEN: usize = 8 + 1 + 32 + 4 + (TickState::LEN * TICK_ARRAY_SIZE_USIZE) + 1 + 32 + 82;
+    pub const VERSION: u8 = 1;
 
     pub const DISCRIMINATOR: [u8; 8] = [0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
 
-    fn get_array_start_index(tick: i32, tick_spacing: i32) -> i32 {
+    /// A fresh array with every tick index filled in
+    pub fn new(pool_id: Pubkey, start_tick_index: i32, tick_spacing: u16) -> Self {
+        let mut tick_array = Self {
+            pool_id,
+            start_tick_index,
+            ..Default::default()
+        };
+        for (i, tick) in tick_array.ticks.iter_mut().enumerate() {
+            tick.tick = start_tick_index + i as i32 * tick_spacing as i32;
+        }
+        tick_array
+    }
+
+    pub fn get_array_start_index(tick: i32, tick_spacing: i32) -> i32 {
         let ticks_in_array = TICK_ARRAY_SIZE * tick_spacing;
         let mut start = tick / ticks_in_array;
         if tick < 0 && tick % ticks_in_array != 0 {
@@ -38,17 +57,173 @@ impl TickArrayState {
 
         ((tick - start_index) / tick_spacing) as usize
     }
+
+    /// Whether `start_tick_index` starts an array that holds at least one usable tick
+    pub fn check_is_valid_start_index(start_tick_index: i32, tick_spacing: u16) -> bool {
+        let tick_spacing = tick_spacing as i32;
+        start_tick_index == Self::get_array_start_index(start_tick_index, tick_spacing)
+            && start_tick_index >= Self::get_array_start_index(MIN_TICK, tick_spacing)
+            && start_tick_index <= Self::get_array_start_index(MAX_TICK, tick_spacing)
+    }
+
+    pub fn get_tick_state_mut(
+        &mut self,
+        tick: i32,
+        tick_spacing: u16,
+    ) -> Result<&mut TickState, ClmmError> {
+        let tick_spacing = tick_spacing as i32;
+        if Self::get_array_start_index(tick, tick_spacing) != self.start_tick_index
+            || tick % tick_spacing != 0
+        {
+            return Err(ClmmError::InvalidTickArray);
+        }
+        let offset = Self::get_tick_offset_in_array(tick, tick_spacing);
+        Ok(&mut self.ticks[offset])
+    }
+
+    /// Track ticks flipping between initialized and uninitialized
+    pub fn update_initialized_tick_count(&mut self, add: bool) -> Result<(), ClmmError> {
+        self.initialized_tick_count = if add {
+            self.initialized_tick_count.checked_add(1)
+        } else {
+            self.initialized_tick_count.checked_sub(1)
+        }
+        .ok_or(ClmmError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Offset of the next initialized tick in the swap direction, searching only this array
+    ///
+    /// zero_for_one searches at or below `tick_current`, otherwise strictly above it.
+    pub fn next_initialized_tick_offset(
+        &self,
+        tick_current: i32,
+        tick_spacing: u16,
+        zero_for_one: bool,
+    ) -> Option<usize> {
+        let tick_spacing = tick_spacing as i32;
+        let end_tick_index = self.start_tick_index + TICK_ARRAY_SIZE * tick_spacing;
+
+        if zero_for_one {
+            if tick_current < self.start_tick_index {
+                return None;
+            }
+            let from = if tick_current >= end_tick_index {
+                TICK_ARRAY_SIZE_USIZE - 1
+            } else {
+                ((tick_current - self.start_tick_index) / tick_spacing) as usize
+            };
+            (0..=from).rev().find(|&i| self.ticks[i].is_initialized())
+        } else {
+            if tick_current >= end_tick_index - tick_spacing {
+                return None;
+            }
+            let from = if tick_current < self.start_tick_index {
+                0
+            } else {
+                ((tick_current - self.start_tick_index) / tick_spacing) as usize + 1
+            };
+            (from..TICK_ARRAY_SIZE_USIZE).find(|&i| self.ticks[i].is_initialized())
+        }
+    }
+}
+
+impl AccountState for TickArrayState {
+    const DISCRIMINATOR: [u8; 8] = TickArrayState::DISCRIMINATOR;
+    const LEN: usize = TickArrayState::LEN;
+    const VERSION: u8 = TickArrayState::VERSION;
 }
 
 impl Default for TickArrayState {
     fn default() -> Self {
         Self {
             discriminator: TickArrayState::DISCRIMINATOR,
+            version: TickArrayState::VERSION,
             pool_id: Pubkey::default(),
             start_tick_index: 0,
             ticks: [TickState::default(); TICK_ARRAY_SIZE_USIZE],
             initialized_tick_count: 0,
-            padding: [0; 115],
+            payer: Pubkey::default(),
+            padding: [0; 82],
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_array_start_index() {
+        assert_eq!(TickArrayState::get_array_start_index(0, 10), 0);
+        assert_eq!(TickArrayState::get_array_start_index(599, 10), 0);
+        assert_eq!(TickArrayState::get_array_start_index(600, 10), 600);
+        assert_eq!(TickArrayState::get_array_start_index(-1, 10), -600);
+        assert_eq!(TickArrayState::get_array_start_index(-600, 10), -600);
+        assert_eq!(TickArrayState::get_array_start_index(-601, 10), -1200);
+    }
+
+    #[test]
+    fn test_check_is_valid_start_index() {
+        assert!(TickArrayState::check_is_valid_start_index(0, 10));
+        assert!(TickArrayState::check_is_valid_start_index(-600, 10));
+        assert!(!TickArrayState::check_is_valid_start_index(10, 10));
+        let min_start = TickArrayState::get_array_start_index(MIN_TICK, 10);
+        assert!(TickArrayState::check_is_valid_start_index(min_start, 10));
+        assert!(!TickArrayState::check_is_valid_start_index(
+            min_start - 600,
+            10
+        ));
+        let max_start = TickArrayState::get_array_start_index(MAX_TICK, 10);
+        assert!(!TickArrayState::check_is_valid_start_index(
+            max_start + 600,
+            10
+        ));
+    }
+
+    #[test]
+    fn test_next_initialized_tick_offset() {
+        let mut tick_array = TickArrayState::new(Pubkey::default(), 0, 10);
+        tick_array.ticks[2].liquidity_gross = 1; // tick 20
+        tick_array.ticks[5].liquidity_gross = 1; // tick 50
+
+        // Downwards includes the current tick
+        assert_eq!(
+            tick_array.next_initialized_tick_offset(50, 10, true),
+            Some(5)
+        );
+        assert_eq!(
+            tick_array.next_initialized_tick_offset(49, 10, true),
+            Some(2)
+        );
+        assert_eq!(tick_array.next_initialized_tick_offset(19, 10, true), None);
+        assert_eq!(tick_array.next_initialized_tick_offset(-1, 10, true), None);
+        assert_eq!(
+            tick_array.next_initialized_tick_offset(1000, 10, true),
+            Some(5)
+        );
+
+        // Upwards excludes it
+        assert_eq!(
+            tick_array.next_initialized_tick_offset(20, 10, false),
+            Some(5)
+        );
+        assert_eq!(
+            tick_array.next_initialized_tick_offset(19, 10, false),
+            Some(2)
+        );
+        assert_eq!(
+            tick_array.next_initialized_tick_offset(-5, 10, false),
+            Some(2)
+        );
+        assert_eq!(tick_array.next_initialized_tick_offset(50, 10, false), None);
+    }
+
+    #[test]
+    fn test_get_tick_state_mut_rejects_foreign_ticks() {
+        let mut tick_array = TickArrayState::new(Pubkey::default(), 600, 10);
+        assert_eq!(tick_array.get_tick_state_mut(610, 10).unwrap().tick, 610);
+        assert!(tick_array.get_tick_state_mut(0, 10).is_err());
+        assert!(tick_array.get_tick_state_mut(615, 10).is_err());
+    }
+}
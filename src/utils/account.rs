@@ -0,0 +1,101 @@
+use solana_account_info::AccountInfo;
+use solana_loader_v3_interface::get_program_data_address;
+use solana_loader_v3_interface::state::UpgradeableLoaderState;
+use solana_program_error::{ProgramError, ProgramResult};
+use solana_pubkey::Pubkey;
+use solana_rent::Rent;
+use solana_sdk_ids::bpf_loader_upgradeable;
+use solana_system_interface::instruction as system_instruction;
+use solana_sysvar::Sysvar;
+
+use crate::error::ClmmError;
+use crate::utils::{invoke, invoke_signed};
+
+/// Creates a rent-exempt account of `space` bytes owned by `owner`.
+///
+/// `signer_seeds` sign for `new_account` when it is a PDA. An account that was
+/// already funded by someone else is topped up, allocated and assigned instead.
+pub fn create_account<'info>(
+    payer: &AccountInfo<'info>,
+    new_account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    owner: &Pubkey,
+    space: usize,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let required = Rent::get()?.minimum_balance(space);
+    let current = new_account.lamports();
+
+    if current == 0 {
+        return invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                new_account.key,
+                required,
+                space as u64,
+                owner,
+            ),
+            &[payer.clone(), new_account.clone(), system_program.clone()],
+            signer_seeds,
+        );
+    }
+
+    let top_up = required.saturating_sub(current);
+    if top_up > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, new_account.key, top_up),
+            &[payer.clone(), new_account.clone(), system_program.clone()],
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(new_account.key, space as u64),
+        &[new_account.clone(), system_program.clone()],
+        signer_seeds,
+    )?;
+    invoke_signed(
+        &system_instruction::assign(new_account.key, owner),
+        &[new_account.clone(), system_program.clone()],
+        signer_seeds,
+    )
+}
+
+pub fn check_signer(info: &AccountInfo) -> ProgramResult {
+    if !info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    Ok(())
+}
+
+pub fn check_system_program(info: &AccountInfo) -> ProgramResult {
+    if *info.key != solana_system_interface::program::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+/// Checks that `authority` is the upgrade authority recorded in the program's ProgramData account.
+///
+/// An immutable program has no upgrade authority, so the check then always fails.
+pub fn check_upgrade_authority(
+    program_id: &Pubkey,
+    program_data: &AccountInfo,
+    authority: &AccountInfo,
+) -> ProgramResult {
+    if *program_data.key != get_program_data_address(program_id)
+        || *program_data.owner != bpf_loader_upgradeable::ID
+    {
+        return Err(ClmmError::InvalidAccountData.into());
+    }
+    let data = program_data.try_borrow_data()?;
+    // Bincode layout: u32 variant (3 for ProgramData), u64 slot, Option<Pubkey> authority
+    let metadata = data
+        .get(..UpgradeableLoaderState::size_of_programdata_metadata())
+        .ok_or(ClmmError::InvalidAccountData)?;
+    if metadata[..4] != 3u32.to_le_bytes() {
+        return Err(ClmmError::InvalidAccountData.into());
+    }
+    if metadata[12] != 1 || metadata[13..] != authority.key.to_bytes() {
+        return Err(ClmmError::Unauthorized.into());
+    }
+    Ok(())
+}
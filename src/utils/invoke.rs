@@ -0,0 +1,43 @@
+use solana_account_info::AccountInfo;
+use solana_instruction::Instruction;
+use solana_program_error::ProgramResult;
+use solana_pubkey::Pubkey;
+
+/// Cross-program invocation that also works when the program runs natively.
+///
+/// `solana_cpi` turns CPIs into no-ops off-chain, so native builds route them
+/// through the syscall stubs that `solana-program-test` installs instead.
+pub fn invoke(instruction: &Instruction, account_infos: &[AccountInfo]) -> ProgramResult {
+    invoke_signed(instruction, account_infos, &[])
+}
+
+/// Like [`invoke`], signing for the PDAs derived from `signers_seeds`.
+pub fn invoke_signed(
+    instruction: &Instruction,
+    account_infos: &[AccountInfo],
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    #[cfg(target_os = "solana")]
+    return solana_cpi::invoke_signed(instruction, account_infos, signers_seeds);
+
+    #[cfg(not(target_os = "solana"))]
+    solana_sysvar::program_stubs::sol_invoke_signed(instruction, account_infos, signers_seeds)
+}
+
+/// Sets the instruction's return data, routed like [`invoke_signed`] off-chain.
+pub fn set_return_data(data: &[u8]) {
+    #[cfg(target_os = "solana")]
+    solana_cpi::set_return_data(data);
+
+    #[cfg(not(target_os = "solana"))]
+    solana_sysvar::program_stubs::sol_set_return_data(data)
+}
+
+/// Return data left by the last CPI, with the program that set it.
+pub fn get_return_data() -> Option<(Pubkey, Vec<u8>)> {
+    #[cfg(target_os = "solana")]
+    return solana_cpi::get_return_data();
+
+    #[cfg(not(target_os = "solana"))]
+    solana_sysvar::program_stubs::sol_get_return_data()
+}
@@ -0,0 +1,7 @@
+pub mod account;
+pub mod invoke;
+pub mod token;
+
+pub use account::*;
+pub use invoke::*;
+pub use token::*;
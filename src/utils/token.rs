@@ -0,0 +1,223 @@
+use solana_account_info::AccountInfo;
+use solana_program_error::{ProgramError, ProgramResult};
+use solana_program_pack::Pack;
+use solana_pubkey::Pubkey;
+use spl_token_interface::instruction::{self as token_instruction, AuthorityType};
+use spl_token_interface::state::{Account as TokenAccount, Mint};
+
+use crate::error::ClmmError;
+use crate::utils::{create_account, invoke, invoke_signed};
+
+pub fn check_token_program(info: &AccountInfo) -> ProgramResult {
+    if *info.key != spl_token_interface::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+pub fn unpack_token_account(info: &AccountInfo) -> Result<TokenAccount, ProgramError> {
+    if *info.owner != spl_token_interface::ID {
+        return Err(ClmmError::InvalidAccountOwner.into());
+    }
+    TokenAccount::unpack(&info.try_borrow_data()?)
+}
+
+pub fn unpack_mint(info: &AccountInfo) -> Result<Mint, ProgramError> {
+    if *info.owner != spl_token_interface::ID {
+        return Err(ClmmError::InvalidAccountOwner.into());
+    }
+    Mint::unpack(&info.try_borrow_data()?)
+}
+
+/// Checks that `owner` holds the position NFT `nft_mint` in `nft_account`.
+pub fn check_position_nft(
+    nft_account: &AccountInfo,
+    nft_mint: &Pubkey,
+    owner: &Pubkey,
+) -> ProgramResult {
+    let account = unpack_token_account(nft_account)?;
+    if account.mint != *nft_mint || account.owner != *owner || account.amount != 1 {
+        return Err(ClmmError::Unauthorized.into());
+    }
+    Ok(())
+}
+
+/// Moves `amount` from a user token account into a pool vault.
+pub fn transfer_from_user<'info>(
+    authority: &AccountInfo<'info>,
+    from: &AccountInfo<'info>,
+    to_vault: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    amount: u64,
+) -> ProgramResult {
+    if amount == 0 {
+        return Ok(());
+    }
+    invoke(
+        &token_instruction::transfer(
+            token_program.key,
+            from.key,
+            to_vault.key,
+            authority.key,
+            &[],
+            amount,
+        )?,
+        &[
+            from.clone(),
+            to_vault.clone(),
+            authority.clone(),
+            token_program.clone(),
+        ],
+    )
+}
+
+/// Moves `amount` out of a pool vault, signed by the pool PDA.
+pub fn transfer_from_pool<'info>(
+    pool: &AccountInfo<'info>,
+    pool_seeds: &[&[u8]],
+    from_vault: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    amount: u64,
+) -> ProgramResult {
+    if amount == 0 {
+        return Ok(());
+    }
+    invoke_signed(
+        &token_instruction::transfer(
+            token_program.key,
+            from_vault.key,
+            to.key,
+            pool.key,
+            &[],
+            amount,
+        )?,
+        &[
+            from_vault.clone(),
+            to.clone(),
+            pool.clone(),
+            token_program.clone(),
+        ],
+        &[pool_seeds],
+    )
+}
+
+/// Creates an NFT for `owner`: a zero-decimal mint and a token account holding its only token.
+///
+/// `mint_authority` signs with `mint_authority_seeds` and is revoked right after minting,
+/// so the supply is fixed at one.
+#[allow(clippy::too_many_arguments)]
+pub fn mint_position_nft<'info>(
+    payer: &AccountInfo<'info>,
+    owner: &Pubkey,
+    nft_mint: &AccountInfo<'info>,
+    nft_account: &AccountInfo<'info>,
+    mint_authority: &AccountInfo<'info>,
+    mint_authority_seeds: &[&[u8]],
+    token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> ProgramResult {
+    create_account(
+        payer,
+        nft_mint,
+        system_program,
+        token_program.key,
+        Mint::LEN,
+        &[],
+    )?;
+    invoke(
+        &token_instruction::initialize_mint2(
+            token_program.key,
+            nft_mint.key,
+            mint_authority.key,
+            None,
+            0,
+        )?,
+        &[nft_mint.clone(), token_program.clone()],
+    )?;
+    create_account(
+        payer,
+        nft_account,
+        system_program,
+        token_program.key,
+        TokenAccount::LEN,
+        &[],
+    )?;
+    invoke(
+        &token_instruction::initialize_account3(
+            token_program.key,
+            nft_account.key,
+            nft_mint.key,
+            owner,
+        )?,
+        &[nft_account.clone(), nft_mint.clone(), token_program.clone()],
+    )?;
+    invoke_signed(
+        &token_instruction::mint_to(
+            token_program.key,
+            nft_mint.key,
+            nft_account.key,
+            mint_authority.key,
+            &[],
+            1,
+        )?,
+        &[
+            nft_mint.clone(),
+            nft_account.clone(),
+            mint_authority.clone(),
+            token_program.clone(),
+        ],
+        &[mint_authority_seeds],
+    )?;
+    invoke_signed(
+        &token_instruction::set_authority(
+            token_program.key,
+            nft_mint.key,
+            None,
+            AuthorityType::MintTokens,
+            mint_authority.key,
+            &[],
+        )?,
+        &[
+            nft_mint.clone(),
+            mint_authority.clone(),
+            token_program.clone(),
+        ],
+        &[mint_authority_seeds],
+    )
+}
+
+/// Burns an NFT held by `owner` and closes its token account, refunding the rent to `owner`.
+pub fn burn_position_nft<'info>(
+    owner: &AccountInfo<'info>,
+    nft_mint: &AccountInfo<'info>,
+    nft_account: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+) -> ProgramResult {
+    invoke(
+        &token_instruction::burn(
+            token_program.key,
+            nft_account.key,
+            nft_mint.key,
+            owner.key,
+            &[],
+            1,
+        )?,
+        &[
+            nft_account.clone(),
+            nft_mint.clone(),
+            owner.clone(),
+            token_program.clone(),
+        ],
+    )?;
+    invoke(
+        &token_instruction::close_account(
+            token_program.key,
+            nft_account.key,
+            owner.key,
+            owner.key,
+            &[],
+        )?,
+        &[nft_account.clone(), owner.clone(), token_program.clone()],
+    )
+}
@@ -0,0 +1,1124 @@
+//! Shared harness for the integration tests: boots the program in `ProgramTest`,
+//! sets up mints and token accounts, and builds every instruction.
+
+// solana-program-test flags its whole API as unstable ahead of 4.0
+#![allow(dead_code, deprecated)]
+
+use borsh::BorshDeserialize;
+use clmm_demo::instruction::ClmmInstruction;
+use clmm_demo::instructions::{Deadline, SqrtPriceBand, SwapQuote};
+use clmm_demo::pda;
+use clmm_demo::state::{PoolState, TickArrayState};
+use solana_account::AccountSharedData;
+use solana_clock::Clock;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_keypair::Keypair;
+use solana_loader_v3_interface::get_program_data_address;
+use solana_loader_v3_interface::state::UpgradeableLoaderState;
+use solana_program_pack::Pack;
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_pubkey::Pubkey;
+use solana_sdk_ids::bpf_loader_upgradeable;
+use solana_signer::Signer;
+use solana_system_interface::instruction as system_instruction;
+use solana_transaction::Transaction;
+use spl_token_interface::instruction as token_instruction;
+use spl_token_interface::state::{Account as TokenAccount, Mint};
+
+pub const PROGRAM_ID: Pubkey = clmm_demo::ID;
+
+fn build_instruction(
+    program_id: Pubkey,
+    instruction: &ClmmInstruction,
+    accounts: Vec<AccountMeta>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts,
+        data: borsh::to_vec(instruction).unwrap(),
+    }
+}
+
+/// Addresses of a pool and everything derived from it
+#[derive(Debug, Clone, Copy)]
+pub struct PoolKeys {
+    pub amm_config: Pubkey,
+    pub pool: Pubkey,
+    pub token_mint_0: Pubkey,
+    pub token_mint_1: Pubkey,
+    pub token_vault_0: Pubkey,
+    pub token_vault_1: Pubkey,
+    pub tick_spacing: u16,
+}
+
+impl PoolKeys {
+    pub fn new(amm_config: Pubkey, mint_a: Pubkey, mint_b: Pubkey, tick_spacing: u16) -> Self {
+        let (token_mint_0, token_mint_1) = if mint_a < mint_b {
+            (mint_a, mint_b)
+        } else {
+            (mint_b, mint_a)
+        };
+        let (pool, _) =
+            pda::find_pool_address(&PROGRAM_ID, &amm_config, &token_mint_0, &token_mint_1);
+        Self {
+            amm_config,
+            pool,
+            token_mint_0,
+            token_mint_1,
+            token_vault_0: pda::find_pool_vault_address(&PROGRAM_ID, &pool, &token_mint_0).0,
+            token_vault_1: pda::find_pool_vault_address(&PROGRAM_ID, &pool, &token_mint_1).0,
+            tick_spacing,
+        }
+    }
+
+    /// Tick array PDA holding `tick`
+    pub fn tick_array(&self, tick: i32) -> Pubkey {
+        let start = TickArrayState::get_array_start_index(tick, self.tick_spacing as i32);
+        pda::find_tick_array_address(&PROGRAM_ID, &self.pool, start).0
+    }
+}
+
+/// A position and the accounts needed to operate on it
+#[derive(Debug, Clone, Copy)]
+pub struct PositionKeys {
+    pub position: Pubkey,
+    pub nft_mint: Pubkey,
+    pub nft_account: Pubkey,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+}
+
+/// Optional CreatePool settings, all off by default
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PoolOptions {
+    pub permission_authority: Option<Pubkey>,
+    pub open_time: u64,
+    pub launch_duration: u64,
+    pub launch_max_amount_0: u64,
+    pub launch_max_amount_1: u64,
+}
+
+#[derive(Clone, Copy)]
+pub struct BundleKeys {
+    pub bundle: Pubkey,
+    pub bundle_mint: Pubkey,
+    pub bundle_token_account: Pubkey,
+}
+
+pub struct ClmmTest {
+    pub context: ProgramTestContext,
+    /// Attached to every swap and liquidity op built while set
+    pub deadline: Option<Deadline>,
+    /// Attached to every liquidity op built while set
+    pub sqrt_price_band: Option<SqrtPriceBand>,
+}
+
+impl ClmmTest {
+    pub async fn start() -> Self {
+        Self::start_with(|_| {}).await
+    }
+
+    /// Like [`ClmmTest::start`], letting `configure` add programs that call into this one.
+    pub async fn start_with(configure: impl FnOnce(&mut ProgramTest)) -> Self {
+        let mut program_test = ProgramTest::new(
+            "clmm_demo",
+            PROGRAM_ID,
+            processor!(clmm_demo::processor::process_instruction),
+        );
+        program_test.prefer_bpf(false);
+        configure(&mut program_test);
+        let mut context = program_test.start_with_context().await;
+
+        // The builtin processor has no loader accounts, so record the payer as upgrade authority
+        let program_data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+            slot: 0,
+            upgrade_authority_address: Some(context.payer.pubkey()),
+        })
+        .unwrap();
+        let mut account = AccountSharedData::new(
+            1_000_000_000,
+            program_data.len(),
+            &bpf_loader_upgradeable::ID,
+        );
+        account.set_data_from_slice(&program_data);
+        context.set_account(&get_program_data_address(&PROGRAM_ID), &account);
+
+        Self {
+            context,
+            deadline: None,
+            sqrt_price_band: None,
+        }
+    }
+
+    pub fn payer(&self) -> Keypair {
+        self.context.payer.insecure_clone()
+    }
+
+    /// Sends `instructions` in one transaction paid by the context payer.
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let payer = self.payer();
+        let mut all_signers = vec![&payer];
+        all_signers.extend_from_slice(signers);
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+    }
+
+    pub async fn get_state<T: BorshDeserialize>(&mut self, address: Pubkey) -> T {
+        let account = self
+            .context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap_or_else(|| panic!("account {address} not found"));
+        T::deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub async fn unix_timestamp(&mut self) -> i64 {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp
+    }
+
+    pub async fn set_unix_timestamp(&mut self, unix_timestamp: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = unix_timestamp;
+        self.context.set_sysvar(&clock);
+    }
+
+    pub async fn account_exists(&mut self, address: Pubkey) -> bool {
+        self.context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .is_some()
+    }
+
+    pub async fn token_balance(&mut self, token_account: Pubkey) -> u64 {
+        self.context
+            .banks_client
+            .get_packed_account_data::<TokenAccount>(token_account)
+            .await
+            .unwrap()
+            .amount
+    }
+
+    pub async fn airdrop(&mut self, to: &Pubkey, lamports: u64) {
+        let payer = self.payer();
+        self.process(
+            &[system_instruction::transfer(&payer.pubkey(), to, lamports)],
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+
+    pub async fn create_mint(&mut self, decimals: u8) -> Pubkey {
+        let payer = self.payer();
+        let mint = Keypair::new();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        self.process(
+            &[
+                system_instruction::create_account(
+                    &payer.pubkey(),
+                    &mint.pubkey(),
+                    rent.minimum_balance(Mint::LEN),
+                    Mint::LEN as u64,
+                    &spl_token_interface::ID,
+                ),
+                token_instruction::initialize_mint2(
+                    &spl_token_interface::ID,
+                    &mint.pubkey(),
+                    &payer.pubkey(),
+                    None,
+                    decimals,
+                )
+                .unwrap(),
+            ],
+            &[&mint],
+        )
+        .await
+        .unwrap();
+        mint.pubkey()
+    }
+
+    pub async fn create_token_account(&mut self, mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+        let payer = self.payer();
+        let account = Keypair::new();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        self.process(
+            &[
+                system_instruction::create_account(
+                    &payer.pubkey(),
+                    &account.pubkey(),
+                    rent.minimum_balance(TokenAccount::LEN),
+                    TokenAccount::LEN as u64,
+                    &spl_token_interface::ID,
+                ),
+                token_instruction::initialize_account3(
+                    &spl_token_interface::ID,
+                    &account.pubkey(),
+                    mint,
+                    owner,
+                )
+                .unwrap(),
+            ],
+            &[&account],
+        )
+        .await
+        .unwrap();
+        account.pubkey()
+    }
+
+    /// Mints `amount` to `account`. The context payer is every mint's authority.
+    pub async fn mint_to(&mut self, mint: &Pubkey, account: &Pubkey, amount: u64) {
+        let payer = self.payer();
+        self.process(
+            &[token_instruction::mint_to(
+                &spl_token_interface::ID,
+                mint,
+                account,
+                &payer.pubkey(),
+                &[],
+                amount,
+            )
+            .unwrap()],
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+
+    /// Creates a token account for `owner` holding `amount` of `mint`.
+    pub async fn create_funded_token_account(
+        &mut self,
+        mint: &Pubkey,
+        owner: &Pubkey,
+        amount: u64,
+    ) -> Pubkey {
+        let account = self.create_token_account(mint, owner).await;
+        self.mint_to(mint, &account, amount).await;
+        account
+    }
+
+    pub async fn initialize_fee_tier_registry(
+        &mut self,
+        admin: &Keypair,
+    ) -> Result<Pubkey, BanksClientError> {
+        let (registry, _) = pda::find_fee_tier_registry_address(&PROGRAM_ID);
+        let instruction = build_instruction(
+            PROGRAM_ID,
+            &ClmmInstruction::InitializeFeeTierRegistry,
+            vec![
+                AccountMeta::new(admin.pubkey(), true),
+                AccountMeta::new(registry, false),
+                AccountMeta::new_readonly(solana_system_interface::program::ID, false),
+                AccountMeta::new_readonly(get_program_data_address(&PROGRAM_ID), false),
+            ],
+        );
+        self.process(&[instruction], &[admin]).await?;
+        Ok(registry)
+    }
+
+    /// Creates the fee tier, setting up the registry with `admin` on first use
+    #[allow(clippy::too_many_arguments)]
+    pub async fn initialize_config(
+        &mut self,
+        admin: &Keypair,
+        index: u16,
+        trade_fee_rate: u32,
+        protocol_fee_rate: u32,
+        fund_fee_rate: u32,
+        fund_owner: Pubkey,
+        max_referral_fee_rate: u32,
+        tick_spacing: u16,
+    ) -> Result<Pubkey, BanksClientError> {
+        let (registry, _) = pda::find_fee_tier_registry_address(&PROGRAM_ID);
+        if !self.account_exists(registry).await {
+            self.initialize_fee_tier_registry(admin).await?;
+        }
+        let (amm_config, _) = pda::find_amm_config_address(&PROGRAM_ID, index);
+        let instruction = build_instruction(
+            PROGRAM_ID,
+            &ClmmInstruction::InitializeConfig {
+                index,
+                trade_fee_rate,
+                protocol_fee_rate,
+                tick_spacing,
+                fund_fee_rate,
+                fund_owner,
+                max_referral_fee_rate,
+            },
+            vec![
+                AccountMeta::new(admin.pubkey(), true),
+                AccountMeta::new(amm_config, false),
+                AccountMeta::new(registry, false),
+                AccountMeta::new_readonly(solana_system_interface::program::ID, false),
+            ],
+        );
+        self.process(&[instruction], &[admin]).await?;
+        Ok(amm_config)
+    }
+
+    pub async fn set_fee_tier_enabled(
+        &mut self,
+        admin: &Keypair,
+        amm_config: Pubkey,
+        enabled: bool,
+    ) -> Result<(), BanksClientError> {
+        let (registry, _) = pda::find_fee_tier_registry_address(&PROGRAM_ID);
+        let instruction = build_instruction(
+            PROGRAM_ID,
+            &ClmmInstruction::SetFeeTierEnabled { enabled },
+            vec![
+                AccountMeta::new_readonly(admin.pubkey(), true),
+                AccountMeta::new(registry, false),
+                AccountMeta::new(amm_config, false),
+            ],
+        );
+        self.process(&[instruction], &[admin]).await
+    }
+
+    pub async fn set_pool_fee_rate(
+        &mut self,
+        admin: &Keypair,
+        keys: &PoolKeys,
+        trade_fee_rate: Option<u32>,
+    ) -> Result<(), BanksClientError> {
+        let instruction = build_instruction(
+            PROGRAM_ID,
+            &ClmmInstruction::SetPoolFeeRate { trade_fee_rate },
+            vec![
+                AccountMeta::new_readonly(admin.pubkey(), true),
+                AccountMeta::new_readonly(keys.amm_config, false),
+                AccountMeta::new(keys.pool, false),
+            ],
+        );
+        self.process(&[instruction], &[admin]).await
+    }
+
+    pub async fn set_max_referral_fee_rate(
+        &mut self,
+        admin: &Keypair,
+        amm_config: Pubkey,
+        max_referral_fee_rate: u32,
+    ) -> Result<(), BanksClientError> {
+        let instruction = build_instruction(
+            PROGRAM_ID,
+            &ClmmInstruction::SetMaxReferralFeeRate {
+                max_referral_fee_rate,
+            },
+            vec![
+                AccountMeta::new_readonly(admin.pubkey(), true),
+                AccountMeta::new(amm_config, false),
+            ],
+        );
+        self.process(&[instruction], &[admin]).await
+    }
+
+    pub async fn create_pool(
+        &mut self,
+        creator: &Keypair,
+        keys: &PoolKeys,
+        sqrt_price_x64: u128,
+    ) -> Result<(), BanksClientError> {
+        self.create_pool_with_options(creator, keys, sqrt_price_x64, PoolOptions::default())
+            .await
+    }
+
+    pub async fn create_pool_with_options(
+        &mut self,
+        creator: &Keypair,
+        keys: &PoolKeys,
+        sqrt_price_x64: u128,
+        options: PoolOptions,
+    ) -> Result<(), BanksClientError> {
+        let instruction = build_instruction(
+            PROGRAM_ID,
+            &ClmmInstruction::CreatePool {
+                sqrt_price_x64,
+                permission_authority: options.permission_authority,
+                open_time: options.open_time,
+                launch_duration: options.launch_duration,
+                launch_max_amount_0: options.launch_max_amount_0,
+                launch_max_amount_1: options.launch_max_amount_1,
+            },
+            vec![
+                AccountMeta::new(creator.pubkey(), true),
+                AccountMeta::new_readonly(keys.amm_config, false),
+                AccountMeta::new(keys.pool, false),
+                AccountMeta::new_readonly(keys.token_mint_0, false),
+                AccountMeta::new_readonly(keys.token_mint_1, false),
+                AccountMeta::new(keys.token_vault_0, false),
+                AccountMeta::new(keys.token_vault_1, false),
+                AccountMeta::new_readonly(spl_token_interface::ID, false),
+                AccountMeta::new_readonly(solana_system_interface::program::ID, false),
+            ],
+        );
+        self.process(&[instruction], &[creator]).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn open_position(
+        &mut self,
+        owner: &Keypair,
+        keys: &PoolKeys,
+        tick_lower: i32,
+        tick_upper: i32,
+        liquidity: u128,
+        token_account_0: Pubkey,
+        token_account_1: Pubkey,
+        amount_0_max: u64,
+        amount_1_max: u64,
+    ) -> Result<PositionKeys, BanksClientError> {
+        let nft_mint = Keypair::new();
+        let nft_account = Keypair::new();
+        let (position, _) = pda::find_position_address(&PROGRAM_ID, &nft_mint.pubkey());
+        let mut accounts = vec![
+            AccountMeta::new(owner.pubkey(), true),
+            AccountMeta::new(keys.pool, false),
+            AccountMeta::new(nft_mint.pubkey(), true),
+            AccountMeta::new(nft_account.pubkey(), true),
+            AccountMeta::new(position, false),
+            AccountMeta::new(keys.tick_array(tick_lower), false),
+            AccountMeta::new(keys.tick_array(tick_upper), false),
+            AccountMeta::new(token_account_0, false),
+            AccountMeta::new(token_account_1, false),
+            AccountMeta::new(keys.token_vault_0, false),
+            AccountMeta::new(keys.token_vault_1, false),
+            AccountMeta::new_readonly(spl_token_interface::ID, false),
+            AccountMeta::new_readonly(solana_system_interface::program::ID, false),
+        ];
+        accounts.extend(self.allowlist_entry(keys, owner.pubkey()).await);
+        let instruction = build_instruction(
+            PROGRAM_ID,
+            &ClmmInstruction::OpenPosition {
+                tick_lower,
+                tick_upper,
+                liquidity,
+                amount_0_max,
+                amount_1_max,
+                deadline: self.deadline,
+                sqrt_price_band: self.sqrt_price_band,
+            },
+            accounts,
+        );
+        self.process(&[instruction], &[owner, &nft_mint, &nft_account])
+            .await?;
+        Ok(PositionKeys {
+            position,
+            nft_mint: nft_mint.pubkey(),
+            nft_account: nft_account.pubkey(),
+            tick_lower,
+            tick_upper,
+        })
+    }
+
+    pub async fn initialize_position_bundle(
+        &mut self,
+        owner: &Keypair,
+    ) -> Result<BundleKeys, BanksClientError> {
+        let bundle_mint = Keypair::new();
+        let bundle_token_account = Keypair::new();
+        let (bundle, _) = pda::find_position_bundle_address(&PROGRAM_ID, &bundle_mint.pubkey());
+        let instruction = build_instruction(
+            PROGRAM_ID,
+            &ClmmInstruction::InitializePositionBundle,
+            vec![
+                AccountMeta::new(owner.pubkey(), true),
+                AccountMeta::new(bundle_mint.pubkey(), true),
+                AccountMeta::new(bundle_token_account.pubkey(), true),
+                AccountMeta::new(bundle, false),
+                AccountMeta::new_readonly(spl_token_interface::ID, false),
+                AccountMeta::new_readonly(solana_system_interface::program::ID, false),
+            ],
+        );
+        self.process(
+            &[instruction],
+            &[owner, &bundle_mint, &bundle_token_account],
+        )
+        .await?;
+        Ok(BundleKeys {
+            bundle,
+            bundle_mint: bundle_mint.pubkey(),
+            bundle_token_account: bundle_token_account.pubkey(),
+        })
+    }
+
+    /// Opens a bundled position. The returned keys point at the bundle NFT,
+    /// so they work with the regular liquidity and fee helpers.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn open_bundled_position(
+        &mut self,
+        owner: &Keypair,
+        keys: &PoolKeys,
+        bundle: &BundleKeys,
+        bundle_index: u16,
+        tick_lower: i32,
+        tick_upper: i32,
+        liquidity: u128,
+        token_account_0: Pubkey,
+        token_account_1: Pubkey,
+    ) -> Result<PositionKeys, BanksClientError> {
+        let (position, _) =
+            pda::find_bundled_position_address(&PROGRAM_ID, &bundle.bundle_mint, bundle_index);
+        let mut accounts = vec![
+            AccountMeta::new(owner.pubkey(), true),
+            AccountMeta::new_readonly(bundle.bundle_token_account, false),
+            AccountMeta::new(bundle.bundle, false),
+            AccountMeta::new(keys.pool, false),
+            AccountMeta::new(position, false),
+            AccountMeta::new(keys.tick_array(tick_lower), false),
+            AccountMeta::new(keys.tick_array(tick_upper), false),
+            AccountMeta::new(token_account_0, false),
+            AccountMeta::new(token_account_1, false),
+            AccountMeta::new(keys.token_vault_0, false),
+            AccountMeta::new(keys.token_vault_1, false),
+            AccountMeta::new_readonly(spl_token_interface::ID, false),
+            AccountMeta::new_readonly(solana_system_interface::program::ID, false),
+        ];
+        accounts.extend(self.allowlist_entry(keys, owner.pubkey()).await);
+        let instruction = build_instruction(
+            PROGRAM_ID,
+            &ClmmInstruction::OpenBundledPosition {
+                bundle_index,
+                tick_lower,
+                tick_upper,
+                liquidity,
+                amount_0_max: u64::MAX,
+                amount_1_max: u64::MAX,
+                deadline: self.deadline,
+                sqrt_price_band: self.sqrt_price_band,
+            },
+            accounts,
+        );
+        self.process(&[instruction], &[owner]).await?;
+        Ok(PositionKeys {
+            position,
+            nft_mint: bundle.bundle_mint,
+            nft_account: bundle.bundle_token_account,
+            tick_lower,
+            tick_upper,
+        })
+    }
+
+    pub async fn close_bundled_position(
+        &mut self,
+        owner: &Keypair,
+        bundle: &BundleKeys,
+        bundle_index: u16,
+    ) -> Result<(), BanksClientError> {
+        let (position, _) =
+            pda::find_bundled_position_address(&PROGRAM_ID, &bundle.bundle_mint, bundle_index);
+        let instruction = build_instruction(
+            PROGRAM_ID,
+            &ClmmInstruction::CloseBundledPosition { bundle_index },
+            vec![
+                AccountMeta::new(owner.pubkey(), true),
+                AccountMeta::new_readonly(bundle.bundle_token_account, false),
+                AccountMeta::new(bundle.bundle, false),
+                AccountMeta::new(position, false),
+            ],
+        );
+        self.process(&[instruction], &[owner]).await
+    }
+
+    pub async fn close_position_bundle(
+        &mut self,
+        owner: &Keypair,
+        bundle: &BundleKeys,
+    ) -> Result<(), BanksClientError> {
+        let instruction = build_instruction(
+            PROGRAM_ID,
+            &ClmmInstruction::ClosePositionBundle,
+            vec![
+                AccountMeta::new(owner.pubkey(), true),
+                AccountMeta::new(bundle.bundle_mint, false),
+                AccountMeta::new(bundle.bundle_token_account, false),
+                AccountMeta::new(bundle.bundle, false),
+                AccountMeta::new_readonly(spl_token_interface::ID, false),
+            ],
+        );
+        self.process(&[instruction], &[owner]).await
+    }
+
+    /// Accounts shared by IncreaseLiquidity, DecreaseLiquidity and CollectFees
+    /// The wallet's allowlist entry, when the pool is permissioned.
+    async fn allowlist_entry(&mut self, keys: &PoolKeys, wallet: Pubkey) -> Option<AccountMeta> {
+        let pool: PoolState = self.get_state(keys.pool).await;
+        pool.is_permissioned().then(|| {
+            let (entry, _) = pda::find_allowlist_entry_address(&PROGRAM_ID, &keys.pool, &wallet);
+            AccountMeta::new_readonly(entry, false)
+        })
+    }
+
+    pub async fn add_to_allowlist(
+        &mut self,
+        authority: &Keypair,
+        keys: &PoolKeys,
+        wallet: Pubkey,
+        permissions: u8,
+    ) -> Result<(), BanksClientError> {
+        let (entry, _) = pda::find_allowlist_entry_address(&PROGRAM_ID, &keys.pool, &wallet);
+        let instruction = build_instruction(
+            PROGRAM_ID,
+            &ClmmInstruction::AddToAllowlist {
+                wallet,
+                permissions,
+            },
+            vec![
+                AccountMeta::new(authority.pubkey(), true),
+                AccountMeta::new_readonly(keys.pool, false),
+                AccountMeta::new(entry, false),
+                AccountMeta::new_readonly(solana_system_interface::program::ID, false),
+            ],
+        );
+        self.process(&[instruction], &[authority]).await
+    }
+
+    pub async fn remove_from_allowlist(
+        &mut self,
+        authority: &Keypair,
+        keys: &PoolKeys,
+        wallet: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let (entry, _) = pda::find_allowlist_entry_address(&PROGRAM_ID, &keys.pool, &wallet);
+        let instruction = build_instruction(
+            PROGRAM_ID,
+            &ClmmInstruction::RemoveFromAllowlist,
+            vec![
+                AccountMeta::new(authority.pubkey(), true),
+                AccountMeta::new_readonly(keys.pool, false),
+                AccountMeta::new(entry, false),
+            ],
+        );
+        self.process(&[instruction], &[authority]).await
+    }
+
+    fn position_accounts(
+        owner: &Keypair,
+        keys: &PoolKeys,
+        position: &PositionKeys,
+        token_account_0: Pubkey,
+        token_account_1: Pubkey,
+    ) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(owner.pubkey(), true),
+            AccountMeta::new_readonly(position.nft_account, false),
+            AccountMeta::new(keys.pool, false),
+            AccountMeta::new(position.position, false),
+            AccountMeta::new(keys.tick_array(position.tick_lower), false),
+            AccountMeta::new(keys.tick_array(position.tick_upper), false),
+            AccountMeta::new(token_account_0, false),
+            AccountMeta::new(token_account_1, false),
+            AccountMeta::new(keys.token_vault_0, false),
+            AccountMeta::new(keys.token_vault_1, false),
+            AccountMeta::new_readonly(spl_token_interface::ID, false),
+        ]
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn increase_liquidity(
+        &mut self,
+        owner: &Keypair,
+        keys: &PoolKeys,
+        position: &PositionKeys,
+        liquidity: u128,
+        token_account_0: Pubkey,
+        token_account_1: Pubkey,
+        amount_0_max: u64,
+        amount_1_max: u64,
+    ) -> Result<(), BanksClientError> {
+        let mut accounts =
+            Self::position_accounts(owner, keys, position, token_account_0, token_account_1);
+        accounts.extend(self.allowlist_entry(keys, owner.pubkey()).await);
+        let instruction = build_instruction(
+            PROGRAM_ID,
+            &ClmmInstruction::IncreaseLiquidity {
+                liquidity,
+                amount_0_max,
+                amount_1_max,
+                deadline: self.deadline,
+                sqrt_price_band: self.sqrt_price_band,
+            },
+            accounts,
+        );
+        self.process(&[instruction], &[owner]).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn decrease_liquidity(
+        &mut self,
+        owner: &Keypair,
+        keys: &PoolKeys,
+        position: &PositionKeys,
+        liquidity: u128,
+        recipient_token_account_0: Pubkey,
+        recipient_token_account_1: Pubkey,
+        amount_0_min: u64,
+        amount_1_min: u64,
+    ) -> Result<(), BanksClientError> {
+        let instruction = build_instruction(
+            PROGRAM_ID,
+            &ClmmInstruction::DecreaseLiquidity {
+                liquidity,
+                amount_0_min,
+                amount_1_min,
+                deadline: self.deadline,
+                sqrt_price_band: self.sqrt_price_band,
+            },
+            Self::position_accounts(
+                owner,
+                keys,
+                position,
+                recipient_token_account_0,
+                recipient_token_account_1,
+            ),
+        );
+        self.process(&[instruction], &[owner]).await
+    }
+
+    pub async fn collect_fees(
+        &mut self,
+        owner: &Keypair,
+        keys: &PoolKeys,
+        position: &PositionKeys,
+        recipient_token_account_0: Pubkey,
+        recipient_token_account_1: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let instruction = build_instruction(
+            PROGRAM_ID,
+            &ClmmInstruction::CollectFees,
+            Self::position_accounts(
+                owner,
+                keys,
+                position,
+                recipient_token_account_0,
+                recipient_token_account_1,
+            ),
+        );
+        self.process(&[instruction], &[owner]).await
+    }
+
+    pub async fn close_position(
+        &mut self,
+        owner: &Keypair,
+        position: &PositionKeys,
+    ) -> Result<(), BanksClientError> {
+        let instruction = build_instruction(
+            PROGRAM_ID,
+            &ClmmInstruction::ClosePosition,
+            vec![
+                AccountMeta::new(owner.pubkey(), true),
+                AccountMeta::new(position.nft_mint, false),
+                AccountMeta::new(position.nft_account, false),
+                AccountMeta::new(position.position, false),
+                AccountMeta::new_readonly(spl_token_interface::ID, false),
+            ],
+        );
+        self.process(&[instruction], &[owner]).await
+    }
+
+    pub async fn initialize_tick_array(
+        &mut self,
+        payer: &Keypair,
+        keys: &PoolKeys,
+        start_tick_index: i32,
+    ) -> Result<(), BanksClientError> {
+        let (tick_array, _) =
+            pda::find_tick_array_address(&PROGRAM_ID, &keys.pool, start_tick_index);
+        let instruction = build_instruction(
+            PROGRAM_ID,
+            &ClmmInstruction::InitializeTickArray { start_tick_index },
+            vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new_readonly(keys.pool, false),
+                AccountMeta::new(tick_array, false),
+                AccountMeta::new_readonly(solana_system_interface::program::ID, false),
+            ],
+        );
+        self.process(&[instruction], &[payer]).await
+    }
+
+    pub async fn close_tick_array(
+        &mut self,
+        keys: &PoolKeys,
+        tick: i32,
+        recipient: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let instruction = build_instruction(
+            PROGRAM_ID,
+            &ClmmInstruction::CloseTickArray,
+            vec![
+                AccountMeta::new(keys.pool, false),
+                AccountMeta::new(keys.tick_array(tick), false),
+                AccountMeta::new(recipient, false),
+            ],
+        );
+        let payer = self.payer();
+        self.process(&[instruction], &[&payer]).await
+    }
+
+    pub async fn lamports(&mut self, address: Pubkey) -> u64 {
+        self.context
+            .banks_client
+            .get_balance(address)
+            .await
+            .unwrap()
+    }
+
+    pub async fn compound_fees(
+        &mut self,
+        owner: &Keypair,
+        keys: &PoolKeys,
+        position: &PositionKeys,
+    ) -> Result<(), BanksClientError> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(owner.pubkey(), true),
+            AccountMeta::new_readonly(position.nft_account, false),
+            AccountMeta::new(keys.pool, false),
+            AccountMeta::new(position.position, false),
+            AccountMeta::new(keys.tick_array(position.tick_lower), false),
+            AccountMeta::new(keys.tick_array(position.tick_upper), false),
+        ];
+        accounts.extend(self.allowlist_entry(keys, owner.pubkey()).await);
+        let instruction = build_instruction(
+            PROGRAM_ID,
+            &ClmmInstruction::CompoundFees {
+                deadline: self.deadline,
+                sqrt_price_band: self.sqrt_price_band,
+            },
+            accounts,
+        );
+        self.process(&[instruction], &[owner]).await
+    }
+
+    /// Moves `position` to `[tick_lower, tick_upper)`, returning the moved keys.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn reposition(
+        &mut self,
+        owner: &Keypair,
+        keys: &PoolKeys,
+        position: &PositionKeys,
+        tick_lower: i32,
+        tick_upper: i32,
+        liquidity_min: u128,
+        recipient_token_account_0: Pubkey,
+        recipient_token_account_1: Pubkey,
+        amount_0_leftover_max: u64,
+        amount_1_leftover_max: u64,
+    ) -> Result<PositionKeys, BanksClientError> {
+        let mut accounts = vec![
+            AccountMeta::new(owner.pubkey(), true),
+            AccountMeta::new_readonly(position.nft_account, false),
+            AccountMeta::new(keys.pool, false),
+            AccountMeta::new(position.position, false),
+            AccountMeta::new(keys.tick_array(position.tick_lower), false),
+            AccountMeta::new(keys.tick_array(position.tick_upper), false),
+            AccountMeta::new(keys.tick_array(tick_lower), false),
+            AccountMeta::new(keys.tick_array(tick_upper), false),
+            AccountMeta::new(recipient_token_account_0, false),
+            AccountMeta::new(recipient_token_account_1, false),
+            AccountMeta::new(keys.token_vault_0, false),
+            AccountMeta::new(keys.token_vault_1, false),
+            AccountMeta::new_readonly(spl_token_interface::ID, false),
+            AccountMeta::new_readonly(solana_system_interface::program::ID, false),
+        ];
+        accounts.extend(self.allowlist_entry(keys, owner.pubkey()).await);
+        let instruction = build_instruction(
+            PROGRAM_ID,
+            &ClmmInstruction::Reposition {
+                tick_lower,
+                tick_upper,
+                liquidity_min,
+                amount_0_leftover_max,
+                amount_1_leftover_max,
+                deadline: self.deadline,
+                sqrt_price_band: self.sqrt_price_band,
+            },
+            accounts,
+        );
+        self.process(&[instruction], &[owner]).await?;
+        Ok(PositionKeys {
+            tick_lower,
+            tick_upper,
+            ..*position
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn collect_fund_fees(
+        &mut self,
+        fund_owner: &Keypair,
+        keys: &PoolKeys,
+        recipient_token_account_0: Pubkey,
+        recipient_token_account_1: Pubkey,
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    ) -> Result<(), BanksClientError> {
+        let instruction = build_instruction(
+            PROGRAM_ID,
+            &ClmmInstruction::CollectFundFees {
+                amount_0_requested,
+                amount_1_requested,
+            },
+            vec![
+                AccountMeta::new_readonly(fund_owner.pubkey(), true),
+                AccountMeta::new_readonly(keys.amm_config, false),
+                AccountMeta::new(keys.pool, false),
+                AccountMeta::new(recipient_token_account_0, false),
+                AccountMeta::new(recipient_token_account_1, false),
+                AccountMeta::new(keys.token_vault_0, false),
+                AccountMeta::new(keys.token_vault_1, false),
+                AccountMeta::new_readonly(spl_token_interface::ID, false),
+            ],
+        );
+        self.process(&[instruction], &[fund_owner]).await
+    }
+
+    /// Simulates `QuoteSwap` through the tick arrays holding each of `ticks` and decodes its return data.
+    pub async fn quote_swap(
+        &mut self,
+        keys: &PoolKeys,
+        zero_for_one: bool,
+        amount: u64,
+        is_base_input: bool,
+        ticks: &[i32],
+    ) -> Result<SwapQuote, BanksClientError> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(keys.amm_config, false),
+            AccountMeta::new_readonly(keys.pool, false),
+        ];
+        accounts.extend(
+            ticks
+                .iter()
+                .map(|tick| AccountMeta::new_readonly(keys.tick_array(*tick), false)),
+        );
+        let instruction = build_instruction(
+            PROGRAM_ID,
+            &ClmmInstruction::QuoteSwap {
+                amount,
+                sqrt_price_limit_x64: 0,
+                zero_for_one,
+                is_base_input,
+            },
+            accounts,
+        );
+        let payer = self.payer();
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&payer.pubkey()),
+            &[&payer],
+            blockhash,
+        );
+        let simulation = self
+            .context
+            .banks_client
+            .simulate_transaction(transaction)
+            .await?;
+        if let Some(Err(err)) = simulation.result {
+            return Err(BanksClientError::TransactionError(err));
+        }
+        let return_data = simulation
+            .simulation_details
+            .and_then(|details| details.return_data)
+            .expect("quote sets return data");
+        assert_eq!(return_data.program_id, PROGRAM_ID);
+        Ok(SwapQuote::try_from_slice(&return_data.data).unwrap())
+    }
+
+    /// Swaps through the tick arrays holding each of `ticks`, in order.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn swap(
+        &mut self,
+        payer: &Keypair,
+        keys: &PoolKeys,
+        zero_for_one: bool,
+        input_token_account: Pubkey,
+        output_token_account: Pubkey,
+        amount: u64,
+        other_amount_threshold: u64,
+        is_base_input: bool,
+        ticks: &[i32],
+    ) -> Result<(), BanksClientError> {
+        self.swap_with_referral(
+            payer,
+            keys,
+            zero_for_one,
+            input_token_account,
+            output_token_account,
+            amount,
+            other_amount_threshold,
+            is_base_input,
+            ticks,
+            None,
+        )
+        .await
+    }
+
+    /// Like [`ClmmTest::swap`], paying a referral share to the referral token account.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn swap_with_referral(
+        &mut self,
+        payer: &Keypair,
+        keys: &PoolKeys,
+        zero_for_one: bool,
+        input_token_account: Pubkey,
+        output_token_account: Pubkey,
+        amount: u64,
+        other_amount_threshold: u64,
+        is_base_input: bool,
+        ticks: &[i32],
+        referral: Option<(Pubkey, u32)>,
+    ) -> Result<(), BanksClientError> {
+        let (input_vault, output_vault) = if zero_for_one {
+            (keys.token_vault_0, keys.token_vault_1)
+        } else {
+            (keys.token_vault_1, keys.token_vault_0)
+        };
+        let mut accounts = vec![
+            AccountMeta::new_readonly(payer.pubkey(), true),
+            AccountMeta::new_readonly(keys.amm_config, false),
+            AccountMeta::new(keys.pool, false),
+            AccountMeta::new(input_token_account, false),
+            AccountMeta::new(output_token_account, false),
+            AccountMeta::new(input_vault, false),
+            AccountMeta::new(output_vault, false),
+            AccountMeta::new_readonly(spl_token_interface::ID, false),
+        ];
+        accounts.extend(self.allowlist_entry(keys, payer.pubkey()).await);
+        accounts.extend(referral.map(|(referral, _)| AccountMeta::new(referral, false)));
+        accounts.extend(
+            ticks
+                .iter()
+                .map(|tick| AccountMeta::new(keys.tick_array(*tick), false)),
+        );
+        let instruction = build_instruction(
+            PROGRAM_ID,
+            &ClmmInstruction::Swap {
+                amount_in: amount,
+                minimum_amount_out: other_amount_threshold,
+                sqrt_price_limit_x64: 0,
+                is_base_input,
+                deadline: self.deadline,
+                referral_fee_rate: referral.map_or(0, |(_, rate)| rate),
+            },
+            accounts,
+        );
+        self.process(&[instruction], &[payer]).await
+    }
+}
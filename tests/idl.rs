@@ -0,0 +1,633 @@
+//! Builds the Anchor-compatible IDL from the program's borsh schemas and checks
+//! it against the committed `idl/clmm_demo.json`.
+//!
+//! Instruction arguments, account layouts, events and error codes come from the
+//! Rust types. Instruction accounts are listed in [`instruction_accounts`], in the
+//! order each processor reads them.
+//!
+//! After changing any of them, regenerate the file with
+//! `UPDATE_IDL=1 cargo test --test idl`.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use borsh::schema::{Declaration, Definition, Fields};
+use borsh::BorshSchema;
+use clmm_demo::error::ClmmError;
+use clmm_demo::events::*;
+use clmm_demo::instruction::ClmmInstruction;
+use clmm_demo::instructions::SwapQuote;
+use clmm_demo::state::*;
+use num_traits::FromPrimitive;
+use serde_json::{json, Map, Value};
+use solana_loader_v3_interface::get_program_data_address;
+
+const PRIMITIVES: &[&str] = &[
+    "bool", "u8", "u16", "u32", "u64", "u128", "i8", "i16", "i32", "i64", "i128",
+];
+
+/// Borsh schema definitions of every type reachable from the IDL
+struct Schema {
+    definitions: BTreeMap<Declaration, Definition>,
+    /// IDL `types` entries, by name
+    types: BTreeMap<String, Value>,
+}
+
+impl Schema {
+    fn new() -> Self {
+        Self {
+            definitions: BTreeMap::new(),
+            types: BTreeMap::new(),
+        }
+    }
+
+    fn add<T: BorshSchema>(&mut self) -> Declaration {
+        T::add_definitions_recursively(&mut self.definitions);
+        T::declaration()
+    }
+
+    fn definition(&self, declaration: &str) -> &Definition {
+        self.definitions
+            .get(declaration)
+            .unwrap_or_else(|| panic!("no schema for {declaration}"))
+    }
+
+    /// IDL type of `declaration`, registering named types on the way
+    fn idl_type(&mut self, declaration: &str) -> Value {
+        if PRIMITIVES.contains(&declaration) {
+            return json!(declaration);
+        }
+        if declaration == "Address" {
+            return json!("pubkey");
+        }
+        match self.definition(declaration).clone() {
+            Definition::Sequence {
+                length_width: 0,
+                length_range,
+                elements,
+            } => json!({ "array": [self.idl_type(&elements), length_range.end()] }),
+            Definition::Sequence { elements, .. } => json!({ "vec": self.idl_type(&elements) }),
+            Definition::Enum { variants, .. } if declaration.starts_with("Option<") => {
+                json!({ "option": self.idl_type(&variants[1].2) })
+            }
+            _ => {
+                self.define(declaration, false);
+                json!({ "defined": { "name": declaration } })
+            }
+        }
+    }
+
+    fn fields(&mut self, declaration: &str) -> Option<Value> {
+        let Definition::Struct { fields } = self.definition(declaration).clone() else {
+            panic!("{declaration} is not a struct");
+        };
+        match fields {
+            Fields::NamedFields(fields) => Some(Value::Array(
+                fields
+                    .iter()
+                    .map(|(name, ty)| json!({ "name": name, "type": self.idl_type(ty) }))
+                    .collect(),
+            )),
+            Fields::UnnamedFields(fields) => Some(Value::Array(
+                fields.iter().map(|ty| self.idl_type(ty)).collect(),
+            )),
+            Fields::Empty => None,
+        }
+    }
+
+    /// Adds `declaration` to the IDL types. Account layouts leave out the
+    /// discriminator, which Anchor clients read separately.
+    fn define(&mut self, declaration: &str, is_account: bool) {
+        if self.types.contains_key(declaration) {
+            return;
+        }
+        // Placeholder so recursive types terminate
+        self.types.insert(declaration.to_string(), Value::Null);
+        let ty = match self.definition(declaration).clone() {
+            Definition::Struct { .. } => {
+                let mut fields = self.fields(declaration).unwrap_or(json!([]));
+                if is_account {
+                    let fields = fields.as_array_mut().unwrap();
+                    assert_eq!(fields[0]["name"], "discriminator");
+                    fields.remove(0);
+                }
+                json!({ "kind": "struct", "fields": fields })
+            }
+            Definition::Enum { variants, .. } => {
+                let variants = variants
+                    .iter()
+                    .map(|(_, name, variant)| {
+                        let mut value = json!({ "name": name });
+                        if let Some(fields) = self.fields(variant) {
+                            value["fields"] = fields;
+                        }
+                        value
+                    })
+                    .collect::<Vec<_>>();
+                json!({ "kind": "enum", "variants": variants })
+            }
+            definition => panic!("unexpected definition for {declaration}: {definition:?}"),
+        };
+        self.types.insert(
+            declaration.to_string(),
+            json!({ "name": declaration, "type": ty }),
+        );
+    }
+}
+
+enum Seed {
+    Const(&'static [u8]),
+    Account(&'static str),
+    Arg(&'static str),
+}
+
+struct Account {
+    name: &'static str,
+    writable: bool,
+    signer: bool,
+    optional: bool,
+    address: Option<String>,
+    seeds: Vec<Seed>,
+}
+
+fn account(name: &'static str) -> Account {
+    Account {
+        name,
+        writable: false,
+        signer: false,
+        optional: false,
+        address: None,
+        seeds: Vec::new(),
+    }
+}
+
+impl Account {
+    fn writable(mut self) -> Self {
+        self.writable = true;
+        self
+    }
+
+    fn signer(mut self) -> Self {
+        self.signer = true;
+        self
+    }
+
+    fn optional(mut self) -> Self {
+        self.optional = true;
+        self
+    }
+
+    fn pda(mut self, seeds: Vec<Seed>) -> Self {
+        self.seeds = seeds;
+        self
+    }
+
+    fn to_json(&self) -> Value {
+        let mut value = json!({ "name": self.name });
+        if self.writable {
+            value["writable"] = json!(true);
+        }
+        if self.signer {
+            value["signer"] = json!(true);
+        }
+        if self.optional {
+            value["optional"] = json!(true);
+        }
+        if let Some(address) = &self.address {
+            value["address"] = json!(address);
+        }
+        if !self.seeds.is_empty() {
+            let seeds = self
+                .seeds
+                .iter()
+                .map(|seed| match seed {
+                    Seed::Const(bytes) => json!({ "kind": "const", "value": bytes }),
+                    Seed::Account(path) => json!({ "kind": "account", "path": path }),
+                    Seed::Arg(path) => json!({ "kind": "arg", "path": path }),
+                })
+                .collect::<Vec<_>>();
+            value["pda"] = json!({ "seeds": seeds });
+        }
+        value
+    }
+}
+
+fn token_program() -> Account {
+    Account {
+        address: Some(spl_token_interface::ID.to_string()),
+        ..account("token_program")
+    }
+}
+
+fn system_program() -> Account {
+    Account {
+        address: Some(solana_system_interface::program::ID.to_string()),
+        ..account("system_program")
+    }
+}
+
+fn fee_tier_registry() -> Account {
+    account("fee_tier_registry")
+        .writable()
+        .pda(vec![Seed::Const(FEE_TIER_REGISTRY_SEED)])
+}
+
+fn pool_vault(name: &'static str, mint: &'static str) -> Account {
+    account(name).writable().pda(vec![
+        Seed::Const(POOL_VAULT_SEED),
+        Seed::Account("pool"),
+        Seed::Account(mint),
+    ])
+}
+
+fn allowlist_entry(wallet: &'static str) -> Account {
+    account("allowlist_entry").optional().pda(vec![
+        Seed::Const(ALLOWLIST_ENTRY_SEED),
+        Seed::Account("pool"),
+        Seed::Account(wallet),
+    ])
+}
+
+/// Position owner, pool, position and the two tick arrays of its range
+fn position_accounts(owner: Account) -> Vec<Account> {
+    vec![
+        owner,
+        account("nft_account"),
+        account("pool").writable(),
+        account("position").writable(),
+        account("tick_array_lower").writable(),
+        account("tick_array_upper").writable(),
+    ]
+}
+
+fn token_accounts(prefix: &'static str) -> Vec<Account> {
+    let (token_account_0, token_account_1) = match prefix {
+        "recipient" => ("recipient_token_account_0", "recipient_token_account_1"),
+        _ => ("token_account_0", "token_account_1"),
+    };
+    vec![
+        account(token_account_0).writable(),
+        account(token_account_1).writable(),
+        account("token_vault_0").writable(),
+        account("token_vault_1").writable(),
+        token_program(),
+    ]
+}
+
+/// Accounts each instruction expects, with extra notes for trailing accounts.
+fn instruction_accounts(name: &str) -> (Vec<Account>, Option<&'static str>) {
+    let accounts = match name {
+        "InitializeConfig" => vec![
+            account("admin").writable().signer(),
+            account("amm_config").writable(),
+            fee_tier_registry(),
+            system_program(),
+        ],
+        "CreatePool" => vec![
+            account("creator").writable().signer(),
+            account("amm_config"),
+            account("pool").writable().pda(vec![
+                Seed::Const(POOL_SEED),
+                Seed::Account("amm_config"),
+                Seed::Account("token_mint_0"),
+                Seed::Account("token_mint_1"),
+            ]),
+            account("token_mint_0"),
+            account("token_mint_1"),
+            pool_vault("token_vault_0", "token_mint_0"),
+            pool_vault("token_vault_1", "token_mint_1"),
+            token_program(),
+            system_program(),
+        ],
+        "OpenPosition" => {
+            let mut accounts = vec![
+                account("payer").writable().signer(),
+                account("pool").writable(),
+                account("nft_mint").writable().signer(),
+                account("nft_account").writable().signer(),
+                account("position")
+                    .writable()
+                    .pda(vec![Seed::Const(POSITION_SEED), Seed::Account("nft_mint")]),
+                account("tick_array_lower").writable(),
+                account("tick_array_upper").writable(),
+            ];
+            accounts.extend(token_accounts("payer"));
+            accounts.push(system_program());
+            accounts.push(allowlist_entry("payer"));
+            accounts
+        }
+        "IncreaseLiquidity" => {
+            let mut accounts = position_accounts(account("owner").signer());
+            accounts.extend(token_accounts("owner"));
+            accounts.push(allowlist_entry("owner"));
+            accounts
+        }
+        "DecreaseLiquidity" | "CollectFees" => {
+            let mut accounts = position_accounts(account("owner").signer());
+            accounts.extend(token_accounts("recipient"));
+            accounts
+        }
+        "Swap" => vec![
+            account("payer").signer(),
+            account("amm_config"),
+            account("pool").writable(),
+            account("input_token_account").writable(),
+            account("output_token_account").writable(),
+            account("input_vault").writable(),
+            account("output_vault").writable(),
+            token_program(),
+            allowlist_entry("payer"),
+            account("referral_token_account").writable().optional(),
+        ],
+        "MigrateAccount" => vec![
+            account("admin").signer(),
+            account("payer").writable().signer(),
+            account("amm_config"),
+            account("target").writable(),
+            system_program(),
+            account("pool").optional(),
+        ],
+        "InitializeFeeTierRegistry" => vec![
+            account("admin").writable().signer(),
+            fee_tier_registry(),
+            system_program(),
+            Account {
+                address: Some(get_program_data_address(&clmm_demo::ID).to_string()),
+                ..account("program_data")
+            },
+        ],
+        "SetFeeTierEnabled" => vec![
+            account("admin").signer(),
+            fee_tier_registry(),
+            account("amm_config").writable(),
+        ],
+        "ClosePosition" => vec![
+            account("owner").writable().signer(),
+            account("nft_mint").writable(),
+            account("nft_account").writable(),
+            account("position")
+                .writable()
+                .pda(vec![Seed::Const(POSITION_SEED), Seed::Account("nft_mint")]),
+            token_program(),
+        ],
+        "CloseTickArray" => vec![
+            account("pool").writable(),
+            account("tick_array").writable(),
+            account("recipient").writable(),
+        ],
+        "InitializePositionBundle" => vec![
+            account("payer").writable().signer(),
+            account("bundle_mint").writable().signer(),
+            account("bundle_token_account").writable().signer(),
+            account("position_bundle").writable().pda(vec![
+                Seed::Const(POSITION_BUNDLE_SEED),
+                Seed::Account("bundle_mint"),
+            ]),
+            token_program(),
+            system_program(),
+        ],
+        "OpenBundledPosition" => {
+            let mut accounts = vec![
+                account("owner").writable().signer(),
+                account("bundle_token_account"),
+                account("position_bundle").writable(),
+                account("pool").writable(),
+                account("position").writable(),
+                account("tick_array_lower").writable(),
+                account("tick_array_upper").writable(),
+            ];
+            accounts.extend(token_accounts("owner"));
+            accounts.push(system_program());
+            accounts.push(allowlist_entry("owner"));
+            accounts
+        }
+        "CloseBundledPosition" => vec![
+            account("owner").writable().signer(),
+            account("bundle_token_account"),
+            account("position_bundle").writable(),
+            account("position").writable(),
+        ],
+        "ClosePositionBundle" => vec![
+            account("owner").writable().signer(),
+            account("bundle_mint").writable(),
+            account("bundle_token_account").writable(),
+            account("position_bundle").writable().pda(vec![
+                Seed::Const(POSITION_BUNDLE_SEED),
+                Seed::Account("bundle_mint"),
+            ]),
+            token_program(),
+        ],
+        "CompoundFees" => {
+            let mut accounts = position_accounts(account("owner").signer());
+            accounts.push(allowlist_entry("owner"));
+            accounts
+        }
+        "Reposition" => {
+            let mut accounts = vec![
+                account("owner").writable().signer(),
+                account("nft_account"),
+                account("pool").writable(),
+                account("position").writable(),
+                account("old_tick_array_lower").writable(),
+                account("old_tick_array_upper").writable(),
+                account("tick_array_lower").writable(),
+                account("tick_array_upper").writable(),
+            ];
+            accounts.extend(token_accounts("recipient"));
+            accounts.push(system_program());
+            accounts.push(allowlist_entry("owner"));
+            accounts
+        }
+        "CollectFundFees" => {
+            let mut accounts = vec![
+                account("fund_owner").signer(),
+                account("amm_config"),
+                account("pool").writable(),
+            ];
+            accounts.extend(token_accounts("recipient"));
+            accounts
+        }
+        "AddToAllowlist" => vec![
+            account("permission_authority").writable().signer(),
+            account("pool"),
+            account("allowlist_entry").writable().pda(vec![
+                Seed::Const(ALLOWLIST_ENTRY_SEED),
+                Seed::Account("pool"),
+                Seed::Arg("wallet"),
+            ]),
+            system_program(),
+        ],
+        "RemoveFromAllowlist" => vec![
+            account("permission_authority").writable().signer(),
+            account("pool"),
+            account("allowlist_entry").writable(),
+        ],
+        "SetPoolFeeRate" => vec![
+            account("admin").signer(),
+            account("amm_config"),
+            account("pool").writable(),
+        ],
+        "InitializeTickArray" => vec![
+            account("payer").writable().signer(),
+            account("pool"),
+            account("tick_array").writable(),
+            system_program(),
+        ],
+        "QuoteSwap" => vec![account("amm_config"), account("pool")],
+        "SetMaxReferralFeeRate" => {
+            vec![account("admin").signer(), account("amm_config").writable()]
+        }
+        name => panic!("no accounts listed for instruction {name}"),
+    };
+    let docs = match name {
+        "Swap" => Some(
+            "Tick arrays follow as remaining writable accounts, starting with the one holding the current tick. Closed arrays are passed at their address.",
+        ),
+        "QuoteSwap" => Some(
+            "Tick arrays follow as remaining accounts, starting with the one holding the current tick. Closed arrays are passed at their address.",
+        ),
+        _ => None,
+    };
+    (accounts, docs)
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            snake.push('_');
+        }
+        snake.push(c.to_ascii_lowercase());
+    }
+    snake
+}
+
+fn generate_idl() -> Value {
+    let mut schema = Schema::new();
+
+    let instruction = schema.add::<ClmmInstruction>();
+    let Definition::Enum { variants, .. } = schema.definition(&instruction).clone() else {
+        panic!("ClmmInstruction is not an enum");
+    };
+    let instructions = variants
+        .iter()
+        .map(|(index, name, variant)| {
+            let args = schema.fields(variant).unwrap_or(json!([]));
+            let (accounts, docs) = instruction_accounts(name);
+            let mut value = json!({
+                "name": to_snake_case(name),
+                "discriminator": [index],
+                "accounts": accounts.iter().map(Account::to_json).collect::<Vec<_>>(),
+                "args": args,
+            });
+            if let Some(docs) = docs {
+                value["docs"] = json!([docs]);
+            }
+            if name == "QuoteSwap" {
+                let quote = schema.add::<SwapQuote>();
+                value["returns"] = schema.idl_type(&quote);
+            }
+            value
+        })
+        .collect::<Vec<_>>();
+
+    let mut accounts = Vec::new();
+    let mut add_account =
+        |schema: &mut Schema, declaration: Declaration, discriminator: [u8; 8]| {
+            schema.define(&declaration, true);
+            accounts.push(json!({ "name": declaration, "discriminator": discriminator }));
+        };
+    let declaration = schema.add::<AmmConfig>();
+    add_account(&mut schema, declaration, AmmConfig::DISCRIMINATOR);
+    let declaration = schema.add::<FeeTierRegistry>();
+    add_account(&mut schema, declaration, FeeTierRegistry::DISCRIMINATOR);
+    let declaration = schema.add::<PoolState>();
+    add_account(&mut schema, declaration, PoolState::DISCRIMINATOR);
+    let declaration = schema.add::<PositionState>();
+    add_account(&mut schema, declaration, PositionState::DISCRIMINATOR);
+    let declaration = schema.add::<PositionBundleState>();
+    add_account(&mut schema, declaration, PositionBundleState::DISCRIMINATOR);
+    let declaration = schema.add::<TickArrayState>();
+    add_account(&mut schema, declaration, TickArrayState::DISCRIMINATOR);
+    let declaration = schema.add::<AllowlistEntry>();
+    add_account(&mut schema, declaration, AllowlistEntry::DISCRIMINATOR);
+
+    let mut events = Vec::new();
+    let mut add_event = |schema: &mut Schema, declaration: Declaration, discriminator: [u8; 8]| {
+        schema.define(&declaration, false);
+        events.push(json!({ "name": declaration, "discriminator": discriminator }));
+    };
+    let declaration = schema.add::<PoolCreatedEvent>();
+    add_event(&mut schema, declaration, PoolCreatedEvent::DISCRIMINATOR);
+    let declaration = schema.add::<PositionOpenedEvent>();
+    add_event(&mut schema, declaration, PositionOpenedEvent::DISCRIMINATOR);
+    let declaration = schema.add::<PositionClosedEvent>();
+    add_event(&mut schema, declaration, PositionClosedEvent::DISCRIMINATOR);
+    let declaration = schema.add::<LiquidityIncreasedEvent>();
+    add_event(
+        &mut schema,
+        declaration,
+        LiquidityIncreasedEvent::DISCRIMINATOR,
+    );
+    let declaration = schema.add::<LiquidityDecreasedEvent>();
+    add_event(
+        &mut schema,
+        declaration,
+        LiquidityDecreasedEvent::DISCRIMINATOR,
+    );
+    let declaration = schema.add::<SwapEvent>();
+    add_event(&mut schema, declaration, SwapEvent::DISCRIMINATOR);
+    let declaration = schema.add::<CollectFeesEvent>();
+    add_event(&mut schema, declaration, CollectFeesEvent::DISCRIMINATOR);
+    let declaration = schema.add::<CollectFundFeesEvent>();
+    add_event(
+        &mut schema,
+        declaration,
+        CollectFundFeesEvent::DISCRIMINATOR,
+    );
+
+    let errors = (0..)
+        .map_while(ClmmError::from_u32)
+        .enumerate()
+        .map(|(code, error)| json!({ "code": code, "name": format!("{error:?}"), "msg": error.to_string() }))
+        .collect::<Vec<_>>();
+
+    let mut idl = Map::new();
+    idl.insert("address".into(), json!(clmm_demo::ID.to_string()));
+    idl.insert(
+        "metadata".into(),
+        json!({
+            "name": "clmm_demo",
+            "version": env!("CARGO_PKG_VERSION"),
+            "spec": "0.1.0",
+            "description": env!("CARGO_PKG_DESCRIPTION"),
+        }),
+    );
+    idl.insert("instructions".into(), json!(instructions));
+    idl.insert("accounts".into(), json!(accounts));
+    idl.insert("events".into(), json!(events));
+    idl.insert("errors".into(), json!(errors));
+    idl.insert(
+        "types".into(),
+        json!(schema.types.into_values().collect::<Vec<_>>()),
+    );
+    Value::Object(idl)
+}
+
+#[test]
+fn test_idl_matches_program() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("idl/clmm_demo.json");
+    let generated = serde_json::to_string_pretty(&generate_idl()).unwrap() + "\n";
+
+    if std::env::var_os("UPDATE_IDL").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, generated).unwrap();
+        return;
+    }
+    let committed = std::fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+        committed == generated,
+        "{} is out of date, regenerate it with `UPDATE_IDL=1 cargo test --test idl`",
+        path.display()
+    );
+}
@@ -0,0 +1,2124 @@
+#![cfg(feature = "test-sbf")]
+#![allow(deprecated)]
+
+mod common;
+
+use clmm_demo::cpi;
+use clmm_demo::error::ClmmError;
+use clmm_demo::instructions::{Deadline, SqrtPriceBand};
+use clmm_demo::libraries::liquidity_math::{
+    get_delta_amount_0_unsigned, get_delta_amount_1_unsigned, LiquidityQuote,
+};
+use clmm_demo::libraries::tick_math::get_sqrt_price_at_tick;
+use clmm_demo::pda;
+use clmm_demo::state::{
+    AllowlistEntry, AmmConfig, FeeTierRegistry, PoolState, PositionBundleState, PositionState,
+    TickArrayState,
+};
+use common::{ClmmTest, PoolKeys, PoolOptions, PositionKeys};
+use solana_account_info::AccountInfo;
+use solana_instruction::error::InstructionError;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_keypair::Keypair;
+use solana_program_error::{ProgramError, ProgramResult};
+use solana_program_test::{processor, BanksClientError};
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::TransactionError;
+
+const TRADE_FEE_RATE: u32 = 3_000;
+const PROTOCOL_FEE_RATE: u32 = 120_000;
+const TICK_SPACING: u16 = 10;
+const INITIAL_BALANCE: u64 = 1_000_000_000_000;
+
+const TICK_LOWER: i32 = -100;
+const TICK_UPPER: i32 = 100;
+const LIQUIDITY: u128 = 10_000_000_000;
+
+struct Setup {
+    test: ClmmTest,
+    user: Keypair,
+    keys: PoolKeys,
+    user_token_0: Pubkey,
+    user_token_1: Pubkey,
+}
+
+fn assert_clmm_error(result: Result<(), BanksClientError>, expected: ClmmError) {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+            assert_eq!(code, expected as u32)
+        }
+        err => panic!("unexpected error {err:?}"),
+    }
+}
+
+/// Config, pool at price 1 and a user holding both tokens
+async fn setup() -> Setup {
+    setup_on(ClmmTest::start().await).await
+}
+
+async fn setup_on(mut test: ClmmTest) -> Setup {
+    let admin = test.payer();
+    let user = Keypair::new();
+    test.airdrop(&user.pubkey(), 10_000_000_000).await;
+
+    let amm_config = test
+        .initialize_config(
+            &admin,
+            0,
+            TRADE_FEE_RATE,
+            PROTOCOL_FEE_RATE,
+            0,
+            admin.pubkey(),
+            0,
+            TICK_SPACING,
+        )
+        .await
+        .unwrap();
+    let mint_a = test.create_mint(6).await;
+    let mint_b = test.create_mint(9).await;
+    let keys = PoolKeys::new(amm_config, mint_a, mint_b, TICK_SPACING);
+    test.create_pool(&user, &keys, 1 << 64).await.unwrap();
+
+    let user_token_0 = test
+        .create_funded_token_account(&keys.token_mint_0, &user.pubkey(), INITIAL_BALANCE)
+        .await;
+    let user_token_1 = test
+        .create_funded_token_account(&keys.token_mint_1, &user.pubkey(), INITIAL_BALANCE)
+        .await;
+
+    Setup {
+        test,
+        user,
+        keys,
+        user_token_0,
+        user_token_1,
+    }
+}
+
+async fn open_default_position(setup: &mut Setup) -> PositionKeys {
+    setup
+        .test
+        .open_position(
+            &setup.user,
+            &setup.keys,
+            TICK_LOWER,
+            TICK_UPPER,
+            LIQUIDITY,
+            setup.user_token_0,
+            setup.user_token_1,
+            u64::MAX,
+            u64::MAX,
+        )
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_full_lifecycle() {
+    let mut setup = setup().await;
+    let keys = setup.keys;
+    let (user_token_0, user_token_1) = (setup.user_token_0, setup.user_token_1);
+
+    // Config and pool
+    let amm_config: AmmConfig = setup.test.get_state(keys.amm_config).await;
+    assert_eq!(amm_config.admin, setup.test.payer().pubkey());
+    assert_eq!(amm_config.trade_fee_rate, TRADE_FEE_RATE);
+    assert_eq!(amm_config.tick_spacing, TICK_SPACING);
+
+    let pool: PoolState = setup.test.get_state(keys.pool).await;
+    assert_eq!(pool.token_mint_0, keys.token_mint_0);
+    assert_eq!(pool.token_vault_1, keys.token_vault_1);
+    assert_eq!(pool.tick_current, 0);
+    assert_eq!(pool.sqrt_price_x64, 1 << 64);
+    assert_eq!(pool.liquidity, 0);
+    assert_eq!(setup.test.token_balance(keys.token_vault_0).await, 0);
+    assert_eq!(setup.test.token_balance(keys.token_vault_1).await, 0);
+
+    // Open a position around the current price
+    let position_keys = open_default_position(&mut setup).await;
+    let test = &mut setup.test;
+    let user = &setup.user;
+
+    let sqrt_price_lower = get_sqrt_price_at_tick(TICK_LOWER).unwrap();
+    let sqrt_price_upper = get_sqrt_price_at_tick(TICK_UPPER).unwrap();
+    let expected_0 =
+        get_delta_amount_0_unsigned(1 << 64, sqrt_price_upper, LIQUIDITY, true).unwrap();
+    let expected_1 =
+        get_delta_amount_1_unsigned(sqrt_price_lower, 1 << 64, LIQUIDITY, true).unwrap();
+    assert_eq!(test.token_balance(keys.token_vault_0).await, expected_0);
+    assert_eq!(test.token_balance(keys.token_vault_1).await, expected_1);
+    assert_eq!(
+        test.token_balance(user_token_0).await,
+        INITIAL_BALANCE - expected_0
+    );
+    assert_eq!(test.token_balance(position_keys.nft_account).await, 1);
+
+    let position: PositionState = test.get_state(position_keys.position).await;
+    assert_eq!(position.liquidity, LIQUIDITY);
+    assert_eq!(position.pool_id, keys.pool);
+    let pool: PoolState = test.get_state(keys.pool).await;
+    assert_eq!(pool.liquidity, LIQUIDITY);
+    assert!(pool.is_tick_array_initialized(-600));
+    assert!(pool.is_tick_array_initialized(0));
+
+    let lower_array: TickArrayState = test.get_state(keys.tick_array(TICK_LOWER)).await;
+    let lower = lower_array.ticks[TickArrayState::get_tick_offset_in_array(TICK_LOWER, 10)];
+    assert_eq!(lower.tick, TICK_LOWER);
+    assert_eq!(lower.liquidity_net, LIQUIDITY as i128);
+    assert_eq!(lower_array.initialized_tick_count, 1);
+
+    // Swap token 0 for token 1
+    let vault_0_before = test.token_balance(keys.token_vault_0).await;
+    let vault_1_before = test.token_balance(keys.token_vault_1).await;
+    let user_1_before = test.token_balance(user_token_1).await;
+    test.swap(
+        user,
+        &keys,
+        true,
+        user_token_0,
+        user_token_1,
+        1_000_000,
+        990_000,
+        true,
+        &[0, -600],
+    )
+    .await
+    .unwrap();
+
+    let amount_out = test.token_balance(user_token_1).await - user_1_before;
+    assert_eq!(
+        test.token_balance(keys.token_vault_0).await,
+        vault_0_before + 1_000_000
+    );
+    assert_eq!(
+        test.token_balance(keys.token_vault_1).await,
+        vault_1_before - amount_out
+    );
+    // 0.3% fee and a little price impact
+    assert!(amount_out < 997_000 && amount_out > 990_000);
+
+    let pool: PoolState = test.get_state(keys.pool).await;
+    assert!(pool.sqrt_price_x64 < 1 << 64);
+    assert!(pool.tick_current < 0 && pool.tick_current > TICK_LOWER);
+    assert_eq!(pool.liquidity, LIQUIDITY);
+    assert!(pool.fee_growth_global_0_x64 > 0);
+    assert_eq!(pool.fee_growth_global_1_x64, 0);
+    // 12% of the 3000 fee
+    assert_eq!(pool.protocol_fees_token_0, 360);
+
+    // Swap back, token 1 for exactly 500_000 token 0
+    let user_0_before = test.token_balance(user_token_0).await;
+    let user_1_before = test.token_balance(user_token_1).await;
+    test.swap(
+        user,
+        &keys,
+        false,
+        user_token_1,
+        user_token_0,
+        500_000,
+        510_000,
+        false,
+        &[pool.tick_current, 0],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        test.token_balance(user_token_0).await - user_0_before,
+        500_000
+    );
+    let amount_in = user_1_before - test.token_balance(user_token_1).await;
+    assert!(amount_in > 500_000 && amount_in < 510_000);
+
+    let pool: PoolState = test.get_state(keys.pool).await;
+    assert!(pool.fee_growth_global_1_x64 > 0);
+    assert!(pool.protocol_fees_token_1 > 0);
+
+    // Collect fees, the only LP earns everything but the protocol share
+    let user_0_before = test.token_balance(user_token_0).await;
+    let user_1_before = test.token_balance(user_token_1).await;
+    test.collect_fees(user, &keys, &position_keys, user_token_0, user_token_1)
+        .await
+        .unwrap();
+    let fees_0 = test.token_balance(user_token_0).await - user_0_before;
+    let fees_1 = test.token_balance(user_token_1).await - user_1_before;
+    assert!((2_638..=2_640).contains(&fees_0), "fees_0 = {fees_0}");
+    assert!(fees_1 > 0);
+
+    let position: PositionState = test.get_state(position_keys.position).await;
+    assert_eq!(position.token_fees_owed_0, 0);
+    assert_eq!(position.token_fees_owed_1, 0);
+
+    // Remove all liquidity, leaving only protocol fees and rounding dust
+    test.decrease_liquidity(
+        user,
+        &keys,
+        &position_keys,
+        LIQUIDITY,
+        user_token_0,
+        user_token_1,
+        0,
+        0,
+    )
+    .await
+    .unwrap();
+
+    let position: PositionState = test.get_state(position_keys.position).await;
+    assert_eq!(position.liquidity, 0);
+    let pool: PoolState = test.get_state(keys.pool).await;
+    assert_eq!(pool.liquidity, 0);
+    assert!(!pool.is_tick_array_initialized(-600));
+    assert!(!pool.is_tick_array_initialized(0));
+
+    let vault_0 = test.token_balance(keys.token_vault_0).await;
+    let vault_1 = test.token_balance(keys.token_vault_1).await;
+    // Deposits round up, withdrawals and fee growth round down
+    assert!(vault_0 >= pool.protocol_fees_token_0 && vault_0 - pool.protocol_fees_token_0 <= 3);
+    assert!(vault_1 >= pool.protocol_fees_token_1 && vault_1 - pool.protocol_fees_token_1 <= 3);
+
+    let lower_array: TickArrayState = test.get_state(keys.tick_array(TICK_LOWER)).await;
+    assert_eq!(lower_array.initialized_tick_count, 0);
+}
+
+#[tokio::test]
+async fn test_increase_liquidity_slippage() {
+    let mut setup = setup().await;
+    let position_keys = open_default_position(&mut setup).await;
+
+    let result = setup
+        .test
+        .increase_liquidity(
+            &setup.user,
+            &setup.keys,
+            &position_keys,
+            LIQUIDITY,
+            setup.user_token_0,
+            setup.user_token_1,
+            1,
+            1,
+        )
+        .await;
+    assert_clmm_error(result, ClmmError::SlippageExceeded);
+
+    setup
+        .test
+        .increase_liquidity(
+            &setup.user,
+            &setup.keys,
+            &position_keys,
+            LIQUIDITY,
+            setup.user_token_0,
+            setup.user_token_1,
+            u64::MAX,
+            u64::MAX,
+        )
+        .await
+        .unwrap();
+    let position: PositionState = setup.test.get_state(position_keys.position).await;
+    assert_eq!(position.liquidity, 2 * LIQUIDITY);
+}
+
+#[tokio::test]
+async fn test_open_position_charges_quoted_amounts() {
+    let mut setup = setup().await;
+    let quote =
+        LiquidityQuote::from_amount_0(0, 1 << 64, TICK_LOWER, TICK_UPPER, 1_234_567).unwrap();
+
+    // The quoted amounts are exact, so they pass as the slippage limits
+    setup
+        .test
+        .open_position(
+            &setup.user,
+            &setup.keys,
+            TICK_LOWER,
+            TICK_UPPER,
+            quote.liquidity,
+            setup.user_token_0,
+            setup.user_token_1,
+            quote.amount_0,
+            quote.amount_1,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        setup.test.token_balance(setup.keys.token_vault_0).await,
+        quote.amount_0
+    );
+    assert_eq!(
+        setup.test.token_balance(setup.keys.token_vault_1).await,
+        quote.amount_1
+    );
+}
+
+#[tokio::test]
+async fn test_only_nft_holder_can_decrease() {
+    let mut setup = setup().await;
+    let position_keys = open_default_position(&mut setup).await;
+
+    let attacker = Keypair::new();
+    setup.test.airdrop(&attacker.pubkey(), 1_000_000_000).await;
+    let attacker_0 = setup
+        .test
+        .create_token_account(&setup.keys.token_mint_0, &attacker.pubkey())
+        .await;
+    let attacker_1 = setup
+        .test
+        .create_token_account(&setup.keys.token_mint_1, &attacker.pubkey())
+        .await;
+
+    let result = setup
+        .test
+        .decrease_liquidity(
+            &attacker,
+            &setup.keys,
+            &position_keys,
+            LIQUIDITY,
+            attacker_0,
+            attacker_1,
+            0,
+            0,
+        )
+        .await;
+    assert_clmm_error(result, ClmmError::Unauthorized);
+}
+
+#[tokio::test]
+async fn test_swap_minimum_out() {
+    let mut setup = setup().await;
+    open_default_position(&mut setup).await;
+
+    let result = setup
+        .test
+        .swap(
+            &setup.user,
+            &setup.keys,
+            true,
+            setup.user_token_0,
+            setup.user_token_1,
+            1_000_000,
+            1_000_000,
+            true,
+            &[0, -600],
+        )
+        .await;
+    assert_clmm_error(result, ClmmError::SlippageExceeded);
+}
+
+#[tokio::test]
+async fn test_swap_past_last_tick_array() {
+    let mut setup = setup().await;
+    open_default_position(&mut setup).await;
+
+    // Enough input to push the price through the position and out of the only array given
+    let result = setup
+        .test
+        .swap(
+            &setup.user,
+            &setup.keys,
+            false,
+            setup.user_token_1,
+            setup.user_token_0,
+            100_000_000_000,
+            0,
+            true,
+            &[0],
+        )
+        .await;
+    assert_clmm_error(result, ClmmError::NotEnoughTickArrays);
+}
+
+#[tokio::test]
+async fn test_swap_rejects_repeated_tick_array() {
+    let mut setup = setup().await;
+    open_default_position(&mut setup).await;
+    let pool_before: PoolState = setup.test.get_state(setup.keys.pool).await;
+
+    // Small enough to stay inside the first array, where the array order is never checked
+    let result = setup
+        .test
+        .swap(
+            &setup.user,
+            &setup.keys,
+            true,
+            setup.user_token_0,
+            setup.user_token_1,
+            1_000,
+            0,
+            true,
+            &[0, 0],
+        )
+        .await;
+    assert_clmm_error(result, ClmmError::InvalidTickArray);
+
+    let pool: PoolState = setup.test.get_state(setup.keys.pool).await;
+    assert_eq!(pool.sqrt_price_x64, pool_before.sqrt_price_x64);
+}
+
+#[tokio::test]
+async fn test_fee_tier_registry_requires_upgrade_authority() {
+    let mut test = ClmmTest::start().await;
+    let outsider = Keypair::new();
+    test.airdrop(&outsider.pubkey(), 10_000_000_000).await;
+
+    let result = test
+        .initialize_fee_tier_registry(&outsider)
+        .await
+        .map(|_| ());
+    assert_clmm_error(result, ClmmError::Unauthorized);
+
+    let admin = test.payer();
+    let registry_key = test.initialize_fee_tier_registry(&admin).await.unwrap();
+    let registry: FeeTierRegistry = test.get_state(registry_key).await;
+    assert_eq!(registry.admin, admin.pubkey());
+}
+
+#[tokio::test]
+async fn test_fee_tier_registry() {
+    let mut setup = setup().await;
+    let admin = setup.test.payer();
+    let keys = setup.keys;
+
+    let second = setup
+        .test
+        .initialize_config(&admin, 1, 500, PROTOCOL_FEE_RATE, 0, admin.pubkey(), 0, 1)
+        .await
+        .unwrap();
+
+    // Every tier is listed, so clients can discover them without probing indices
+    let (registry_key, _) = pda::find_fee_tier_registry_address(&common::PROGRAM_ID);
+    let registry: FeeTierRegistry = setup.test.get_state(registry_key).await;
+    let tiers = registry.tiers();
+    assert_eq!(tiers.len(), 2);
+    assert_eq!(tiers[0].amm_config, keys.amm_config);
+    assert_eq!(tiers[0].tick_spacing, TICK_SPACING);
+    assert_eq!(tiers[1].amm_config, second);
+    assert_eq!(tiers[1].trade_fee_rate, 500);
+
+    // Tick spacings are unique across tiers
+    let result = setup
+        .test
+        .initialize_config(
+            &admin,
+            2,
+            10_000,
+            PROTOCOL_FEE_RATE,
+            0,
+            admin.pubkey(),
+            0,
+            TICK_SPACING,
+        )
+        .await
+        .map(|_| ());
+    assert_clmm_error(result, ClmmError::DuplicateTickSpacing);
+
+    // Only the registry admin adds tiers
+    let result = setup
+        .test
+        .initialize_config(
+            &setup.user,
+            3,
+            10_000,
+            PROTOCOL_FEE_RATE,
+            0,
+            setup.user.pubkey(),
+            0,
+            200,
+        )
+        .await
+        .map(|_| ());
+    assert_clmm_error(result, ClmmError::Unauthorized);
+
+    // A disabled tier rejects new pools
+    let result = setup
+        .test
+        .set_fee_tier_enabled(&setup.user, second, false)
+        .await;
+    assert_clmm_error(result, ClmmError::Unauthorized);
+    setup
+        .test
+        .set_fee_tier_enabled(&admin, second, false)
+        .await
+        .unwrap();
+    let registry: FeeTierRegistry = setup.test.get_state(registry_key).await;
+    assert_eq!(registry.tiers()[1].status, AmmConfig::STATUS_DISABLED);
+
+    let second_keys = PoolKeys::new(second, keys.token_mint_0, keys.token_mint_1, 1);
+    let result = setup
+        .test
+        .create_pool(&setup.user, &second_keys, 1 << 64)
+        .await;
+    assert_clmm_error(result, ClmmError::FeeTierDisabled);
+
+    setup
+        .test
+        .set_fee_tier_enabled(&admin, second, true)
+        .await
+        .unwrap();
+    setup
+        .test
+        .create_pool(&setup.user, &second_keys, 1 << 64)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_close_position_and_tick_array() {
+    let mut setup = setup().await;
+    let keys = setup.keys;
+    let position_keys = open_default_position(&mut setup).await;
+    let test = &mut setup.test;
+    let user = &setup.user;
+    let (user_token_0, user_token_1) = (setup.user_token_0, setup.user_token_1);
+
+    // Nothing can be closed while liquidity is in place
+    let result = test.close_position(user, &position_keys).await;
+    assert_clmm_error(result, ClmmError::PositionNotEmpty);
+    let result = test
+        .close_tick_array(&keys, TICK_LOWER, user.pubkey())
+        .await;
+    assert_clmm_error(result, ClmmError::TickArrayNotEmpty);
+
+    test.decrease_liquidity(
+        user,
+        &keys,
+        &position_keys,
+        LIQUIDITY,
+        user_token_0,
+        user_token_1,
+        0,
+        0,
+    )
+    .await
+    .unwrap();
+
+    let lamports_before = test.lamports(user.pubkey()).await;
+    test.close_position(user, &position_keys).await.unwrap();
+    assert!(!test.account_exists(position_keys.position).await);
+    assert!(!test.account_exists(position_keys.nft_account).await);
+    assert!(test.lamports(user.pubkey()).await > lamports_before);
+
+    // Only the account that paid for the array gets the rent back
+    let result = test
+        .close_tick_array(&keys, TICK_LOWER, Keypair::new().pubkey())
+        .await;
+    assert_clmm_error(result, ClmmError::Unauthorized);
+
+    let lamports_before = test.lamports(user.pubkey()).await;
+    let tick_array_lamports = test.lamports(keys.tick_array(TICK_LOWER)).await;
+    test.close_tick_array(&keys, TICK_LOWER, user.pubkey())
+        .await
+        .unwrap();
+    assert!(!test.account_exists(keys.tick_array(TICK_LOWER)).await);
+    assert_eq!(
+        test.lamports(user.pubkey()).await,
+        lamports_before + tick_array_lamports
+    );
+    let pool: PoolState = test.get_state(keys.pool).await;
+    assert!(
+        !pool.is_tick_array_initialized(TickArrayState::get_array_start_index(
+            TICK_LOWER,
+            TICK_SPACING as i32
+        ))
+    );
+
+    // A closed array is created again by the next position that needs it
+    open_default_position(&mut setup).await;
+    assert!(setup.test.account_exists(keys.tick_array(TICK_LOWER)).await);
+}
+
+#[tokio::test]
+async fn test_swap_through_closed_tick_array() {
+    let mut setup = setup().await;
+    let keys = setup.keys;
+    open_default_position(&mut setup).await;
+    let test = &mut setup.test;
+    let user = &setup.user;
+    let (user_token_0, user_token_1) = (setup.user_token_0, setup.user_token_1);
+
+    // Liquidity two arrays up, with an emptied array in between that anyone may close
+    test.open_position(
+        user,
+        &keys,
+        1200,
+        1300,
+        LIQUIDITY,
+        user_token_0,
+        user_token_1,
+        u64::MAX,
+        u64::MAX,
+    )
+    .await
+    .unwrap();
+    let emptied = test
+        .open_position(
+            user,
+            &keys,
+            600,
+            700,
+            LIQUIDITY,
+            user_token_0,
+            user_token_1,
+            u64::MAX,
+            u64::MAX,
+        )
+        .await
+        .unwrap();
+    test.decrease_liquidity(
+        user,
+        &keys,
+        &emptied,
+        LIQUIDITY,
+        user_token_0,
+        user_token_1,
+        0,
+        0,
+    )
+    .await
+    .unwrap();
+    test.close_position(user, &emptied).await.unwrap();
+    test.close_tick_array(&keys, 600, user.pubkey())
+        .await
+        .unwrap();
+    assert!(!test.account_exists(keys.tick_array(600)).await);
+
+    let quote = test
+        .quote_swap(&keys, false, 60_000_000, true, &[0, 600, 1200])
+        .await
+        .unwrap();
+    test.swap(
+        user,
+        &keys,
+        false,
+        user_token_1,
+        user_token_0,
+        60_000_000,
+        0,
+        true,
+        &[0, 600, 1200],
+    )
+    .await
+    .unwrap();
+
+    let pool: PoolState = test.get_state(keys.pool).await;
+    assert!((1200..1300).contains(&pool.tick_current));
+    assert_eq!(pool.tick_current, quote.tick_after);
+    assert_eq!(pool.liquidity, LIQUIDITY);
+    assert!(!test.account_exists(keys.tick_array(600)).await);
+
+    // A missing account only stands in for the array expected at its position
+    let result = test
+        .swap(
+            user,
+            &keys,
+            true,
+            user_token_0,
+            user_token_1,
+            1_000,
+            0,
+            true,
+            &[600],
+        )
+        .await;
+    assert_clmm_error(result, ClmmError::InvalidTickArray);
+}
+
+#[tokio::test]
+async fn test_position_bundle() {
+    let mut setup = setup().await;
+    let keys = setup.keys;
+    let test = &mut setup.test;
+    let user = &setup.user;
+    let (user_token_0, user_token_1) = (setup.user_token_0, setup.user_token_1);
+
+    let bundle = test.initialize_position_bundle(user).await.unwrap();
+    let ranges = [(-100, 100), (-600, -100), (100, 700)];
+    let mut positions = Vec::new();
+    for (index, (tick_lower, tick_upper)) in ranges.into_iter().enumerate() {
+        let position = test
+            .open_bundled_position(
+                user,
+                &keys,
+                &bundle,
+                index as u16,
+                tick_lower,
+                tick_upper,
+                LIQUIDITY,
+                user_token_0,
+                user_token_1,
+            )
+            .await
+            .unwrap();
+        positions.push(position);
+    }
+    let state: PositionBundleState = test.get_state(bundle.bundle).await;
+    assert_eq!(state.position_bitmap[0], 0b111);
+
+    // A slot holds one position at a time
+    let result = test
+        .open_bundled_position(
+            user,
+            &keys,
+            &bundle,
+            0,
+            -100,
+            100,
+            LIQUIDITY,
+            user_token_0,
+            user_token_1,
+        )
+        .await
+        .map(|_| ());
+    assert_clmm_error(result, ClmmError::BundleSlotInUse);
+
+    // The bundle NFT authorizes the regular liquidity flows
+    test.increase_liquidity(
+        user,
+        &keys,
+        &positions[1],
+        LIQUIDITY,
+        user_token_0,
+        user_token_1,
+        u64::MAX,
+        u64::MAX,
+    )
+    .await
+    .unwrap();
+    let position: PositionState = test.get_state(positions[1].position).await;
+    assert_eq!(position.liquidity, 2 * LIQUIDITY);
+    assert_eq!(position.nft_mint, bundle.bundle_mint);
+
+    // Fees earned in a slot are collected through the bundle NFT as well
+    test.swap(
+        user,
+        &keys,
+        true,
+        user_token_0,
+        user_token_1,
+        100_000,
+        0,
+        true,
+        &[0, -600],
+    )
+    .await
+    .unwrap();
+    let balance_before = test.token_balance(user_token_0).await;
+    test.collect_fees(user, &keys, &positions[0], user_token_0, user_token_1)
+        .await
+        .unwrap();
+    assert!(test.token_balance(user_token_0).await > balance_before);
+
+    // Without the bundle NFT the same flows refuse the bundled position
+    let stranger = Keypair::new();
+    let stranger_position = PositionKeys {
+        nft_account: test
+            .create_token_account(&bundle.bundle_mint, &stranger.pubkey())
+            .await,
+        ..positions[0]
+    };
+    let result = test
+        .collect_fees(
+            &stranger,
+            &keys,
+            &stranger_position,
+            user_token_0,
+            user_token_1,
+        )
+        .await;
+    assert_clmm_error(result, ClmmError::Unauthorized);
+    let result = test
+        .decrease_liquidity(
+            &stranger,
+            &keys,
+            &stranger_position,
+            1,
+            user_token_0,
+            user_token_1,
+            0,
+            0,
+        )
+        .await;
+    assert_clmm_error(result, ClmmError::Unauthorized);
+
+    // A bundled position cannot be closed as a standalone one, which would burn the bundle NFT
+    let result = test.close_position(user, &positions[0]).await;
+    assert_clmm_error(result, ClmmError::InvalidPda);
+
+    let result = test.close_position_bundle(user, &bundle).await;
+    assert_clmm_error(result, ClmmError::PositionBundleNotEmpty);
+
+    for (index, position) in positions.iter().enumerate() {
+        let state: PositionState = test.get_state(position.position).await;
+        test.decrease_liquidity(
+            user,
+            &keys,
+            position,
+            state.liquidity,
+            user_token_0,
+            user_token_1,
+            0,
+            0,
+        )
+        .await
+        .unwrap();
+        test.close_bundled_position(user, &bundle, index as u16)
+            .await
+            .unwrap();
+        assert!(!test.account_exists(position.position).await);
+    }
+
+    test.close_position_bundle(user, &bundle).await.unwrap();
+    assert!(!test.account_exists(bundle.bundle).await);
+    assert!(!test.account_exists(bundle.bundle_token_account).await);
+}
+
+#[tokio::test]
+async fn test_compound_fees() {
+    let mut setup = setup().await;
+    let keys = setup.keys;
+    let position_keys = open_default_position(&mut setup).await;
+    let test = &mut setup.test;
+    let user = &setup.user;
+    let (user_token_0, user_token_1) = (setup.user_token_0, setup.user_token_1);
+
+    // Trade both ways so the position earns fees in both tokens
+    test.swap(
+        user,
+        &keys,
+        true,
+        user_token_0,
+        user_token_1,
+        1_000_000,
+        0,
+        true,
+        &[0, -600],
+    )
+    .await
+    .unwrap();
+    let pool: PoolState = test.get_state(keys.pool).await;
+    test.swap(
+        user,
+        &keys,
+        false,
+        user_token_1,
+        user_token_0,
+        1_000_000,
+        0,
+        true,
+        &[pool.tick_current, 0],
+    )
+    .await
+    .unwrap();
+
+    let vault_0_before = test.token_balance(keys.token_vault_0).await;
+    let vault_1_before = test.token_balance(keys.token_vault_1).await;
+    let user_0_before = test.token_balance(user_token_0).await;
+    test.compound_fees(user, &keys, &position_keys)
+        .await
+        .unwrap();
+
+    let position: PositionState = test.get_state(position_keys.position).await;
+    assert!(position.liquidity > LIQUIDITY);
+    // One side is fully used, only dust of the other stays owed
+    assert!(position.token_fees_owed_0 <= 1 || position.token_fees_owed_1 <= 1);
+    assert!(position.token_fees_owed_0 < 3_000 && position.token_fees_owed_1 < 3_000);
+
+    // Nothing leaves or enters the vaults
+    assert_eq!(test.token_balance(keys.token_vault_0).await, vault_0_before);
+    assert_eq!(test.token_balance(keys.token_vault_1).await, vault_1_before);
+    assert_eq!(test.token_balance(user_token_0).await, user_0_before);
+
+    // Compounding again with nothing left to add is a no-op
+    test.compound_fees(user, &keys, &position_keys)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_reposition() {
+    let mut setup = setup().await;
+    let keys = setup.keys;
+    let position_keys = open_default_position(&mut setup).await;
+    let test = &mut setup.test;
+    let user = &setup.user;
+    let (user_token_0, user_token_1) = (setup.user_token_0, setup.user_token_1);
+
+    test.swap(
+        user,
+        &keys,
+        true,
+        user_token_0,
+        user_token_1,
+        1_000_000,
+        0,
+        true,
+        &[0, -600],
+    )
+    .await
+    .unwrap();
+
+    // Mostly above the price, so most of token 1 cannot be redeposited
+    let (tick_lower, tick_upper) = (-200, 1_000);
+    let result = test
+        .reposition(
+            user,
+            &keys,
+            &position_keys,
+            tick_lower,
+            tick_upper,
+            0,
+            user_token_0,
+            user_token_1,
+            u64::MAX,
+            0,
+        )
+        .await;
+    assert_clmm_error(result.map(|_| ()), ClmmError::SlippageExceeded);
+    let result = test
+        .reposition(
+            user,
+            &keys,
+            &position_keys,
+            tick_lower,
+            tick_upper,
+            u128::MAX,
+            user_token_0,
+            user_token_1,
+            u64::MAX,
+            u64::MAX,
+        )
+        .await;
+    assert_clmm_error(result.map(|_| ()), ClmmError::SlippageExceeded);
+
+    let vault_0_before = test.token_balance(keys.token_vault_0).await;
+    let vault_1_before = test.token_balance(keys.token_vault_1).await;
+    let user_0_before = test.token_balance(user_token_0).await;
+    let user_1_before = test.token_balance(user_token_1).await;
+    let moved = test
+        .reposition(
+            user,
+            &keys,
+            &position_keys,
+            tick_lower,
+            tick_upper,
+            1,
+            user_token_0,
+            user_token_1,
+            u64::MAX,
+            u64::MAX,
+        )
+        .await
+        .unwrap();
+
+    let position: PositionState = test.get_state(moved.position).await;
+    assert_eq!(position.tick_lower_index, tick_lower);
+    assert_eq!(position.tick_upper_index, tick_upper);
+    assert!(position.liquidity > 0);
+    assert_eq!(position.token_fees_owed_0, 0);
+    assert_eq!(position.token_fees_owed_1, 0);
+
+    // Only the leftovers leave the vaults
+    let paid_0 = test.token_balance(user_token_0).await - user_0_before;
+    let paid_1 = test.token_balance(user_token_1).await - user_1_before;
+    assert!(paid_1 > 0);
+    assert_eq!(
+        vault_0_before - test.token_balance(keys.token_vault_0).await,
+        paid_0
+    );
+    assert_eq!(
+        vault_1_before - test.token_balance(keys.token_vault_1).await,
+        paid_1
+    );
+
+    // The old ticks are released, the new upper tick lives in a freshly created array
+    let old_upper: TickArrayState = test.get_state(keys.tick_array(TICK_UPPER)).await;
+    assert_eq!(
+        old_upper.ticks[TickArrayState::get_tick_offset_in_array(TICK_UPPER, 10)].liquidity_gross,
+        0
+    );
+    let new_upper: TickArrayState = test.get_state(keys.tick_array(tick_upper)).await;
+    assert_eq!(new_upper.payer, user.pubkey());
+    assert_eq!(
+        new_upper.ticks[TickArrayState::get_tick_offset_in_array(tick_upper, 10)].liquidity_gross,
+        position.liquidity
+    );
+
+    // The moved position can be withdrawn in full
+    test.decrease_liquidity(
+        user,
+        &keys,
+        &moved,
+        position.liquidity,
+        user_token_0,
+        user_token_1,
+        0,
+        0,
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_fund_fees() {
+    let mut setup = setup().await;
+    let admin = setup.test.payer();
+    let fund_owner = Keypair::new();
+    let test = &mut setup.test;
+    let user = &setup.user;
+    let (user_token_0, user_token_1) = (setup.user_token_0, setup.user_token_1);
+
+    // Protocol and fund shares together cannot exceed the whole fee
+    let result = test
+        .initialize_config(
+            &admin,
+            1,
+            TRADE_FEE_RATE,
+            600_000,
+            500_000,
+            fund_owner.pubkey(),
+            0,
+            60,
+        )
+        .await
+        .map(|_| ());
+    assert_clmm_error(result, ClmmError::InvalidFeeRate);
+
+    let amm_config = test
+        .initialize_config(
+            &admin,
+            1,
+            TRADE_FEE_RATE,
+            PROTOCOL_FEE_RATE,
+            40_000,
+            fund_owner.pubkey(),
+            0,
+            60,
+        )
+        .await
+        .unwrap();
+    let keys = PoolKeys::new(
+        amm_config,
+        setup.keys.token_mint_0,
+        setup.keys.token_mint_1,
+        60,
+    );
+    test.create_pool(user, &keys, 1 << 64).await.unwrap();
+    test.open_position(
+        user,
+        &keys,
+        -120,
+        120,
+        LIQUIDITY,
+        user_token_0,
+        user_token_1,
+        u64::MAX,
+        u64::MAX,
+    )
+    .await
+    .unwrap();
+
+    test.swap(
+        user,
+        &keys,
+        true,
+        user_token_0,
+        user_token_1,
+        1_000_000,
+        0,
+        true,
+        &[0, -120],
+    )
+    .await
+    .unwrap();
+
+    // 12% and 4% of the 3000 fee, LPs keep the remaining 2520
+    let pool: PoolState = test.get_state(keys.pool).await;
+    assert_eq!(pool.protocol_fees_token_0, 360);
+    assert_eq!(pool.fund_fees_token_0, 120);
+    assert_eq!(pool.fund_fees_token_1, 0);
+    assert_eq!(pool.fee_growth_global_0_x64, (2_520u128 << 64) / LIQUIDITY);
+
+    let fund_token_0 = test
+        .create_funded_token_account(&keys.token_mint_0, &fund_owner.pubkey(), 0)
+        .await;
+    let fund_token_1 = test
+        .create_funded_token_account(&keys.token_mint_1, &fund_owner.pubkey(), 0)
+        .await;
+
+    // Only the fund owner collects
+    let result = test
+        .collect_fund_fees(user, &keys, user_token_0, user_token_1, u64::MAX, u64::MAX)
+        .await;
+    assert_clmm_error(result, ClmmError::Unauthorized);
+
+    test.collect_fund_fees(
+        &fund_owner,
+        &keys,
+        fund_token_0,
+        fund_token_1,
+        100,
+        u64::MAX,
+    )
+    .await
+    .unwrap();
+    assert_eq!(test.token_balance(fund_token_0).await, 100);
+    test.collect_fund_fees(
+        &fund_owner,
+        &keys,
+        fund_token_0,
+        fund_token_1,
+        u64::MAX,
+        u64::MAX,
+    )
+    .await
+    .unwrap();
+    assert_eq!(test.token_balance(fund_token_0).await, 120);
+    assert_eq!(test.token_balance(fund_token_1).await, 0);
+
+    let pool: PoolState = test.get_state(keys.pool).await;
+    assert_eq!(pool.fund_fees_token_0, 0);
+    assert_eq!(pool.protocol_fees_token_0, 360);
+}
+
+#[tokio::test]
+async fn test_referral_fees() {
+    let mut setup = setup().await;
+    let admin = setup.test.payer();
+    let test = &mut setup.test;
+    let user = &setup.user;
+    let (user_token_0, user_token_1) = (setup.user_token_0, setup.user_token_1);
+
+    let amm_config = test
+        .initialize_config(
+            &admin,
+            1,
+            TRADE_FEE_RATE,
+            PROTOCOL_FEE_RATE,
+            0,
+            admin.pubkey(),
+            100_000,
+            60,
+        )
+        .await
+        .unwrap();
+    let keys = PoolKeys::new(
+        amm_config,
+        setup.keys.token_mint_0,
+        setup.keys.token_mint_1,
+        60,
+    );
+    test.create_pool(user, &keys, 1 << 64).await.unwrap();
+    test.open_position(
+        user,
+        &keys,
+        -120,
+        120,
+        LIQUIDITY,
+        user_token_0,
+        user_token_1,
+        u64::MAX,
+        u64::MAX,
+    )
+    .await
+    .unwrap();
+
+    let referrer = Keypair::new();
+    let referral_token_0 = test
+        .create_funded_token_account(&keys.token_mint_0, &referrer.pubkey(), 0)
+        .await;
+    let referral_token_1 = test
+        .create_funded_token_account(&keys.token_mint_1, &referrer.pubkey(), 0)
+        .await;
+
+    // The referral account must hold the input token
+    let result = test
+        .swap_with_referral(
+            user,
+            &keys,
+            true,
+            user_token_0,
+            user_token_1,
+            1_000_000,
+            0,
+            true,
+            &[0, -120],
+            Some((referral_token_1, 100_000)),
+        )
+        .await;
+    assert_clmm_error(result, ClmmError::InvalidAccountData);
+
+    // Swaps pick their referral share, up to the config's cap
+    let result = test
+        .swap_with_referral(
+            user,
+            &keys,
+            true,
+            user_token_0,
+            user_token_1,
+            1_000_000,
+            0,
+            true,
+            &[0, -120],
+            Some((referral_token_0, 100_001)),
+        )
+        .await;
+    assert_clmm_error(result, ClmmError::InvalidFeeRate);
+
+    let user_0_before = test.token_balance(user_token_0).await;
+    let vault_0_before = test.token_balance(keys.token_vault_0).await;
+    test.swap_with_referral(
+        user,
+        &keys,
+        true,
+        user_token_0,
+        user_token_1,
+        1_000_000,
+        0,
+        true,
+        &[0, -120],
+        Some((referral_token_0, 100_000)),
+    )
+    .await
+    .unwrap();
+
+    // 10% of the 3000 fee goes to the referrer, 12% to the protocol, LPs keep 2340
+    assert_eq!(
+        user_0_before - test.token_balance(user_token_0).await,
+        1_000_000
+    );
+    assert_eq!(test.token_balance(referral_token_0).await, 300);
+    assert_eq!(
+        test.token_balance(keys.token_vault_0).await - vault_0_before,
+        999_700
+    );
+    let pool: PoolState = test.get_state(keys.pool).await;
+    assert_eq!(pool.protocol_fees_token_0, 360);
+    assert_eq!(pool.fee_growth_global_0_x64, (2_340u128 << 64) / LIQUIDITY);
+
+    // Without a referrer LPs keep that share
+    let pool: PoolState = test.get_state(keys.pool).await;
+    test.swap(
+        user,
+        &keys,
+        false,
+        user_token_1,
+        user_token_0,
+        1_000_000,
+        0,
+        true,
+        &[pool.tick_current, 0],
+    )
+    .await
+    .unwrap();
+    let pool: PoolState = test.get_state(keys.pool).await;
+    assert_eq!(pool.protocol_fees_token_1, 360);
+    assert_eq!(pool.fee_growth_global_1_x64, (2_640u128 << 64) / LIQUIDITY);
+    assert_eq!(test.token_balance(referral_token_1).await, 0);
+
+    // Only the config admin moves the cap, which must leave LPs a share
+    let result = test.set_max_referral_fee_rate(user, amm_config, 0).await;
+    assert_clmm_error(result, ClmmError::Unauthorized);
+    let result = test
+        .set_max_referral_fee_rate(&admin, amm_config, 1_000_000 - PROTOCOL_FEE_RATE + 1)
+        .await;
+    assert_clmm_error(result, ClmmError::InvalidFeeRate);
+    test.set_max_referral_fee_rate(&admin, amm_config, 50_000)
+        .await
+        .unwrap();
+    let config: AmmConfig = test.get_state(amm_config).await;
+    assert_eq!(config.max_referral_fee_rate, 50_000);
+
+    let pool: PoolState = test.get_state(keys.pool).await;
+    let result = test
+        .swap_with_referral(
+            user,
+            &keys,
+            true,
+            user_token_0,
+            user_token_1,
+            1_000_000,
+            0,
+            true,
+            &[pool.tick_current, pool.tick_current - 3600],
+            Some((referral_token_0, 100_000)),
+        )
+        .await;
+    assert_clmm_error(result, ClmmError::InvalidFeeRate);
+    test.swap_with_referral(
+        user,
+        &keys,
+        true,
+        user_token_0,
+        user_token_1,
+        1_000_000,
+        0,
+        true,
+        &[pool.tick_current, pool.tick_current - 3600],
+        Some((referral_token_0, 50_000)),
+    )
+    .await
+    .unwrap();
+    assert_eq!(test.token_balance(referral_token_0).await, 300 + 150);
+}
+
+#[tokio::test]
+async fn test_permissioned_pool() {
+    let mut setup = setup().await;
+    let test = &mut setup.test;
+    let user = &setup.user;
+    let authority = Keypair::new();
+    test.airdrop(&authority.pubkey(), 1_000_000_000).await;
+
+    let mint_a = test.create_mint(6).await;
+    let mint_b = test.create_mint(6).await;
+    let keys = PoolKeys::new(setup.keys.amm_config, mint_a, mint_b, TICK_SPACING);
+    test.create_pool_with_options(
+        user,
+        &keys,
+        1 << 64,
+        PoolOptions {
+            permission_authority: Some(authority.pubkey()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let user_token_0 = test
+        .create_funded_token_account(&keys.token_mint_0, &user.pubkey(), INITIAL_BALANCE)
+        .await;
+    let user_token_1 = test
+        .create_funded_token_account(&keys.token_mint_1, &user.pubkey(), INITIAL_BALANCE)
+        .await;
+
+    async fn open(
+        test: &mut ClmmTest,
+        user: &Keypair,
+        keys: &PoolKeys,
+        token_account_0: solana_pubkey::Pubkey,
+        token_account_1: solana_pubkey::Pubkey,
+    ) -> Result<PositionKeys, BanksClientError> {
+        test.open_position(
+            user,
+            keys,
+            TICK_LOWER,
+            TICK_UPPER,
+            LIQUIDITY,
+            token_account_0,
+            token_account_1,
+            u64::MAX,
+            u64::MAX,
+        )
+        .await
+    }
+    let result = open(test, user, &keys, user_token_0, user_token_1)
+        .await
+        .map(|_| ());
+    assert_clmm_error(result, ClmmError::NotAllowlisted);
+
+    // Only the permission authority manages the allowlist, and only on permissioned pools
+    let result = test
+        .add_to_allowlist(user, &keys, user.pubkey(), AllowlistEntry::PERMISSION_ALL)
+        .await;
+    assert_clmm_error(result, ClmmError::Unauthorized);
+    let result = test
+        .add_to_allowlist(
+            &authority,
+            &setup.keys,
+            user.pubkey(),
+            AllowlistEntry::PERMISSION_ALL,
+        )
+        .await;
+    assert_clmm_error(result, ClmmError::PoolNotPermissioned);
+    let result = test
+        .add_to_allowlist(&authority, &keys, user.pubkey(), 0)
+        .await;
+    assert_clmm_error(result, ClmmError::InvalidPermissions);
+
+    // Swap permission alone does not allow providing liquidity
+    test.add_to_allowlist(
+        &authority,
+        &keys,
+        user.pubkey(),
+        AllowlistEntry::PERMISSION_SWAP,
+    )
+    .await
+    .unwrap();
+    let result = open(test, user, &keys, user_token_0, user_token_1)
+        .await
+        .map(|_| ());
+    assert_clmm_error(result, ClmmError::NotAllowlisted);
+
+    test.add_to_allowlist(
+        &authority,
+        &keys,
+        user.pubkey(),
+        AllowlistEntry::PERMISSION_ALL,
+    )
+    .await
+    .unwrap();
+    let position_keys = open(test, user, &keys, user_token_0, user_token_1)
+        .await
+        .unwrap();
+    test.swap(
+        user,
+        &keys,
+        true,
+        user_token_0,
+        user_token_1,
+        1_000_000,
+        0,
+        true,
+        &[0, -600],
+    )
+    .await
+    .unwrap();
+
+    // Once removed, the wallet can no longer trade or add liquidity
+    let authority_lamports = test.lamports(authority.pubkey()).await;
+    test.remove_from_allowlist(&authority, &keys, user.pubkey())
+        .await
+        .unwrap();
+    assert!(test.lamports(authority.pubkey()).await > authority_lamports);
+    let pool: PoolState = test.get_state(keys.pool).await;
+    let result = test
+        .swap(
+            user,
+            &keys,
+            false,
+            user_token_1,
+            user_token_0,
+            1_000_000,
+            0,
+            true,
+            &[pool.tick_current, 0],
+        )
+        .await;
+    assert_clmm_error(result, ClmmError::NotAllowlisted);
+    let result = test
+        .increase_liquidity(
+            user,
+            &keys,
+            &position_keys,
+            LIQUIDITY,
+            user_token_0,
+            user_token_1,
+            u64::MAX,
+            u64::MAX,
+        )
+        .await;
+    assert_clmm_error(result, ClmmError::NotAllowlisted);
+    let result = test.compound_fees(user, &keys, &position_keys).await;
+    assert_clmm_error(result, ClmmError::NotAllowlisted);
+    let result = test
+        .reposition(
+            user,
+            &keys,
+            &position_keys,
+            TICK_LOWER - 100,
+            TICK_UPPER + 100,
+            0,
+            user_token_0,
+            user_token_1,
+            u64::MAX,
+            u64::MAX,
+        )
+        .await
+        .map(|_| ());
+    assert_clmm_error(result, ClmmError::NotAllowlisted);
+
+    // Funds can always leave
+    test.collect_fees(user, &keys, &position_keys, user_token_0, user_token_1)
+        .await
+        .unwrap();
+    test.decrease_liquidity(
+        user,
+        &keys,
+        &position_keys,
+        LIQUIDITY,
+        user_token_0,
+        user_token_1,
+        0,
+        0,
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_open_time_and_launch_window() {
+    let mut setup = setup().await;
+    let test = &mut setup.test;
+    let user = &setup.user;
+
+    let mint_a = test.create_mint(6).await;
+    let mint_b = test.create_mint(6).await;
+    let keys = PoolKeys::new(setup.keys.amm_config, mint_a, mint_b, TICK_SPACING);
+    let open_time = test.unix_timestamp().await as u64 + 100;
+    test.create_pool_with_options(
+        user,
+        &keys,
+        1 << 64,
+        PoolOptions {
+            open_time,
+            launch_duration: 60,
+            launch_max_amount_0: 100_000,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let user_token_0 = test
+        .create_funded_token_account(&keys.token_mint_0, &user.pubkey(), INITIAL_BALANCE)
+        .await;
+    let user_token_1 = test
+        .create_funded_token_account(&keys.token_mint_1, &user.pubkey(), INITIAL_BALANCE)
+        .await;
+
+    // Liquidity can be seeded before the pool opens
+    test.open_position(
+        user,
+        &keys,
+        TICK_LOWER,
+        TICK_UPPER,
+        LIQUIDITY,
+        user_token_0,
+        user_token_1,
+        u64::MAX,
+        u64::MAX,
+    )
+    .await
+    .unwrap();
+    let result = test
+        .swap(
+            user,
+            &keys,
+            true,
+            user_token_0,
+            user_token_1,
+            100_000,
+            0,
+            true,
+            &[0, -600],
+        )
+        .await;
+    assert_clmm_error(result, ClmmError::PoolNotOpen);
+
+    // During the launch window swaps are capped
+    test.set_unix_timestamp(open_time as i64).await;
+    let result = test
+        .swap(
+            user,
+            &keys,
+            true,
+            user_token_0,
+            user_token_1,
+            100_001,
+            0,
+            true,
+            &[0, -600],
+        )
+        .await;
+    assert_clmm_error(result, ClmmError::LaunchSwapTooLarge);
+    test.swap(
+        user,
+        &keys,
+        true,
+        user_token_0,
+        user_token_1,
+        100_000,
+        0,
+        true,
+        &[0, -600],
+    )
+    .await
+    .unwrap();
+
+    test.set_unix_timestamp(open_time as i64 + 60).await;
+    let pool: PoolState = test.get_state(keys.pool).await;
+    test.swap(
+        user,
+        &keys,
+        true,
+        user_token_0,
+        user_token_1,
+        1_000_000,
+        0,
+        true,
+        &[pool.tick_current],
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_pool_fee_rate_override() {
+    let mut setup = setup().await;
+    open_default_position(&mut setup).await;
+    let admin = setup.test.payer();
+    let test = &mut setup.test;
+    let user = &setup.user;
+    let keys = &setup.keys;
+    let (user_token_0, user_token_1) = (setup.user_token_0, setup.user_token_1);
+
+    // Only the config admin sets the override, and within the fee bound
+    let result = test.set_pool_fee_rate(user, keys, Some(1_000)).await;
+    assert_clmm_error(result, ClmmError::Unauthorized);
+    let result = test.set_pool_fee_rate(&admin, keys, Some(1_000_000)).await;
+    assert_clmm_error(result, ClmmError::InvalidFeeRate);
+
+    test.set_pool_fee_rate(&admin, keys, Some(1_000))
+        .await
+        .unwrap();
+    test.swap(
+        user,
+        keys,
+        true,
+        user_token_0,
+        user_token_1,
+        1_000_000,
+        0,
+        true,
+        &[0, -600],
+    )
+    .await
+    .unwrap();
+
+    // 1000 charged instead of 3000, with the config's 12% protocol share
+    let pool: PoolState = test.get_state(keys.pool).await;
+    assert_eq!(pool.trade_fee_rate_override, 1_000);
+    assert_eq!(pool.protocol_fees_token_0, 120);
+    assert_eq!(pool.fee_growth_global_0_x64, (880u128 << 64) / LIQUIDITY);
+
+    // Clearing falls back to the config rate
+    test.set_pool_fee_rate(&admin, keys, None).await.unwrap();
+    test.swap(
+        user,
+        keys,
+        true,
+        user_token_0,
+        user_token_1,
+        1_000_000,
+        0,
+        true,
+        &[pool.tick_current],
+    )
+    .await
+    .unwrap();
+    let pool: PoolState = test.get_state(keys.pool).await;
+    assert_eq!(pool.status & PoolState::STATUS_FEE_OVERRIDE, 0);
+    assert_eq!(pool.protocol_fees_token_0, 120 + 360);
+}
+
+#[tokio::test]
+async fn test_deadline_and_price_band() {
+    let mut setup = setup().await;
+    let position = open_default_position(&mut setup).await;
+    let test = &mut setup.test;
+    let user = &setup.user;
+    let keys = &setup.keys;
+    let (user_token_0, user_token_1) = (setup.user_token_0, setup.user_token_1);
+    let bundle = test.initialize_position_bundle(user).await.unwrap();
+
+    // Expired by one second, every guarded instruction refuses
+    let now = test.unix_timestamp().await;
+    test.deadline = Some(Deadline::UnixTimestamp(now - 1));
+    let result = test
+        .swap(
+            user,
+            keys,
+            true,
+            user_token_0,
+            user_token_1,
+            1_000,
+            0,
+            true,
+            &[0, -600],
+        )
+        .await;
+    assert_clmm_error(result, ClmmError::DeadlineExceeded);
+    let result = test
+        .open_position(
+            user,
+            keys,
+            TICK_LOWER,
+            TICK_UPPER,
+            LIQUIDITY,
+            user_token_0,
+            user_token_1,
+            u64::MAX,
+            u64::MAX,
+        )
+        .await
+        .map(|_| ());
+    assert_clmm_error(result, ClmmError::DeadlineExceeded);
+    let result = test
+        .increase_liquidity(
+            user,
+            keys,
+            &position,
+            LIQUIDITY,
+            user_token_0,
+            user_token_1,
+            u64::MAX,
+            u64::MAX,
+        )
+        .await;
+    assert_clmm_error(result, ClmmError::DeadlineExceeded);
+    let result = test
+        .decrease_liquidity(user, keys, &position, 1, user_token_0, user_token_1, 0, 0)
+        .await;
+    assert_clmm_error(result, ClmmError::DeadlineExceeded);
+    let result = test
+        .open_bundled_position(
+            user,
+            keys,
+            &bundle,
+            0,
+            TICK_LOWER,
+            TICK_UPPER,
+            LIQUIDITY,
+            user_token_0,
+            user_token_1,
+        )
+        .await
+        .map(|_| ());
+    assert_clmm_error(result, ClmmError::DeadlineExceeded);
+    let result = test.compound_fees(user, keys, &position).await;
+    assert_clmm_error(result, ClmmError::DeadlineExceeded);
+    let result = test
+        .reposition(
+            user,
+            keys,
+            &position,
+            TICK_LOWER,
+            TICK_UPPER,
+            0,
+            user_token_0,
+            user_token_1,
+            u64::MAX,
+            u64::MAX,
+        )
+        .await
+        .map(|_| ());
+    assert_clmm_error(result, ClmmError::DeadlineExceeded);
+
+    test.context.warp_to_slot(50).unwrap();
+    test.deadline = Some(Deadline::Slot(49));
+    let result = test
+        .decrease_liquidity(user, keys, &position, 1, user_token_0, user_token_1, 0, 0)
+        .await;
+    assert_clmm_error(result, ClmmError::DeadlineExceeded);
+
+    // The deadline is inclusive
+    let now = test.unix_timestamp().await;
+    test.deadline = Some(Deadline::UnixTimestamp(now));
+    test.swap(
+        user,
+        keys,
+        true,
+        user_token_0,
+        user_token_1,
+        1_000,
+        0,
+        true,
+        &[0, -600],
+    )
+    .await
+    .unwrap();
+
+    // A band the pool price has left rejects liquidity ops
+    let pool: PoolState = test.get_state(keys.pool).await;
+    test.sqrt_price_band = Some(SqrtPriceBand {
+        min_sqrt_price_x64: pool.sqrt_price_x64 + 1,
+        max_sqrt_price_x64: u128::MAX,
+    });
+    let result = test
+        .increase_liquidity(
+            user,
+            keys,
+            &position,
+            LIQUIDITY,
+            user_token_0,
+            user_token_1,
+            u64::MAX,
+            u64::MAX,
+        )
+        .await;
+    assert_clmm_error(result, ClmmError::PriceOutOfBand);
+    let result = test
+        .open_position(
+            user,
+            keys,
+            TICK_LOWER,
+            TICK_UPPER,
+            LIQUIDITY,
+            user_token_0,
+            user_token_1,
+            u64::MAX,
+            u64::MAX,
+        )
+        .await
+        .map(|_| ());
+    assert_clmm_error(result, ClmmError::PriceOutOfBand);
+    let result = test
+        .decrease_liquidity(user, keys, &position, 1, user_token_0, user_token_1, 0, 0)
+        .await;
+    assert_clmm_error(result, ClmmError::PriceOutOfBand);
+    let result = test
+        .open_bundled_position(
+            user,
+            keys,
+            &bundle,
+            0,
+            TICK_LOWER,
+            TICK_UPPER,
+            LIQUIDITY,
+            user_token_0,
+            user_token_1,
+        )
+        .await
+        .map(|_| ());
+    assert_clmm_error(result, ClmmError::PriceOutOfBand);
+    let result = test.compound_fees(user, keys, &position).await;
+    assert_clmm_error(result, ClmmError::PriceOutOfBand);
+    let result = test
+        .reposition(
+            user,
+            keys,
+            &position,
+            TICK_LOWER,
+            TICK_UPPER,
+            0,
+            user_token_0,
+            user_token_1,
+            u64::MAX,
+            u64::MAX,
+        )
+        .await
+        .map(|_| ());
+    assert_clmm_error(result, ClmmError::PriceOutOfBand);
+
+    test.sqrt_price_band = Some(SqrtPriceBand {
+        min_sqrt_price_x64: pool.sqrt_price_x64,
+        max_sqrt_price_x64: pool.sqrt_price_x64,
+    });
+    test.increase_liquidity(
+        user,
+        keys,
+        &position,
+        LIQUIDITY,
+        user_token_0,
+        user_token_1,
+        u64::MAX,
+        u64::MAX,
+    )
+    .await
+    .unwrap();
+    let position_state: PositionState = test.get_state(position.position).await;
+    assert_eq!(position_state.liquidity, 2 * LIQUIDITY);
+}
+
+#[tokio::test]
+async fn test_initialize_tick_array() {
+    let mut setup = setup().await;
+    let admin = setup.test.payer();
+    let test = &mut setup.test;
+    let user = &setup.user;
+    let keys = &setup.keys;
+
+    // Start indexes must be aligned to a whole array
+    let result = test.initialize_tick_array(user, keys, 10).await;
+    assert_clmm_error(result, ClmmError::InvalidTickArray);
+
+    let start = -1_200;
+    test.initialize_tick_array(user, keys, start).await.unwrap();
+    let tick_array: TickArrayState = test.get_state(keys.tick_array(start)).await;
+    assert_eq!(tick_array.pool_id, keys.pool);
+    assert_eq!(tick_array.start_tick_index, start);
+    assert_eq!(tick_array.payer, user.pubkey());
+    assert_eq!(tick_array.ticks[1].tick, start + TICK_SPACING as i32);
+    let pool: PoolState = test.get_state(keys.pool).await;
+    assert!(!pool.is_tick_array_initialized(start));
+
+    // Creating it again leaves the first payer in place
+    test.initialize_tick_array(&admin, keys, start)
+        .await
+        .unwrap();
+    let tick_array: TickArrayState = test.get_state(keys.tick_array(start)).await;
+    assert_eq!(tick_array.payer, user.pubkey());
+
+    // Positions use the pre-created array
+    test.open_position(
+        user,
+        keys,
+        start,
+        start + 600,
+        LIQUIDITY,
+        setup.user_token_0,
+        setup.user_token_1,
+        u64::MAX,
+        u64::MAX,
+    )
+    .await
+    .unwrap();
+    let tick_array: TickArrayState = test.get_state(keys.tick_array(start)).await;
+    assert_eq!(tick_array.initialized_tick_count, 1);
+    assert_eq!(tick_array.payer, user.pubkey());
+}
+
+#[tokio::test]
+async fn test_quote_swap() {
+    let mut setup = setup().await;
+    open_default_position(&mut setup).await;
+    let test = &mut setup.test;
+    let user = &setup.user;
+    let keys = &setup.keys;
+    let (user_token_0, user_token_1) = (setup.user_token_0, setup.user_token_1);
+
+    let result = test.quote_swap(keys, true, 0, true, &[0, -600]).await;
+    assert_clmm_error(result.map(|_| ()), ClmmError::ZeroAmount);
+
+    // Quoting leaves the pool as it was
+    let pool_before: PoolState = test.get_state(keys.pool).await;
+    let quote = test
+        .quote_swap(keys, true, 1_000_000, true, &[0, -600])
+        .await
+        .unwrap();
+    let pool: PoolState = test.get_state(keys.pool).await;
+    assert_eq!(pool.sqrt_price_x64, pool_before.sqrt_price_x64);
+    assert_eq!(pool.fee_growth_global_0_x64, 0);
+    assert_eq!(quote.amount_in, 1_000_000);
+    assert_eq!(quote.fee_amount, 3_000);
+
+    // The swap then matches the quote exactly
+    let balance_1 = test.token_balance(user_token_1).await;
+    test.swap(
+        user,
+        keys,
+        true,
+        user_token_0,
+        user_token_1,
+        1_000_000,
+        quote.amount_out,
+        true,
+        &[0, -600],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        test.token_balance(user_token_1).await - balance_1,
+        quote.amount_out
+    );
+    let pool: PoolState = test.get_state(keys.pool).await;
+    assert_eq!(pool.sqrt_price_x64, quote.sqrt_price_after_x64);
+    assert_eq!(pool.tick_current, quote.tick_after);
+
+    // Exact output quotes report the input needed
+    let quote = test
+        .quote_swap(keys, false, 500_000, false, &[pool.tick_current, 0])
+        .await
+        .unwrap();
+    assert_eq!(quote.amount_out, 500_000);
+    assert!(quote.amount_in > 500_000);
+}
+
+const CPI_CALLER_ID: Pubkey = Pubkey::new_from_array([7; 32]);
+
+/// Stands in for an integrating program: quotes an exact input swap of the
+/// amount in `data` and executes it with the quote as minimum output.
+///
+/// Accounts: the CLMM program, then the `Swap` accounts without optional ones.
+fn process_cpi_caller(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let [program, payer, amm_config, pool, input_token_account, output_token_account, input_vault, output_vault, token_program, tick_arrays @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let amount = u64::from_le_bytes(data.try_into().unwrap());
+    let zero_for_one = *input_vault.key == cpi::load_pool(pool)?.token_vault_0;
+
+    let quote = cpi::quote_swap(
+        program,
+        &cpi::QuoteSwapAccounts {
+            amm_config,
+            pool,
+            tick_arrays,
+        },
+        amount,
+        0,
+        zero_for_one,
+        true,
+    )?;
+    cpi::swap(
+        program,
+        &cpi::SwapAccounts {
+            payer,
+            amm_config,
+            pool,
+            input_token_account,
+            output_token_account,
+            input_vault,
+            output_vault,
+            token_program,
+            allowlist_entry: None,
+            referral_token_account: None,
+            tick_arrays,
+        },
+        amount,
+        quote.amount_out,
+        0,
+        true,
+        None,
+        0,
+        &[],
+    )
+}
+
+#[tokio::test]
+async fn test_cpi_swap() {
+    let test = ClmmTest::start_with(|program_test| {
+        program_test.add_program("cpi_caller", CPI_CALLER_ID, processor!(process_cpi_caller));
+    })
+    .await;
+    let mut setup = setup_on(test).await;
+    open_default_position(&mut setup).await;
+    let test = &mut setup.test;
+    let user = &setup.user;
+    let keys = &setup.keys;
+    let (user_token_0, user_token_1) = (setup.user_token_0, setup.user_token_1);
+
+    let quote = test
+        .quote_swap(keys, true, 1_000_000, true, &[0, -600])
+        .await
+        .unwrap();
+    let balance_1 = test.token_balance(user_token_1).await;
+    let instruction = Instruction {
+        program_id: CPI_CALLER_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(clmm_demo::ID, false),
+            AccountMeta::new_readonly(user.pubkey(), true),
+            AccountMeta::new_readonly(keys.amm_config, false),
+            AccountMeta::new(keys.pool, false),
+            AccountMeta::new(user_token_0, false),
+            AccountMeta::new(user_token_1, false),
+            AccountMeta::new(keys.token_vault_0, false),
+            AccountMeta::new(keys.token_vault_1, false),
+            AccountMeta::new_readonly(spl_token_interface::ID, false),
+            AccountMeta::new(keys.tick_array(0), false),
+            AccountMeta::new(keys.tick_array(-600), false),
+        ],
+        data: 1_000_000u64.to_le_bytes().to_vec(),
+    };
+    test.process(&[instruction], &[user]).await.unwrap();
+
+    assert_eq!(
+        test.token_balance(user_token_1).await - balance_1,
+        quote.amount_out
+    );
+    let pool: PoolState = test.get_state(keys.pool).await;
+    assert_eq!(pool.sqrt_price_x64, quote.sqrt_price_after_x64);
+}